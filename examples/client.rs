@@ -27,6 +27,9 @@ async fn main() -> Result<()> {
         exclude_paths: vec![],
         is_regex: false,
         symbols_only: false,
+        kinds: vec![],
+        definition_only: false,
+        fuzzy: false,
     };
 
     let mut stream = client.search(search_request).await?.into_inner();
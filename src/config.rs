@@ -4,6 +4,7 @@
 
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 
 use crate::utils::normalize_path_for_comparison;
@@ -84,6 +85,117 @@ pub struct Config {
 
     #[serde(default)]
     pub telemetry: TelemetryConfig,
+
+    #[serde(default)]
+    pub ranking: RankingConfig,
+
+    #[serde(default)]
+    pub feedback: FeedbackConfig,
+}
+
+/// Click-feedback learning-to-rank configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeedbackConfig {
+    /// Accept `POST /api/feedback` events and apply learned boosts (default: false).
+    /// Acts as the kill switch: when false, no events are recorded and no
+    /// feedback boost is applied to ranking.
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// JSON-lines file where feedback events are appended and replayed on startup.
+    /// When unset, feedback is kept in memory only.
+    #[serde(default)]
+    pub store_path: Option<String>,
+
+    /// Half-life of learned boosts in days (default: 14)
+    #[serde(default = "default_feedback_half_life_days")]
+    pub half_life_days: f32,
+}
+
+fn default_feedback_half_life_days() -> f32 {
+    14.0
+}
+
+impl Default for FeedbackConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            store_path: None,
+            half_life_days: default_feedback_half_life_days(),
+        }
+    }
+}
+
+/// Search ranking configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RankingConfig {
+    /// Ranking profile: "balanced" (default) ignores file age,
+    /// "recent" favours recently changed files
+    #[serde(default)]
+    pub profile: RankingProfile,
+
+    /// Where file age comes from: "mtime" (default) or "git" (last commit time
+    /// read from the local repository, falling back to mtime for untracked files)
+    #[serde(default)]
+    pub recency_source: RecencySource,
+
+    /// Half-life of the recency boost in days (default: 30).
+    /// A file last changed one half-life ago gets half the boost of a file changed today.
+    #[serde(default = "default_recency_half_life_days")]
+    pub recency_half_life_days: f32,
+
+    /// Explicit recency weight, overriding the profile (0 disables the signal).
+    /// The boost for a file is `1 + weight × 0.5^(age / half_life)`.
+    #[serde(default)]
+    pub recency_weight: Option<f32>,
+}
+
+/// Named ranking presets
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RankingProfile {
+    /// Structural signals only (paths, symbols, dependencies)
+    #[default]
+    Balanced,
+    /// Structural signals plus a boost for recently changed files
+    Recent,
+}
+
+/// Source of the per-file recency signal
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RecencySource {
+    /// File modification time from the filesystem
+    #[default]
+    Mtime,
+    /// Last commit time from the local git history
+    Git,
+}
+
+fn default_recency_half_life_days() -> f32 {
+    30.0
+}
+
+impl Default for RankingConfig {
+    fn default() -> Self {
+        Self {
+            profile: RankingProfile::default(),
+            recency_source: RecencySource::default(),
+            recency_half_life_days: default_recency_half_life_days(),
+            recency_weight: None,
+        }
+    }
+}
+
+impl RankingConfig {
+    /// Recency weight after applying the profile default and any explicit override
+    pub fn effective_recency_weight(&self) -> f32 {
+        let weight = self.recency_weight.unwrap_or(match self.profile {
+            RankingProfile::Balanced => 0.0,
+            RankingProfile::Recent => 1.0,
+        });
+        weight.max(0.0)
+    }
 }
 
 /// Server-related configuration
@@ -190,6 +302,32 @@ pub struct IndexerConfig {
     /// reduced search relevance for symbol-based queries.
     #[serde(default = "default_true")]
     pub enable_symbols: bool,
+
+    /// Directory of tree-sitter query files overriding the built-in symbol
+    /// rules, one `<language>.scm` per language (e.g. `rust.scm`, `go.scm`).
+    /// Languages without a file here keep the built-in queries.
+    #[serde(default)]
+    pub symbol_queries_dir: Option<String>,
+
+    /// Directories searched for C/C++ `#include` targets after the including
+    /// file's own directory. Relative entries are resolved against each
+    /// indexed path (e.g. `include` for `<root>/include`).
+    #[serde(default)]
+    pub include_dirs: Vec<String>,
+
+    /// Import prefixes mapped to the directory holding that package, so
+    /// imports resolve across indexed roots (e.g. `"@acme/shared" = "../shared"`
+    /// for a library checked out beside the repository). Relative entries are
+    /// resolved against each indexed path, like `include_dirs`.
+    #[serde(default)]
+    pub package_roots: BTreeMap<String, String>,
+
+    /// Glob patterns of entry-point files (binaries, package indexes, tests)
+    /// that `/api/dead-code` never reports, matched like search `include`
+    /// patterns. Defaults to `main.rs`, `lib.rs`, `index.ts`, `__main__.py`,
+    /// `tests/**`, `*.test.*` and similar.
+    #[serde(default = "default_entrypoints")]
+    pub entrypoints: Vec<String>,
 }
 
 fn default_address() -> String {
@@ -229,6 +367,10 @@ fn default_true() -> bool {
     true
 }
 
+fn default_entrypoints() -> Vec<String> {
+    crate::search::dead_code::default_entrypoints()
+}
+
 impl Default for ServerConfig {
     fn default() -> Self {
         Self {
@@ -256,6 +398,10 @@ impl Default for IndexerConfig {
             transcode_non_utf8: true,
             batch_size: default_batch_size(),
             enable_symbols: true,
+            symbol_queries_dir: None,
+            include_dirs: Vec::new(),
+            package_roots: BTreeMap::new(),
+            entrypoints: default_entrypoints(),
         }
     }
 }
@@ -285,10 +431,28 @@ impl IndexerConfig {
         sorted_excluded_files.sort();
 
         // Create a deterministic string representation
-        let config_str = format!(
+        let mut config_str = format!(
             "paths:{:?}|exts:{:?}|excludes:{:?}|max_size:{}|exclude_files:{:?}|transcode_non_utf8:{}",
             sorted_paths, sorted_exts, sorted_excludes, self.max_file_size, sorted_excluded_files, self.transcode_non_utf8
         );
+        // Custom symbol queries change extracted symbols, so the query files
+        // themselves are hashed; only appended when set so that existing
+        // fingerprints stay valid
+        if let Some(ref dir) = self.symbol_queries_dir {
+            config_str.push_str(&format!(
+                "|symbol_queries_dir:{}|symbol_queries:{}",
+                dir,
+                symbol_queries_digest(std::path::Path::new(dir))
+            ));
+        }
+        // Include directories change C/C++ dependency edges
+        if !self.include_dirs.is_empty() {
+            config_str.push_str(&format!("|include_dirs:{:?}", self.include_dirs));
+        }
+        // Package roots change cross-root dependency edges
+        if !self.package_roots.is_empty() {
+            config_str.push_str(&format!("|package_roots:{:?}", self.package_roots));
+        }
 
         // Generate MD5 hash
         format!("{:x}", md5::compute(config_str.as_bytes()))
@@ -316,6 +480,28 @@ impl IndexerConfig {
     }
 }
 
+/// Hash of the `.scm` files in a symbol query directory (names and contents,
+/// in name order). An unreadable directory hashes as empty.
+fn symbol_queries_digest(dir: &std::path::Path) -> String {
+    let mut files: Vec<_> = std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|e| e == "scm"))
+        .collect();
+    files.sort();
+
+    let mut context = md5::Context::new();
+    for path in files {
+        if let Some(name) = path.file_name() {
+            context.consume(name.to_string_lossy().as_bytes());
+        }
+        context.consume(std::fs::read(&path).unwrap_or_default());
+        context.consume([0]);
+    }
+    format!("{:x}", context.finalize())
+}
+
 impl Config {
     /// Load configuration from a file
     pub fn from_file(path: &Path) -> Result<Self> {
@@ -451,6 +637,51 @@ watch = false
 # Disable to reduce memory usage and indexing time at the cost of reduced relevance.
 enable_symbols = true
 
+# Directory of tree-sitter query files overriding the built-in symbol rules
+# (optional). One <language>.scm per language, e.g. rust.scm, go.scm, c_sharp.scm,
+# using @definition.<kind>/@name and @import/@path captures.
+# symbol_queries_dir = "/etc/fast_code_search/queries"
+
+# Directories searched for C/C++ #include targets (optional), absolute or
+# relative to each indexed path. The including file's directory is always tried first.
+# include_dirs = ["include", "third_party/include"]
+
+# Entry-point files never reported by /api/dead-code (optional), as glob patterns
+# matched like search include patterns. Setting this replaces the defaults
+# (main.rs, lib.rs, index.ts, __main__.py, tests/**, *.test.*, ...).
+# entrypoints = ["main.rs", "index.ts", "__main__.py", "tests/**", "scripts/**"]
+
+# Import prefixes mapped to package directories (optional), so imports resolve
+# across indexed roots: an npm package, Python package, Rust crate, Go module or
+# C/C++ include directory name. Relative paths are resolved against each indexed path.
+# [indexer.package_roots]
+# "@acme/shared" = "../shared-lib"
+# "github.com/acme/shared" = "/work/shared"
+
+[ranking]
+# Ranking profile: "balanced" (default) or "recent" (boost recently changed files)
+profile = "balanced"
+
+# Where file age comes from: "mtime" (default) or "git" (last commit time)
+# recency_source = "mtime"
+
+# Half-life of the recency boost in days (default: 30)
+# recency_half_life_days = 30.0
+
+# Explicit recency weight overriding the profile (0 disables)
+# recency_weight = 1.0
+
+[feedback]
+# Learn ranking boosts from results users open in the UI (default: false)
+# Setting this to false is the kill switch: nothing is recorded or applied.
+enabled = false
+
+# JSON-lines log of feedback events, replayed on startup (in-memory if unset)
+# store_path = "/var/lib/fast_code_search/feedback.jsonl"
+
+# Half-life of learned boosts in days (default: 14)
+# half_life_days = 14.0
+
 [telemetry]
 # Enable OpenTelemetry trace export (default: false)
 # Set to true to enable OTLP export (console logging is always active)
@@ -530,5 +761,86 @@ paths = ["/code/project"]
         assert!(template.contains("[server]"));
         assert!(template.contains("[indexer]"));
         assert!(template.contains("paths"));
+        assert!(template.contains("[ranking]"));
+        assert!(template.contains("[feedback]"));
+    }
+
+    #[test]
+    fn test_ranking_config_profiles() {
+        let config = Config::default();
+        assert_eq!(config.ranking.effective_recency_weight(), 0.0);
+
+        let toml = r#"
+[ranking]
+profile = "recent"
+recency_source = "git"
+recency_half_life_days = 7.0
+"#;
+        let config: Config = toml::from_str(toml).unwrap();
+        assert_eq!(config.ranking.profile, RankingProfile::Recent);
+        assert_eq!(config.ranking.recency_source, RecencySource::Git);
+        assert_eq!(config.ranking.effective_recency_weight(), 1.0);
+
+        let toml = r#"
+[ranking]
+profile = "recent"
+recency_weight = 0.25
+"#;
+        let config: Config = toml::from_str(toml).unwrap();
+        assert_eq!(config.ranking.effective_recency_weight(), 0.25);
+    }
+
+    #[test]
+    fn test_package_roots_config() {
+        let toml = r#"
+[indexer]
+paths = ["/code/service", "/code/shared"]
+
+[indexer.package_roots]
+"@acme/shared" = "../shared/js"
+"github.com/acme/shared" = "/code/shared/go"
+"#;
+        let config: Config = toml::from_str(toml).unwrap();
+        assert_eq!(
+            config.indexer.package_roots.get("@acme/shared").unwrap(),
+            "../shared/js"
+        );
+        assert_eq!(config.indexer.package_roots.len(), 2);
+
+        // Mappings change dependency edges, so they invalidate a persisted index
+        let mut unmapped = config.indexer.clone();
+        unmapped.package_roots.clear();
+        assert_ne!(config.indexer.fingerprint(), unmapped.fingerprint());
+    }
+
+    #[test]
+    fn test_fingerprint_tracks_symbol_query_files() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("rust.scm"),
+            "(function_item) @definition.function",
+        )
+        .unwrap();
+        let config = IndexerConfig {
+            symbol_queries_dir: Some(dir.path().to_string_lossy().into_owned()),
+            ..IndexerConfig::default()
+        };
+        let before = config.fingerprint();
+        assert_eq!(before, config.fingerprint());
+
+        std::fs::write(
+            dir.path().join("rust.scm"),
+            "(struct_item) @definition.struct",
+        )
+        .unwrap();
+        let edited = config.fingerprint();
+        assert_ne!(before, edited);
+
+        std::fs::write(
+            dir.path().join("go.scm"),
+            "(function_declaration) @definition.function",
+        )
+        .unwrap();
+        assert_ne!(edited, config.fingerprint());
     }
 }
@@ -0,0 +1,238 @@
+//! Import cycle detection.
+//!
+//! An import cycle is a strongly connected component of the import graph:
+//! every file in it reaches every other file through imports. Components are
+//! found with Tarjan's algorithm, run iteratively so that deep import chains
+//! cannot overflow the stack. A single file only counts as a cycle when it
+//! imports itself.
+
+use rustc_hash::{FxHashMap, FxHashSet};
+use serde::{Deserialize, Serialize};
+
+/// Strongly connected components of `graph` (file -> files it imports) that
+/// form an import cycle.
+///
+/// Members of each component are sorted and components are ordered by their
+/// first member, so the result is deterministic.
+pub fn strongly_connected_components(graph: &FxHashMap<u32, FxHashSet<u32>>) -> Vec<Vec<u32>> {
+    let mut roots: Vec<u32> = graph.keys().copied().collect();
+    roots.sort_unstable();
+
+    let mut tarjan = Tarjan {
+        graph,
+        index: FxHashMap::default(),
+        stack: Vec::new(),
+        on_stack: FxHashSet::default(),
+        calls: Vec::new(),
+        components: Vec::new(),
+    };
+    for root in roots {
+        if !tarjan.index.contains_key(&root) {
+            tarjan.run(root);
+        }
+    }
+
+    let mut components = tarjan.components;
+    components.sort_unstable_by_key(|members| members[0]);
+    components
+}
+
+/// State of one iterative run of Tarjan's algorithm
+struct Tarjan<'a> {
+    graph: &'a FxHashMap<u32, FxHashSet<u32>>,
+    /// Discovery index and lowlink per visited node
+    index: FxHashMap<u32, (u32, u32)>,
+    stack: Vec<u32>,
+    on_stack: FxHashSet<u32>,
+    /// Explicit call stack of (node, successors, next successor position)
+    calls: Vec<(u32, Vec<u32>, usize)>,
+    components: Vec<Vec<u32>>,
+}
+
+impl Tarjan<'_> {
+    fn visit(&mut self, node: u32) {
+        let discovered = self.index.len() as u32;
+        self.index.insert(node, (discovered, discovered));
+        self.stack.push(node);
+        self.on_stack.insert(node);
+        let mut successors: Vec<u32> = self
+            .graph
+            .get(&node)
+            .map(|targets| targets.iter().copied().collect())
+            .unwrap_or_default();
+        successors.sort_unstable();
+        self.calls.push((node, successors, 0));
+    }
+
+    fn lower(&mut self, node: u32, value: u32) {
+        if let Some(entry) = self.index.get_mut(&node) {
+            entry.1 = entry.1.min(value);
+        }
+    }
+
+    fn run(&mut self, root: u32) {
+        self.visit(root);
+        while let Some((node, successors, position)) = self.calls.last_mut() {
+            let node = *node;
+            if let Some(&target) = successors.get(*position) {
+                *position += 1;
+                match self.index.get(&target) {
+                    None => self.visit(target),
+                    Some(&(target_index, _)) if self.on_stack.contains(&target) => {
+                        self.lower(node, target_index)
+                    }
+                    Some(_) => {}
+                }
+                continue;
+            }
+
+            self.calls.pop();
+            let (node_index, lowlink) = self.index[&node];
+            if let Some(&(parent, _, _)) = self.calls.last() {
+                self.lower(parent, lowlink);
+            }
+            if lowlink == node_index {
+                let mut component = Vec::new();
+                while let Some(member) = self.stack.pop() {
+                    self.on_stack.remove(&member);
+                    component.push(member);
+                    if member == node {
+                        break;
+                    }
+                }
+                let self_import = self.graph.get(&node).is_some_and(|t| t.contains(&node));
+                if component.len() > 1 || self_import {
+                    component.sort_unstable();
+                    self.components.push(component);
+                }
+            }
+        }
+    }
+}
+
+/// An import between two files of the same cycle
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CycleEdge {
+    pub from: String,
+    pub to: String,
+    /// Lines of the import statements in `from` (1-based)
+    pub lines: Vec<usize>,
+}
+
+/// Files forming one import cycle, with the imports that link them
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImportCycle {
+    /// Display paths of the files in the cycle, sorted
+    pub files: Vec<String>,
+    /// Imports between files of the cycle, sorted by importing file
+    pub edges: Vec<CycleEdge>,
+}
+
+/// Number of cycles of one size
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CycleSizeCount {
+    /// Files in the cycle
+    pub size: usize,
+    pub count: usize,
+}
+
+/// Summary of import cycles in the index
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CycleStats {
+    /// Number of import cycles
+    pub cycles: usize,
+    /// Files that are part of a cycle
+    pub files_in_cycles: usize,
+    /// Files in the largest cycle
+    pub largest_cycle: usize,
+    /// Mean number of files per cycle
+    pub average_cycle_size: f64,
+    /// Cycle counts per size, smallest first
+    pub size_distribution: Vec<CycleSizeCount>,
+    /// Cycles with their import edges, largest first
+    pub largest_cycles: Vec<ImportCycle>,
+}
+
+impl CycleStats {
+    /// Summarize cycles given by their sizes; `largest_cycles` is left empty
+    pub fn from_sizes(sizes: impl IntoIterator<Item = usize>) -> Self {
+        let mut stats = CycleStats::default();
+        let mut by_size: FxHashMap<usize, usize> = FxHashMap::default();
+        for size in sizes {
+            stats.cycles += 1;
+            stats.files_in_cycles += size;
+            stats.largest_cycle = stats.largest_cycle.max(size);
+            *by_size.entry(size).or_default() += 1;
+        }
+        if stats.cycles > 0 {
+            stats.average_cycle_size = stats.files_in_cycles as f64 / stats.cycles as f64;
+        }
+        stats.size_distribution = by_size
+            .into_iter()
+            .map(|(size, count)| CycleSizeCount { size, count })
+            .collect();
+        stats.size_distribution.sort_unstable_by_key(|s| s.size);
+        stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn graph(edges: &[(u32, u32)]) -> FxHashMap<u32, FxHashSet<u32>> {
+        let mut graph: FxHashMap<u32, FxHashSet<u32>> = FxHashMap::default();
+        for &(from, to) in edges {
+            graph.entry(from).or_default().insert(to);
+        }
+        graph
+    }
+
+    #[test]
+    fn test_components_skip_acyclic_files() {
+        // 1 -> 2 -> 3 -> 1, 3 -> 4, 4 -> 5 -> 4, 6 -> 6, 7 -> 1
+        let graph = graph(&[
+            (1, 2),
+            (2, 3),
+            (3, 1),
+            (3, 4),
+            (4, 5),
+            (5, 4),
+            (6, 6),
+            (7, 1),
+        ]);
+        assert_eq!(
+            strongly_connected_components(&graph),
+            vec![vec![1, 2, 3], vec![4, 5], vec![6]]
+        );
+        assert!(strongly_connected_components(&self::graph(&[(1, 2), (2, 3)])).is_empty());
+    }
+
+    #[test]
+    fn test_long_chain_does_not_overflow() {
+        let edges: Vec<(u32, u32)> = (0..200_000)
+            .map(|i| (i, i + 1))
+            .chain([(200_000, 0)])
+            .collect();
+        let components = strongly_connected_components(&graph(&edges));
+        assert_eq!(components.len(), 1);
+        assert_eq!(components[0].len(), 200_001);
+    }
+
+    #[test]
+    fn test_stats_from_sizes() {
+        let stats = CycleStats::from_sizes([2, 3, 2]);
+        assert_eq!(stats.cycles, 3);
+        assert_eq!(stats.files_in_cycles, 7);
+        assert_eq!(stats.largest_cycle, 3);
+        assert!((stats.average_cycle_size - 7.0 / 3.0).abs() < 1e-9);
+        assert_eq!(
+            stats.size_distribution,
+            vec![
+                CycleSizeCount { size: 2, count: 2 },
+                CycleSizeCount { size: 3, count: 1 },
+            ]
+        );
+        assert_eq!(CycleStats::from_sizes([]), CycleStats::default());
+    }
+}
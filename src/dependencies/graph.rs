@@ -0,0 +1,275 @@
+//! Export of the import graph for external tools (Graphviz, Gephi, JSON).
+//!
+//! A [`DependencyGraph`] is built from file-level edges whose endpoints have
+//! already been mapped to node names (file paths, directories or packages);
+//! edges that collapse onto the same pair of nodes are merged and counted.
+
+use rustc_hash::FxHashMap;
+use serde::Serialize;
+use std::fmt::Write;
+
+/// Serialization format of an exported graph
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphFormat {
+    /// Graphviz DOT
+    Dot,
+    /// GraphML (Gephi, yEd, NetworkX)
+    GraphMl,
+    /// `{ "nodes": [...], "edges": [...] }`
+    Json,
+}
+
+impl std::str::FromStr for GraphFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "dot" | "graphviz" => Ok(Self::Dot),
+            "graphml" => Ok(Self::GraphMl),
+            "json" => Ok(Self::Json),
+            other => Err(format!(
+                "Unknown graph format '{}' (expected dot, graphml or json)",
+                other
+            )),
+        }
+    }
+}
+
+/// Granularity of graph nodes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GraphLevel {
+    /// One node per file
+    File,
+    /// One node per directory
+    Directory,
+    /// One node per package, named as declared by the nearest `Cargo.toml`,
+    /// `package.json`, `go.mod`, `pyproject.toml`, `pom.xml`, ... (by
+    /// directory when two manifests declare the same name); files outside
+    /// any package fall back to their directory
+    Package,
+}
+
+impl std::str::FromStr for GraphLevel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "file" | "files" => Ok(Self::File),
+            "dir" | "directory" => Ok(Self::Directory),
+            "package" | "pkg" => Ok(Self::Package),
+            other => Err(format!(
+                "Unknown graph level '{}' (expected file, directory or package)",
+                other
+            )),
+        }
+    }
+}
+
+/// A node of the exported graph
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct GraphNode {
+    /// File path, directory or package directory
+    pub id: String,
+    /// Number of files collapsed into this node
+    pub files: usize,
+}
+
+/// A directed edge `source` imports `target`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct GraphEdge {
+    pub source: String,
+    pub target: String,
+    /// Number of file-level imports merged into this edge
+    pub weight: usize,
+}
+
+/// Import graph ready for export
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DependencyGraph {
+    pub level: GraphLevel,
+    /// Nodes sorted by id
+    pub nodes: Vec<GraphNode>,
+    /// Edges sorted by source, then target
+    pub edges: Vec<GraphEdge>,
+}
+
+impl DependencyGraph {
+    /// Build a graph from file-level edges.
+    ///
+    /// `files` lists every file taking part as `(file, node)`; `edges` are
+    /// `(from_file, to_file)` pairs into that list. Edges within one node
+    /// (after collapsing) are dropped.
+    pub fn build(level: GraphLevel, files: &[(String, String)], edges: &[(usize, usize)]) -> Self {
+        let mut node_files: FxHashMap<&str, usize> = FxHashMap::default();
+        for (_, node) in files {
+            *node_files.entry(node.as_str()).or_default() += 1;
+        }
+
+        let mut weights: FxHashMap<(&str, &str), usize> = FxHashMap::default();
+        for &(from, to) in edges {
+            let (source, target) = (files[from].1.as_str(), files[to].1.as_str());
+            if source != target {
+                *weights.entry((source, target)).or_default() += 1;
+            }
+        }
+
+        let mut nodes: Vec<GraphNode> = node_files
+            .into_iter()
+            .map(|(id, files)| GraphNode {
+                id: id.to_string(),
+                files,
+            })
+            .collect();
+        nodes.sort_by(|a, b| a.id.cmp(&b.id));
+        let mut edges: Vec<GraphEdge> = weights
+            .into_iter()
+            .map(|((source, target), weight)| GraphEdge {
+                source: source.to_string(),
+                target: target.to_string(),
+                weight,
+            })
+            .collect();
+        edges.sort_by(|a, b| (&a.source, &a.target).cmp(&(&b.source, &b.target)));
+
+        Self {
+            level,
+            nodes,
+            edges,
+        }
+    }
+
+    /// Render as Graphviz DOT; collapsed nodes and merged edges carry their counts
+    pub fn to_dot(&self) -> String {
+        let mut out =
+            String::from("digraph dependencies {\n    rankdir=LR;\n    node [shape=box];\n");
+        for node in &self.nodes {
+            if node.files > 1 {
+                let _ = writeln!(
+                    out,
+                    "    \"{}\" [label=\"{} ({} files)\"];",
+                    dot_escape(&node.id),
+                    dot_escape(&node.id),
+                    node.files
+                );
+            } else {
+                let _ = writeln!(out, "    \"{}\";", dot_escape(&node.id));
+            }
+        }
+        for edge in &self.edges {
+            let _ = write!(
+                out,
+                "    \"{}\" -> \"{}\"",
+                dot_escape(&edge.source),
+                dot_escape(&edge.target)
+            );
+            if edge.weight > 1 {
+                let _ = write!(out, " [weight={}, label=\"{}\"]", edge.weight, edge.weight);
+            }
+            out.push_str(";\n");
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    /// Render as GraphML with `files` node and `weight` edge attributes
+    pub fn to_graphml(&self) -> String {
+        let mut out = String::from(concat!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+            "<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n",
+            "  <key id=\"files\" for=\"node\" attr.name=\"files\" attr.type=\"int\"/>\n",
+            "  <key id=\"weight\" for=\"edge\" attr.name=\"weight\" attr.type=\"int\"/>\n",
+            "  <graph id=\"dependencies\" edgedefault=\"directed\">\n",
+        ));
+        for node in &self.nodes {
+            let _ = writeln!(
+                out,
+                "    <node id=\"{}\"><data key=\"files\">{}</data></node>",
+                xml_escape(&node.id),
+                node.files
+            );
+        }
+        for edge in &self.edges {
+            let _ = writeln!(
+                out,
+                "    <edge source=\"{}\" target=\"{}\"><data key=\"weight\">{}</data></edge>",
+                xml_escape(&edge.source),
+                xml_escape(&edge.target),
+                edge.weight
+            );
+        }
+        out.push_str("  </graph>\n</graphml>\n");
+        out
+    }
+}
+
+fn dot_escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(level: GraphLevel) -> DependencyGraph {
+        let node = |file: &str| match level {
+            GraphLevel::File => file.to_string(),
+            _ => file.rsplit_once('/').map_or("", |(dir, _)| dir).to_string(),
+        };
+        let files: Vec<(String, String)> = ["app/main.rs", "app/cli.rs", "lib/a.rs", "lib/b.rs"]
+            .iter()
+            .map(|f| (f.to_string(), node(f)))
+            .collect();
+        // main -> cli, main -> a, cli -> a, cli -> b, a -> b
+        DependencyGraph::build(level, &files, &[(0, 1), (0, 2), (1, 2), (1, 3), (2, 3)])
+    }
+
+    #[test]
+    fn test_collapse_to_directories_merges_edges() {
+        let graph = sample(GraphLevel::Directory);
+        assert_eq!(
+            graph.nodes,
+            vec![
+                GraphNode {
+                    id: "app".to_string(),
+                    files: 2
+                },
+                GraphNode {
+                    id: "lib".to_string(),
+                    files: 2
+                },
+            ]
+        );
+        assert_eq!(
+            graph.edges,
+            vec![GraphEdge {
+                source: "app".to_string(),
+                target: "lib".to_string(),
+                weight: 3
+            }]
+        );
+        assert!(graph
+            .to_dot()
+            .contains("\"app\" -> \"lib\" [weight=3, label=\"3\"];"));
+    }
+
+    #[test]
+    fn test_export_formats_escape_names() {
+        let files = vec![
+            ("a\"b.h".to_string(), "a\"b.h".to_string()),
+            ("<c>&.h".to_string(), "<c>&.h".to_string()),
+        ];
+        let graph = DependencyGraph::build(GraphLevel::File, &files, &[(0, 1)]);
+        assert!(graph.to_dot().contains("\"a\\\"b.h\" -> \"<c>&.h\";"));
+        let graphml = graph.to_graphml();
+        assert!(graphml.contains("<edge source=\"a&quot;b.h\" target=\"&lt;c&gt;&amp;.h\">"));
+        assert_eq!(sample(GraphLevel::File).edges.len(), 5);
+    }
+}
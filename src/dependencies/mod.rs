@@ -3,10 +3,165 @@
 //! Tracks import relationships between files to enable dependency-based
 //! ranking of search results. Files that are imported by many other files
 //! receive a ranking boost.
+//!
+//! Imports are resolved per [`ImportType`]: Rust paths through the crate
+//! layout and Cargo workspace members, Python modules from package and
+//! `pyproject.toml` roots, JS/TS specifiers through tsconfig `paths`/`baseUrl`
+//! and npm workspaces, Go packages through the nearest `go.mod`, Java and C#
+//! names by package/namespace directory, C/C++ includes relative to the
+//! including file and the configured include directories, Ruby
+//! `require_relative` relative to the file, and PHP namespaces by matching
+//! directory names (PSR-4 style). When only a file-name match is possible and
+//! several files qualify, the import is recorded as ambiguous instead of
+//! linked to an arbitrary candidate.
+//!
+//! When several roots are indexed, `[indexer] package_roots` maps import
+//! prefixes (`@acme/shared`, `shared_lib`, `github.com/acme/shared`) to the
+//! directory holding that package, so Rust, Python, JS/TS, Go and C/C++
+//! imports resolve into another root instead of by file name alone.
+//!
+//! The line of each import statement is kept per edge, so import cycles
+//! (see [`cycles`]) can point at the imports that close them.
+
+pub mod cycles;
+pub mod graph;
+pub mod packages;
+mod projects;
+pub mod unresolved;
 
+use crate::symbols::extractor::ImportType;
+pub use cycles::{CycleEdge, CycleSizeCount, CycleStats, ImportCycle};
+pub use graph::{DependencyGraph, GraphEdge, GraphFormat, GraphLevel, GraphNode};
+pub use packages::{PackageInfo, PackageSummary};
+use projects::ProjectConfigs;
 use rustc_hash::{FxHashMap, FxHashSet};
 use std::collections::HashMap;
+use std::ffi::OsString;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
+pub use unresolved::{
+    ExternalModuleCount, UnresolvedImport, UnresolvedImportStats, UnresolvedLanguageCount,
+};
+
+/// Outcome of resolving one import statement
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImportResolution {
+    /// The import names these files (several for Go packages and Java/C# package imports)
+    Resolved(Vec<PathBuf>),
+    /// Several files could be the target and none is preferred
+    Ambiguous(Vec<PathBuf>),
+    /// No indexed file matches (external package or target not indexed yet)
+    Unresolved,
+}
+
+impl ImportResolution {
+    /// Resolved to every file in `paths` (unresolved when empty)
+    fn all(paths: Vec<PathBuf>) -> Self {
+        if paths.is_empty() {
+            Self::Unresolved
+        } else {
+            Self::Resolved(paths)
+        }
+    }
+
+    /// Resolved when exactly one candidate matches the import, ambiguous when several do
+    fn from_candidates(mut candidates: Vec<PathBuf>) -> Self {
+        match candidates.len() {
+            0 => Self::Unresolved,
+            1 => Self::Resolved(candidates),
+            _ => {
+                candidates.sort();
+                candidates.dedup();
+                Self::Ambiguous(candidates)
+            }
+        }
+    }
+}
+
+impl From<Option<PathBuf>> for ImportResolution {
+    fn from(path: Option<PathBuf>) -> Self {
+        Self::all(path.into_iter().collect())
+    }
+}
+
+/// Which way to follow import edges
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DependencyDirection {
+    /// Files the start file imports
+    Dependencies,
+    /// Files that import the start file
+    Dependents,
+}
+
+impl std::str::FromStr for DependencyDirection {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "deps" | "dependencies" | "imports" => Ok(Self::Dependencies),
+            "dependents" | "importers" => Ok(Self::Dependents),
+            other => Err(format!(
+                "Unknown direction '{}' (expected 'deps' or 'dependents')",
+                other
+            )),
+        }
+    }
+}
+
+/// Maximum import hops followed by a transitive walk
+pub const MAX_DEPENDENCY_DEPTH: usize = 64;
+
+/// Maximum files returned by a transitive walk
+pub const MAX_DEPENDENCY_FILES: usize = 10_000;
+
+/// Files reached by a breadth-first walk over import edges
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DependencyLayers {
+    /// `layers[i]` holds the files `i + 1` import hops from the start file, sorted by ID
+    pub layers: Vec<Vec<u32>>,
+    /// Whether the walk stopped at the file cap with files left unvisited
+    pub truncated: bool,
+}
+
+impl DependencyLayers {
+    /// Every reached file, nearest first
+    pub fn files(&self) -> impl Iterator<Item = u32> + '_ {
+        self.layers.iter().flatten().copied()
+    }
+
+    /// Number of reached files
+    pub fn len(&self) -> usize {
+        self.layers.iter().map(Vec::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.layers.is_empty()
+    }
+}
+
+/// Files affected by a change to one file: its transitive dependents
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ImpactAnalysis {
+    /// Dependents grouped by import distance
+    pub dependents: DependencyLayers,
+    /// Affected files the start file also imports, directly or transitively:
+    /// they share an import cycle with it
+    pub cycle: Vec<u32>,
+    /// Whether the file cap cut the walk over the start file's imports short,
+    /// so `cycle` may be incomplete
+    pub cycle_truncated: bool,
+}
+
+/// An import that matched several indexed files and was not linked to any
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AmbiguousImport {
+    /// Import path as written in the source
+    pub path: String,
+    /// Line of the import statement (0-based)
+    pub line: usize,
+    /// Files the import could refer to
+    pub candidates: Vec<u32>,
+}
 
 /// Tracks import/dependency relationships between files in the index.
 ///
@@ -25,6 +180,22 @@ pub struct DependencyIndex {
     path_to_id: HashMap<PathBuf, u32>,
     /// Inverted index: filename -> list of full paths (for fast non-relative import lookup)
     filename_to_paths: HashMap<String, Vec<PathBuf>>,
+    /// Directory -> files directly inside it (Go packages, Java/C# namespaces)
+    dir_to_paths: HashMap<PathBuf, Vec<PathBuf>>,
+    /// Directory name -> directories with that name (for package suffix lookups)
+    dirname_to_dirs: HashMap<String, Vec<PathBuf>>,
+    /// Directories searched for C/C++ `#include` targets
+    include_dirs: Vec<PathBuf>,
+    /// Import prefixes mapped to package directories, longest prefix first
+    package_roots: Vec<(String, PathBuf)>,
+    /// Project manifests (tsconfig, package.json, Cargo.toml, pyproject.toml, go.mod)
+    projects: ProjectConfigs,
+    /// Imports that matched several files, per importing file
+    ambiguous_imports: FxHashMap<u32, Vec<AmbiguousImport>>,
+    /// Imports left unresolved after indexing, per importing file
+    unresolved_imports: FxHashMap<u32, Vec<UnresolvedImport>>,
+    /// Lines (0-based) of the import statements behind each (from, to) edge
+    import_lines: FxHashMap<(u32, u32), Vec<u32>>,
 }
 
 impl DependencyIndex {
@@ -50,6 +221,19 @@ impl DependencyIndex {
                 .push(stored_path.clone());
         }
 
+        if let Some(dir) = stored_path.parent() {
+            let files = self.dir_to_paths.entry(dir.to_path_buf()).or_default();
+            if files.is_empty() {
+                if let Some(name) = dir.file_name().and_then(|s| s.to_str()) {
+                    self.dirname_to_dirs
+                        .entry(name.to_string())
+                        .or_default()
+                        .push(dir.to_path_buf());
+                }
+            }
+            files.push(stored_path.clone());
+        }
+
         self.path_to_id.insert(stored_path, file_id);
     }
 
@@ -69,66 +253,593 @@ impl DependencyIndex {
         self.import_counts.insert(to_file, count as u32);
     }
 
-    /// Add import from raw import path string, resolving it relative to the source file
+    /// Set the directories searched for C/C++ `#include` targets (canonical paths)
+    pub fn set_include_dirs(&mut self, dirs: Vec<PathBuf>) {
+        self.include_dirs = dirs;
+    }
+
+    /// Set the indexed root directories (canonical paths); project manifest
+    /// lookups do not walk above them
+    pub fn set_roots(&mut self, roots: Vec<PathBuf>) {
+        self.projects.set_roots(roots);
+    }
+
+    /// Map import prefixes to the directories holding those packages
+    /// (canonical paths), e.g. `@acme/shared` to a library checked out beside
+    /// the importing repository
+    pub fn set_package_roots(&mut self, mut roots: Vec<(String, PathBuf)>) {
+        roots.sort_by(|a, b| b.0.len().cmp(&a.0.len()).then_with(|| a.0.cmp(&b.0)));
+        self.package_roots = roots;
+    }
+
+    /// Directory mapped to the longest package prefix of `import_path`, with
+    /// the rest of the path after `separator` (empty for the package itself)
+    fn mapped_package<'a>(
+        &self,
+        import_path: &'a str,
+        separator: &str,
+    ) -> Option<(&Path, &'a str)> {
+        self.package_roots.iter().find_map(|(name, dir)| {
+            let rest = import_path.strip_prefix(name.as_str())?;
+            let rest = if rest.is_empty() {
+                rest
+            } else {
+                rest.strip_prefix(separator)?
+            };
+            Some((dir.as_path(), rest))
+        })
+    }
+
+    /// Add import from raw import path string, resolving it relative to the source file.
+    /// Returns the IDs of the imported files (several for Go packages and
+    /// Java/C# package imports; none when the import is unresolved or ambiguous).
     pub fn add_import_from_path(
         &mut self,
         from_file_id: u32,
         from_file_path: &Path,
         import_path: &str,
-    ) -> Option<u32> {
-        let resolved = self.resolve_import_path(from_file_path, import_path)?;
-        let to_file_id = self.path_to_id.get(&resolved).copied()?;
-        self.add_import(from_file_id, to_file_id);
-        Some(to_file_id)
+        import_type: &ImportType,
+    ) -> Vec<u32> {
+        let ImportResolution::Resolved(resolved) =
+            self.resolve_import_path(from_file_path, import_path, import_type)
+        else {
+            return Vec::new();
+        };
+        let to_file_ids: Vec<u32> = resolved
+            .iter()
+            .filter_map(|path| self.path_to_id.get(path).copied())
+            .collect();
+        for &to_file_id in &to_file_ids {
+            self.add_import(from_file_id, to_file_id);
+        }
+        to_file_ids
     }
 
-    /// Resolve an import path relative to the importing file.
+    /// Resolve an import to the indexed files it refers to.
     /// This method is thread-safe and only requires &self.
-    pub fn resolve_import_path(&self, from_file: &Path, import_path: &str) -> Option<PathBuf> {
-        let parent = from_file.parent()?;
-
-        // Handle relative imports
-        if import_path.starts_with('.') {
-            let resolved = parent.join(import_path);
-            // Try with common extensions
-            for ext in &["", ".rs", ".py", ".js", ".ts", ".jsx", ".tsx"] {
-                let with_ext = if ext.is_empty() {
-                    resolved.clone()
-                } else {
-                    resolved.with_extension(&ext[1..])
-                };
-                if let Ok(canonical) = with_ext.canonicalize() {
-                    if self.path_to_id.contains_key(&canonical) {
-                        return Some(canonical);
-                    }
+    pub fn resolve_import_path(
+        &self,
+        from_file: &Path,
+        import_path: &str,
+        import_type: &ImportType,
+    ) -> ImportResolution {
+        match import_type {
+            ImportType::Rust => self.resolve_rust_import(from_file, import_path).into(),
+            ImportType::Python => self.resolve_python_import(from_file, import_path),
+            ImportType::JavaScript => self.resolve_js_import(from_file, import_path),
+            ImportType::Go => ImportResolution::all(self.resolve_go_import(from_file, import_path)),
+            ImportType::Java => ImportResolution::all(self.resolve_java_import(import_path)),
+            ImportType::Include => self.resolve_include(from_file, import_path),
+            ImportType::CSharp => ImportResolution::all(self.resolve_csharp_using(import_path)),
+            ImportType::Ruby => self
+                .resolve_relative(from_file, import_path, &["rb"])
+                .into(),
+            ImportType::Php => self.resolve_php_import(from_file, import_path),
+        }
+    }
+
+    /// Rust: `crate::`, `self::` and `super::` paths and `mod` declarations
+    /// map onto module files; a leading crate name resolves into that crate
+    /// when it is the importing crate or a member of its Cargo workspace.
+    /// Items (`crate::config::Config`) resolve to the file of their module.
+    fn resolve_rust_import(&self, from_file: &Path, import_path: &str) -> Option<PathBuf> {
+        // `a::b::{c, d}`, `a::b::*` and `a::b as c` all import from module `a::b`
+        let path = import_path.split(" as ").next()?;
+        let path = path.split("::{").next()?.trim_end_matches("::*");
+        let segments: Vec<&str> = path.split("::").map(str::trim).collect();
+        let (first, rest) = segments.split_first()?;
+
+        let module_dir = rust_module_dir(from_file)?;
+        let dir = from_file.parent()?;
+        match *first {
+            "crate" => {
+                let krate = self.projects.rust_crate(dir)?;
+                self.rust_submodule_file(&krate.src_dir, rest)
+                    .or_else(|| self.rust_module_root(&krate.src_dir))
+            }
+            "self" => self.rust_submodule_file(&module_dir, rest),
+            "super" => {
+                let mut dir = module_dir.parent()?.to_path_buf();
+                let mut rest = rest;
+                while let Some((&"super", tail)) = rest.split_first() {
+                    dir = dir.parent()?.to_path_buf();
+                    rest = tail;
+                }
+                self.rust_submodule_file(&dir, rest)
+                    .or_else(|| self.rust_module_root(&dir))
+            }
+            name => {
+                let own = self.projects.rust_crate(dir);
+                let src_dir = own
+                    .iter()
+                    .find(|c| c.name == name)
+                    .map(|c| c.src_dir.clone())
+                    .or_else(|| {
+                        let workspace = self.projects.cargo_workspace(&own?.manifest_dir)?;
+                        let member = workspace.iter().find(|c| c.name == name)?;
+                        Some(member.src_dir.clone())
+                    })
+                    .or_else(|| self.mapped_rust_crate(name));
+                match src_dir {
+                    Some(src_dir) => self
+                        .rust_submodule_file(&src_dir, rest)
+                        .or_else(|| self.rust_module_root(&src_dir)),
+                    // `mod name;` or a path into a child module; anything
+                    // else is an external crate
+                    None => self.rust_submodule_file(&module_dir, &segments),
                 }
             }
         }
+    }
 
-        // For non-relative imports, use the filename inverted index (O(1) lookup)
-        // instead of scanning all paths (O(n))
-        let import_filename = Path::new(import_path)
-            .file_name()
-            .and_then(|s| s.to_str())?;
+    /// Source directory of a crate mapped in `package_roots` (crate names
+    /// match with `-` read as `_`)
+    fn mapped_rust_crate(&self, name: &str) -> Option<PathBuf> {
+        let (_, dir) = self
+            .package_roots
+            .iter()
+            .find(|(package, _)| package.replace('-', "_") == name)?;
+        match self.projects.rust_crate(dir) {
+            Some(krate) if krate.manifest_dir.starts_with(dir) => Some(krate.src_dir.clone()),
+            _ => Some(dir.clone()),
+        }
+    }
+
+    /// File of the longest module prefix of `segments` below `dir`
+    /// (`dir/a/b.rs` or `dir/a/b/mod.rs`)
+    fn rust_submodule_file(&self, dir: &Path, segments: &[&str]) -> Option<PathBuf> {
+        (1..=segments.len()).rev().find_map(|len| {
+            let module = dir.join(segments[..len].iter().collect::<PathBuf>());
+            let mut file = module.clone().into_os_string();
+            file.push(".rs");
+            self.registered(Path::new(&file))
+                .or_else(|| self.registered(&module.join("mod.rs")))
+        })
+    }
 
-        // Try exact filename match first
-        if let Some(paths) = self.filename_to_paths.get(import_filename) {
-            if let Some(path) = paths.first() {
-                return Some(path.clone());
+    /// File defining the module whose children live in `dir`: the crate root
+    /// (`lib.rs`/`main.rs`), `dir/mod.rs` or the sibling `dir.rs`
+    fn rust_module_root(&self, dir: &Path) -> Option<PathBuf> {
+        ["lib.rs", "main.rs", "mod.rs"]
+            .iter()
+            .find_map(|root| self.registered(&dir.join(root)))
+            .or_else(|| self.registered(&dir.with_extension("rs")))
+    }
+
+    /// Python: relative imports (`.mod`, `..pkg.mod`) from the importing
+    /// package; absolute ones from a mapped package directory, the top-level
+    /// package root (or the script's directory) and then the `pyproject.toml`
+    /// source roots, in that order.
+    /// Otherwise an indexed module whose path ends with the dotted name is
+    /// used, or reported as ambiguous when several do.
+    fn resolve_python_import(&self, from_file: &Path, import_path: &str) -> ImportResolution {
+        let Some(dir) = from_file.parent() else {
+            return ImportResolution::Unresolved;
+        };
+
+        let module = import_path.trim_start_matches('.');
+        let segments: Vec<&str> = module.split('.').filter(|s| !s.is_empty()).collect();
+        let dots = import_path.len() - module.len();
+        if dots > 0 {
+            let mut base = Some(dir);
+            for _ in 1..dots {
+                base = base.and_then(Path::parent);
             }
+            return base
+                .and_then(|base| self.python_module_file(base, &segments))
+                .into();
         }
 
-        // Try with common extensions appended
-        for ext in &[".rs", ".py", ".js", ".ts", ".jsx", ".tsx"] {
-            let with_ext = format!("{}{}", import_filename, ext);
-            if let Some(paths) = self.filename_to_paths.get(&with_ext) {
-                if let Some(path) = paths.first() {
-                    return Some(path.clone());
-                }
+        if let Some((package_dir, rest)) = self.mapped_package(module, ".") {
+            let rest: Vec<&str> = rest.split('.').filter(|s| !s.is_empty()).collect();
+            return self.python_module_file(package_dir, &rest).into();
+        }
+
+        let mut roots = vec![self
+            .projects
+            .python_package_root(dir)
+            .unwrap_or_else(|| dir.to_path_buf())];
+        roots.extend(self.projects.python_project_roots(dir));
+        if let Some(found) = roots
+            .iter()
+            .find_map(|root| self.python_module_file(root, &segments))
+        {
+            return ImportResolution::Resolved(vec![found]);
+        }
+
+        let Some((name, packages)) = segments.split_last() else {
+            return ImportResolution::Unresolved;
+        };
+        let module_suffix: PathBuf = packages.iter().copied().chain([*name]).collect();
+        let module_file = format!("{}.py", name);
+        let candidates = self
+            .filename_to_paths
+            .get(&module_file)
+            .into_iter()
+            .flatten()
+            .filter(|path| path.ends_with(module_suffix.with_extension("py")))
+            .chain(
+                self.filename_to_paths
+                    .get("__init__.py")
+                    .into_iter()
+                    .flatten()
+                    .filter(|path| path.parent().is_some_and(|p| p.ends_with(&module_suffix))),
+            )
+            .cloned()
+            .collect();
+        ImportResolution::from_candidates(candidates)
+    }
+
+    /// `root/a/b.py` or `root/a/b/__init__.py` for segments `[a, b]`
+    /// (`root/__init__.py` for none)
+    fn python_module_file(&self, root: &Path, segments: &[&str]) -> Option<PathBuf> {
+        let module: PathBuf = root.join(segments.iter().collect::<PathBuf>());
+        if !segments.is_empty() {
+            let mut file = module.clone().into_os_string();
+            file.push(".py");
+            if let Some(found) = self.registered(Path::new(&file)) {
+                return Some(found);
+            }
+        }
+        self.registered(&module.join("__init__.py"))
+    }
+
+    /// JS/TS: relative specifiers from the importing file; bare ones through
+    /// the nearest tsconfig `paths` and `baseUrl`, then npm workspace packages
+    /// and mapped packages, then an indexed file with the specifier's name
+    /// (ambiguous when several)
+    fn resolve_js_import(&self, from_file: &Path, specifier: &str) -> ImportResolution {
+        let Some(dir) = from_file.parent() else {
+            return ImportResolution::Unresolved;
+        };
+        if specifier.starts_with('.') {
+            return self.js_module_file(&dir.join(specifier)).into();
+        }
+
+        if let Some(config) = self.projects.ts_config(dir) {
+            let mut bases = config.substitutions(specifier);
+            bases.extend(config.base_url.as_ref().map(|base| base.join(specifier)));
+            if let Some(found) = bases.iter().find_map(|base| self.js_module_file(base)) {
+                return ImportResolution::Resolved(vec![found]);
+            }
+        }
+
+        if let Some(workspace) = self.projects.npm_workspace(dir) {
+            let package = workspace
+                .iter()
+                .filter(|p| {
+                    specifier == p.name
+                        || specifier
+                            .strip_prefix(p.name.as_str())
+                            .is_some_and(|rest| rest.starts_with('/'))
+                })
+                .max_by_key(|p| p.name.len());
+            if let Some(package) = package {
+                let subpath = specifier[package.name.len()..].trim_start_matches('/');
+                return self
+                    .js_package_file(&package.dir, &package.entries, subpath)
+                    .into();
             }
         }
 
-        None
+        if let Some((package_dir, subpath)) = self.mapped_package(specifier, "/") {
+            let entries = self
+                .projects
+                .npm_package(package_dir)
+                .map(|package| package.entries.clone())
+                .unwrap_or_default();
+            return self.js_package_file(package_dir, &entries, subpath).into();
+        }
+
+        self.resolve_by_file_name(specifier)
+    }
+
+    /// File of `subpath` in the package in `dir` (its `src/` tried second),
+    /// or the package entry point when `subpath` is empty
+    fn js_package_file(&self, dir: &Path, entries: &[String], subpath: &str) -> Option<PathBuf> {
+        if subpath.is_empty() {
+            entries
+                .iter()
+                .map(|entry| dir.join(entry))
+                .chain([dir.join("index"), dir.join("src/index")])
+                .find_map(|base| self.js_module_file(&base))
+        } else {
+            [dir.join(subpath), dir.join("src").join(subpath)]
+                .iter()
+                .find_map(|base| self.js_module_file(base))
+        }
+    }
+
+    /// `base` itself, `base` with a JS/TS extension, or its `index` file
+    fn js_module_file(&self, base: &Path) -> Option<PathBuf> {
+        const EXTENSIONS: [&str; 6] = ["ts", "tsx", "js", "jsx", "mjs", "cjs"];
+        if base.extension().is_some() {
+            if let Some(found) = self.registered(base) {
+                return Some(found);
+            }
+        }
+        EXTENSIONS
+            .iter()
+            .find_map(|ext| {
+                let mut file = base.as_os_str().to_os_string();
+                file.push(".");
+                file.push(ext);
+                self.registered(Path::new(&file))
+            })
+            .or_else(|| {
+                EXTENSIONS
+                    .iter()
+                    .find_map(|ext| self.registered(&base.join(format!("index.{}", ext))))
+            })
+    }
+
+    /// Indexed files named like the import's last path segment (with or
+    /// without a JS/TS extension); when several match, those whose path ends
+    /// with the whole specifier are preferred
+    fn resolve_by_file_name(&self, specifier: &str) -> ImportResolution {
+        let Some(file_name) = Path::new(specifier).file_name().and_then(|s| s.to_str()) else {
+            return ImportResolution::Unresolved;
+        };
+        let candidates: Vec<PathBuf> = ["", ".ts", ".tsx", ".js", ".jsx"]
+            .iter()
+            .filter_map(|ext| self.filename_to_paths.get(&format!("{}{}", file_name, ext)))
+            .find(|paths| !paths.is_empty())
+            .cloned()
+            .unwrap_or_default();
+        if candidates.len() <= 1 {
+            return ImportResolution::from_candidates(candidates);
+        }
+        let suffix = Path::new(specifier.trim_start_matches('@'));
+        let narrowed: Vec<PathBuf> = candidates
+            .iter()
+            .filter(|path| path.with_extension("").ends_with(suffix))
+            .cloned()
+            .collect();
+        match narrowed.len() {
+            1 => ImportResolution::Resolved(narrowed),
+            _ => ImportResolution::Ambiguous(candidates),
+        }
+    }
+
+    /// Go: `import "<module>/<dir>"` names every non-test `.go` file in `<dir>`
+    /// below the root of the importing file's module, or of a mapped module
+    fn resolve_go_import(&self, from_file: &Path, import_path: &str) -> Vec<PathBuf> {
+        if let Some((module_dir, rest)) = self.mapped_package(import_path, "/") {
+            return self
+                .files_in_dir(&module_dir.join(rest), "go")
+                .filter(|path| !path.to_string_lossy().ends_with("_test.go"))
+                .collect();
+        }
+        let Some(module) = from_file
+            .parent()
+            .and_then(|dir| self.projects.go_module(dir))
+        else {
+            return Vec::new();
+        };
+        let package_dir = if import_path == module.path {
+            module.root.clone()
+        } else {
+            match import_path
+                .strip_prefix(module.path.as_str())
+                .and_then(|rest| rest.strip_prefix('/'))
+            {
+                Some(rest) => module.root.join(rest),
+                // Standard library or third-party package
+                None => return Vec::new(),
+            }
+        };
+        self.files_in_dir(&package_dir, "go")
+            .filter(|path| !path.to_string_lossy().ends_with("_test.go"))
+            .collect()
+    }
+
+    /// Java: `a.b.C` is `a/b/C.java`, `a.b` (wildcard import) every class in
+    /// `a/b/`, and `a.b.C.member` (static import) `a/b/C.java`
+    fn resolve_java_import(&self, import_path: &str) -> Vec<PathBuf> {
+        let segments: Vec<&str> = import_path.split('.').collect();
+        let class = self.files_with_suffix(&segments, "java");
+        if !class.is_empty() {
+            return class;
+        }
+        let package = self.package_files(&segments, "java");
+        if !package.is_empty() {
+            return package;
+        }
+        match segments.split_last() {
+            Some((_, owner)) if !owner.is_empty() => self.files_with_suffix(owner, "java"),
+            _ => Vec::new(),
+        }
+    }
+
+    /// C/C++: relative to the including file, then each include directory and
+    /// mapped package (`<pkg/util.h>` as `util.h` in the directory of `pkg`),
+    /// then an indexed file whose path ends with the include (ambiguous when several do)
+    fn resolve_include(&self, from_file: &Path, include: &str) -> ImportResolution {
+        let relative = from_file.parent().map(|dir| dir.join(include));
+        let mapped = self
+            .mapped_package(include, "/")
+            .filter(|(_, rest)| !rest.is_empty())
+            .map(|(dir, rest)| dir.join(rest));
+        let search = relative
+            .into_iter()
+            .chain(self.include_dirs.iter().map(|dir| dir.join(include)))
+            .chain(mapped);
+        for candidate in search {
+            if let Some(found) = self.registered(&candidate) {
+                return ImportResolution::Resolved(vec![found]);
+            }
+        }
+
+        // A bare `util.h` could be any of several headers
+        let Some(file_name) = Path::new(include).file_name().and_then(|s| s.to_str()) else {
+            return ImportResolution::Unresolved;
+        };
+        let candidates = self
+            .filename_to_paths
+            .get(file_name)
+            .into_iter()
+            .flatten()
+            .filter(|path| path.ends_with(include))
+            .cloned()
+            .collect();
+        ImportResolution::from_candidates(candidates)
+    }
+
+    /// C#: `using A.B.C` names the `.cs` files of directory `A/B/C` (or a
+    /// project folder `A.B` containing `C`); `using static A.B.C` names `A/B/C.cs`
+    fn resolve_csharp_using(&self, import_path: &str) -> Vec<PathBuf> {
+        let segments: Vec<&str> = import_path.split('.').map(str::trim).collect();
+        let files = self.package_files(&segments, "cs");
+        if !files.is_empty() {
+            return files;
+        }
+        for split in 2..segments.len() {
+            let project = segments[..split].join(".");
+            let mut components = vec![project.as_str()];
+            components.extend_from_slice(&segments[split..]);
+            let files = self.package_files(&components, "cs");
+            if !files.is_empty() {
+                return files;
+            }
+        }
+        self.files_with_suffix(&segments, "cs")
+    }
+
+    /// PHP: `require`/`include` paths are relative to the file; `use` names
+    /// are matched to `<Name>.php` files whose directories best match the
+    /// namespace, ignoring case (`App\Models\User` → `app/Models/User.php`)
+    fn resolve_php_import(&self, from_file: &Path, import_path: &str) -> ImportResolution {
+        if import_path.ends_with(".php") || import_path.contains('/') {
+            return self
+                .resolve_relative(from_file, import_path.trim_start_matches('/'), &[])
+                .into();
+        }
+
+        let segments: Vec<&str> = import_path.trim_start_matches('\\').split('\\').collect();
+        let Some((class, namespace)) = segments.split_last() else {
+            return ImportResolution::Unresolved;
+        };
+        let Some(candidates) = self.filename_to_paths.get(&format!("{}.php", class)) else {
+            return ImportResolution::Unresolved;
+        };
+
+        let score = |path: &PathBuf| {
+            path.parent()
+                .into_iter()
+                .flat_map(|dir| dir.iter().rev())
+                .zip(namespace.iter().rev())
+                .take_while(|(dir, segment)| dir.eq_ignore_ascii_case(segment))
+                .count()
+        };
+        let best = candidates.iter().map(score).max().unwrap_or(0);
+        let matches = candidates
+            .iter()
+            .filter(|path| score(path) == best)
+            .cloned()
+            .collect();
+        ImportResolution::from_candidates(matches)
+    }
+
+    /// Resolve `relative` against the importing file's directory, trying each
+    /// extension in turn when the path itself is not indexed
+    fn resolve_relative(
+        &self,
+        from_file: &Path,
+        relative: &str,
+        extensions: &[&str],
+    ) -> Option<PathBuf> {
+        let base = from_file.parent()?.join(relative);
+        if let Some(found) = self.registered(&base) {
+            return Some(found);
+        }
+        extensions.iter().find_map(|ext| {
+            let mut with_ext = OsString::from(base.as_os_str());
+            with_ext.push(".");
+            with_ext.push(ext);
+            self.registered(Path::new(&with_ext))
+        })
+    }
+
+    /// Canonical form of `path` if it is an indexed file
+    fn registered(&self, path: &Path) -> Option<PathBuf> {
+        let canonical = path.canonicalize().ok()?;
+        self.path_to_id
+            .contains_key(&canonical)
+            .then_some(canonical)
+    }
+
+    /// Indexed files directly inside `dir` with the given extension
+    fn files_in_dir<'a>(
+        &'a self,
+        dir: &Path,
+        extension: &'a str,
+    ) -> impl Iterator<Item = PathBuf> + 'a {
+        self.dir_to_paths
+            .get(dir)
+            .into_iter()
+            .flatten()
+            .filter(move |path| path.extension().is_some_and(|e| e == extension))
+            .cloned()
+    }
+
+    /// Indexed files at `a/b/C.<extension>` for segments `[a, b, C]`
+    fn files_with_suffix(&self, segments: &[&str], extension: &str) -> Vec<PathBuf> {
+        let Some((name, dirs)) = segments.split_last() else {
+            return Vec::new();
+        };
+        let suffix: PathBuf = dirs
+            .iter()
+            .copied()
+            .chain(std::iter::once(format!("{}.{}", name, extension).as_str()))
+            .collect();
+        self.filename_to_paths
+            .get(&format!("{}.{}", name, extension))
+            .into_iter()
+            .flatten()
+            .filter(|path| path.ends_with(&suffix))
+            .cloned()
+            .collect()
+    }
+
+    /// Indexed `.<extension>` files inside any directory ending in `a/b` for segments `[a, b]`
+    fn package_files(&self, segments: &[&str], extension: &str) -> Vec<PathBuf> {
+        let Some(last) = segments.last() else {
+            return Vec::new();
+        };
+        let suffix: PathBuf = segments.iter().collect();
+        self.dirname_to_dirs
+            .get(*last)
+            .into_iter()
+            .flatten()
+            .filter(|dir| dir.ends_with(&suffix))
+            .flat_map(|dir| self.files_in_dir(dir, extension))
+            .collect()
+    }
+
+    /// The package containing `file`, declared by the nearest package
+    /// manifest (`Cargo.toml`, `package.json`, `go.mod`, ...)
+    pub fn package(&self, file: &Path) -> Option<Arc<PackageInfo>> {
+        self.projects.package(file.parent()?)
     }
 
     /// Get file ID for a resolved path. Thread-safe.
@@ -157,6 +868,78 @@ impl DependencyIndex {
         }
     }
 
+    /// Batch insert import edges as `(from_file, to_file, line)`, keeping the
+    /// line (0-based) of the import statement behind each edge
+    pub fn add_imports_with_lines_batch(&mut self, edges: Vec<(u32, u32, u32)>) {
+        for &(from_file, to_file, line) in &edges {
+            let lines = self.import_lines.entry((from_file, to_file)).or_default();
+            if let Err(pos) = lines.binary_search(&line) {
+                lines.insert(pos, line);
+            }
+        }
+        self.add_imports_batch(
+            edges
+                .into_iter()
+                .map(|(from_file, to_file, _)| (from_file, to_file))
+                .collect(),
+        );
+    }
+
+    /// Lines (0-based, sorted) of the import statements in `from_file` that
+    /// import `to_file`; empty when unknown
+    pub fn import_lines(&self, from_file: u32, to_file: u32) -> &[u32] {
+        self.import_lines
+            .get(&(from_file, to_file))
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Record imports that matched several files
+    pub fn add_ambiguous_batch(&mut self, ambiguous: Vec<(u32, AmbiguousImport)>) {
+        for (file_id, import) in ambiguous {
+            self.ambiguous_imports
+                .entry(file_id)
+                .or_default()
+                .push(import);
+        }
+    }
+
+    /// Imports of the given file that matched several files
+    pub fn get_ambiguous_imports(&self, file_id: u32) -> &[AmbiguousImport] {
+        self.ambiguous_imports
+            .get(&file_id)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Total number of ambiguous imports across all files
+    pub fn total_ambiguous_imports(&self) -> usize {
+        self.ambiguous_imports.values().map(Vec::len).sum()
+    }
+
+    /// Record imports that matched no indexed file
+    pub fn add_unresolved_batch(&mut self, unresolved: Vec<(u32, UnresolvedImport)>) {
+        for (file_id, import) in unresolved {
+            self.unresolved_imports
+                .entry(file_id)
+                .or_default()
+                .push(import);
+        }
+    }
+
+    /// Imports of the given file that matched no indexed file
+    pub fn get_unresolved_imports(&self, file_id: u32) -> &[UnresolvedImport] {
+        self.unresolved_imports
+            .get(&file_id)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Files with unresolved imports, in no particular order
+    pub fn files_with_unresolved_imports(&self) -> impl Iterator<Item = u32> + '_ {
+        self.unresolved_imports.keys().copied()
+    }
+
     /// Get the number of files that import the given file
     pub fn get_import_count(&self, file_id: u32) -> u32 {
         self.import_counts.get(&file_id).copied().unwrap_or(0)
@@ -178,6 +961,99 @@ impl DependencyIndex {
             .unwrap_or_default()
     }
 
+    /// Breadth-first walk from `start` along import edges, up to `max_depth`
+    /// hops and `max_files` files. Each file is reported once, at its shortest
+    /// distance; the start file is never included, even when a cycle leads back to it.
+    pub fn walk(
+        &self,
+        start: u32,
+        direction: DependencyDirection,
+        max_depth: usize,
+        max_files: usize,
+    ) -> DependencyLayers {
+        let edges = match direction {
+            DependencyDirection::Dependencies => &self.imports,
+            DependencyDirection::Dependents => &self.imported_by,
+        };
+        let mut visited = FxHashSet::default();
+        visited.insert(start);
+        let mut result = DependencyLayers::default();
+        let mut frontier = vec![start];
+        let mut reached = 0;
+
+        while !frontier.is_empty() && result.layers.len() < max_depth {
+            let mut layer: Vec<u32> = frontier
+                .iter()
+                .filter_map(|id| edges.get(id))
+                .flatten()
+                .copied()
+                .filter(|id| !visited.contains(id))
+                .collect::<FxHashSet<u32>>()
+                .into_iter()
+                .collect();
+            if layer.is_empty() {
+                break;
+            }
+            layer.sort_unstable();
+            if reached + layer.len() > max_files {
+                layer.truncate(max_files - reached);
+                result.truncated = true;
+            }
+            reached += layer.len();
+            visited.extend(&layer);
+            if !layer.is_empty() {
+                result.layers.push(layer.clone());
+            }
+            if result.truncated {
+                break;
+            }
+            frontier = layer;
+        }
+        result
+    }
+
+    /// Transitive dependents of `file_id` (who is affected by a change to it),
+    /// with the dependents that are part of an import cycle through it.
+    /// Both walks are capped at `max_depth` hops and `max_files` files, which
+    /// are clamped to [`MAX_DEPENDENCY_DEPTH`] and [`MAX_DEPENDENCY_FILES`].
+    pub fn impact(&self, file_id: u32, max_depth: usize, max_files: usize) -> ImpactAnalysis {
+        let max_depth = max_depth.min(MAX_DEPENDENCY_DEPTH);
+        let max_files = max_files.min(MAX_DEPENDENCY_FILES);
+        let dependents = self.walk(
+            file_id,
+            DependencyDirection::Dependents,
+            max_depth,
+            max_files,
+        );
+        let affected: FxHashSet<u32> = dependents.files().collect();
+        if affected.is_empty() {
+            return ImpactAnalysis {
+                dependents,
+                ..ImpactAnalysis::default()
+            };
+        }
+
+        let imports = self.walk(
+            file_id,
+            DependencyDirection::Dependencies,
+            max_depth,
+            max_files,
+        );
+        let mut cycle: Vec<u32> = imports.files().filter(|id| affected.contains(id)).collect();
+        cycle.sort_unstable();
+        ImpactAnalysis {
+            dependents,
+            cycle,
+            cycle_truncated: imports.truncated,
+        }
+    }
+
+    /// Import cycles: groups of files that all reach each other through
+    /// imports (strongly connected components), each sorted by file ID
+    pub fn import_cycles(&self) -> Vec<Vec<u32>> {
+        cycles::strongly_connected_components(&self.imports)
+    }
+
     /// Get total number of dependency edges in the graph
     pub fn total_edges(&self) -> usize {
         self.imports.values().map(|s| s.len()).sum()
@@ -196,6 +1072,12 @@ impl DependencyIndex {
             .collect()
     }
 
+    /// Forget cached project manifests and tsconfigs that a change to `path`
+    /// may affect (no-op for other files)
+    pub fn invalidate_project_config(&self, path: &Path) {
+        self.projects.invalidate(path);
+    }
+
     /// Clear all dependency information
     pub fn clear(&mut self) {
         self.imports.clear();
@@ -203,6 +1085,24 @@ impl DependencyIndex {
         self.import_counts.clear();
         self.path_to_id.clear();
         self.filename_to_paths.clear();
+        self.dir_to_paths.clear();
+        self.dirname_to_dirs.clear();
+        self.ambiguous_imports.clear();
+        self.unresolved_imports.clear();
+        self.import_lines.clear();
+        self.projects.clear();
+    }
+}
+
+/// Directory holding the child modules of a Rust source file: its own
+/// directory for `mod.rs`/`lib.rs`/`main.rs`, otherwise `dir/<stem>`
+fn rust_module_dir(file: &Path) -> Option<PathBuf> {
+    let dir = file.parent()?;
+    let stem = file.file_stem()?;
+    if ["mod", "lib", "main"].iter().any(|root| stem == *root) {
+        Some(dir.to_path_buf())
+    } else {
+        Some(dir.join(stem))
     }
 }
 
@@ -222,6 +1122,72 @@ mod tests {
         assert_eq!(index.get_dependencies(1), vec![2]);
     }
 
+    #[test]
+    fn test_walk_layers_and_caps() {
+        // 1 <- 2 <- 3 <- 4, and 5 imports both 1 and 3
+        let mut index = DependencyIndex::new();
+        for (from, to) in [(2, 1), (3, 2), (4, 3), (5, 1), (5, 3)] {
+            index.add_import(from, to);
+        }
+
+        let all = index.walk(1, DependencyDirection::Dependents, usize::MAX, usize::MAX);
+        assert_eq!(all.layers, vec![vec![2, 5], vec![3], vec![4]]);
+        assert!(!all.truncated);
+
+        let shallow = index.walk(1, DependencyDirection::Dependents, 2, usize::MAX);
+        assert_eq!(shallow.layers, vec![vec![2, 5], vec![3]]);
+
+        let capped = index.walk(1, DependencyDirection::Dependents, usize::MAX, 3);
+        assert_eq!(capped.layers, vec![vec![2, 5], vec![3]]);
+        assert!(capped.truncated);
+
+        let deps = index.walk(5, DependencyDirection::Dependencies, usize::MAX, usize::MAX);
+        assert_eq!(deps.layers, vec![vec![1, 3], vec![2]]);
+    }
+
+    #[test]
+    fn test_impact_reports_cycle() {
+        // 1 -> 2 -> 3 -> 1, and 4 imports 3
+        let mut index = DependencyIndex::new();
+        for (from, to) in [(1, 2), (2, 3), (3, 1), (4, 3)] {
+            index.add_import(from, to);
+        }
+
+        let impact = index.impact(1, usize::MAX, usize::MAX);
+        assert_eq!(impact.dependents.layers, vec![vec![3], vec![2, 4]]);
+        assert_eq!(impact.cycle, vec![2, 3]);
+        assert!(index.impact(4, usize::MAX, usize::MAX).cycle.is_empty());
+        assert!(!impact.cycle_truncated);
+
+        // 1 also imports 5 and 6, so a two-file cap stops both walks: 4 is
+        // dropped from the dependents and 3 is never reached through imports
+        index.add_import(1, 5);
+        index.add_import(1, 6);
+        let capped = index.impact(1, usize::MAX, 2);
+        assert_eq!(capped.dependents.layers, vec![vec![3], vec![2]]);
+        assert!(capped.dependents.truncated);
+        assert_eq!(capped.cycle, vec![2]);
+        assert!(capped.cycle_truncated);
+    }
+
+    #[test]
+    fn test_import_lines_and_cycles() {
+        let mut index = DependencyIndex::new();
+        index.add_imports_with_lines_batch(vec![
+            (1, 2, 7),
+            (1, 2, 3),
+            (2, 1, 0),
+            (1, 2, 3),
+            (2, 3, 1),
+        ]);
+
+        assert_eq!(index.import_lines(1, 2), &[3, 7]);
+        assert_eq!(index.import_lines(2, 1), &[0]);
+        assert!(index.import_lines(3, 2).is_empty());
+        assert_eq!(index.total_edges(), 3);
+        assert_eq!(index.import_cycles(), vec![vec![1, 2]]);
+    }
+
     #[test]
     fn test_bidirectional() {
         let mut index = DependencyIndex::new();
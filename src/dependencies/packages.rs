@@ -0,0 +1,483 @@
+//! Package manifests: which package a file belongs to and what it depends on.
+//!
+//! The manifest closest to a file (see [`PACKAGE_MANIFESTS`]) names its
+//! package. Each manifest format is read for the package name, version and
+//! the dependencies it declares; manifests that cannot be parsed, or that do
+//! not name their package (`Gemfile`, `build.gradle`, virtual Cargo
+//! workspaces), still define a package named after their directory.
+
+use super::projects::{read_json, read_toml, PACKAGE_MANIFESTS};
+use regex::Regex;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// A package declared by a manifest
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PackageInfo {
+    /// Package name (`name` in `Cargo.toml`/`package.json`/`pyproject.toml`,
+    /// the module path of `go.mod`, `groupId:artifactId` of `pom.xml`)
+    pub name: String,
+    /// Declared version, when the manifest has one
+    pub version: Option<String>,
+    /// Path of the manifest file
+    pub manifest: PathBuf,
+    /// Package manager: cargo, npm, go, python, maven, gradle, composer or bundler
+    pub ecosystem: &'static str,
+    /// Names of the declared dependencies (including dev/build ones), sorted
+    pub dependencies: Vec<String>,
+}
+
+impl PackageInfo {
+    /// Directory the manifest lives in (the package root)
+    pub fn dir(&self) -> &Path {
+        self.manifest.parent().unwrap_or(Path::new(""))
+    }
+}
+
+/// An indexed package: its manifest, file count and declared dependencies
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PackageSummary {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    pub ecosystem: &'static str,
+    /// Display path of the manifest
+    pub manifest: String,
+    /// Indexed files belonging to the package
+    pub files: usize,
+    /// Declared dependencies on other indexed packages
+    pub workspace_dependencies: Vec<String>,
+    /// Declared dependencies that are not indexed (third-party packages)
+    pub external_dependencies: Vec<String>,
+}
+
+/// Read the package defined in `dir`, trying [`PACKAGE_MANIFESTS`] in order
+pub fn read_package(dir: &Path) -> Option<PackageInfo> {
+    let manifest_name = PACKAGE_MANIFESTS
+        .iter()
+        .find(|name| dir.join(name).is_file())?;
+    let manifest = dir.join(manifest_name);
+    let dir_name = dir
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let mut package = PackageInfo {
+        name: String::new(),
+        version: None,
+        manifest: manifest.clone(),
+        ecosystem: "",
+        dependencies: Vec::new(),
+    };
+
+    match *manifest_name {
+        "Cargo.toml" => {
+            package.ecosystem = "cargo";
+            if let Some(toml) = read_toml(&manifest) {
+                read_cargo(&toml, &mut package);
+            }
+        }
+        "package.json" => {
+            package.ecosystem = "npm";
+            if let Some(json) = read_json(&manifest) {
+                let sections = [
+                    "dependencies",
+                    "devDependencies",
+                    "peerDependencies",
+                    "optionalDependencies",
+                ];
+                read_json_manifest(&json, &sections, &mut package);
+            }
+        }
+        "composer.json" => {
+            package.ecosystem = "composer";
+            if let Some(json) = read_json(&manifest) {
+                read_json_manifest(&json, &["require", "require-dev"], &mut package);
+                // `php` and `ext-*` are platform requirements, not packages
+                package.dependencies.retain(|name| name.contains('/'));
+            }
+        }
+        "go.mod" => {
+            package.ecosystem = "go";
+            if let Ok(text) = std::fs::read_to_string(&manifest) {
+                read_go_mod(&text, &mut package);
+            }
+        }
+        "pyproject.toml" => {
+            package.ecosystem = "python";
+            if let Some(toml) = read_toml(&manifest) {
+                read_pyproject(&toml, &mut package);
+            }
+        }
+        "setup.py" => {
+            package.ecosystem = "python";
+            if let Ok(text) = std::fs::read_to_string(&manifest) {
+                read_setup_py(&text, &mut package);
+            }
+        }
+        "pom.xml" => {
+            package.ecosystem = "maven";
+            if let Ok(text) = std::fs::read_to_string(&manifest) {
+                read_pom(&text, &mut package);
+            }
+        }
+        "build.gradle" | "build.gradle.kts" => {
+            package.ecosystem = "gradle";
+            if let Ok(text) = std::fs::read_to_string(&manifest) {
+                read_gradle(&text, &mut package);
+            }
+        }
+        "Gemfile" => {
+            package.ecosystem = "bundler";
+            if let Ok(text) = std::fs::read_to_string(&manifest) {
+                package.dependencies = patterns()
+                    .gem
+                    .captures_iter(&text)
+                    .map(|c| c[1].to_string())
+                    .collect();
+            }
+        }
+        _ => {}
+    }
+
+    if package.name.is_empty() {
+        package.name = dir_name;
+    }
+    package.dependencies.sort();
+    package.dependencies.dedup();
+    Some(package)
+}
+
+fn read_cargo(toml: &toml::Value, package: &mut PackageInfo) {
+    if let Some(pkg) = toml.get("package") {
+        package.name = pkg
+            .get("name")
+            .and_then(|n| n.as_str())
+            .unwrap_or_default()
+            .to_string();
+        // `version.workspace = true` has no literal version
+        package.version = pkg
+            .get("version")
+            .and_then(|v| v.as_str())
+            .map(str::to_string);
+    }
+    package.dependencies = ["dependencies", "dev-dependencies", "build-dependencies"]
+        .iter()
+        .filter_map(|section| toml.get(*section)?.as_table())
+        .flat_map(|deps| deps.keys().cloned())
+        .collect();
+}
+
+fn read_json_manifest(json: &serde_json::Value, sections: &[&str], package: &mut PackageInfo) {
+    let field = |key: &str| json.get(key)?.as_str().map(str::to_string);
+    package.name = field("name").unwrap_or_default();
+    package.version = field("version");
+    package.dependencies = sections
+        .iter()
+        .filter_map(|section| json.get(*section)?.as_object())
+        .flat_map(|deps| deps.keys().cloned())
+        .collect();
+}
+
+fn read_go_mod(text: &str, package: &mut PackageInfo) {
+    let mut in_require = false;
+    for line in text.lines() {
+        let line = line.split("//").next().unwrap_or_default().trim();
+        if let Some(path) = line.strip_prefix("module ") {
+            package.name = path.trim().trim_matches('"').to_string();
+        } else if line == "require (" {
+            in_require = true;
+        } else if in_require && line == ")" {
+            in_require = false;
+        } else {
+            let requirement = if in_require {
+                Some(line)
+            } else {
+                line.strip_prefix("require ")
+            };
+            if let Some(module) = requirement.and_then(|r| r.split_whitespace().next()) {
+                package.dependencies.push(module.to_string());
+            }
+        }
+    }
+}
+
+fn read_pyproject(toml: &toml::Value, package: &mut PackageInfo) {
+    let str_field =
+        |table: Option<&toml::Value>, key: &str| table?.get(key)?.as_str().map(str::to_string);
+    let project = toml.get("project");
+    let poetry = toml.get("tool").and_then(|t| t.get("poetry"));
+    package.name = str_field(project, "name")
+        .or_else(|| str_field(poetry, "name"))
+        .unwrap_or_default();
+    package.version = str_field(project, "version").or_else(|| str_field(poetry, "version"));
+
+    // PEP 621: a list of requirement strings, optional groups in a table
+    let requirements = project
+        .and_then(|p| p.get("dependencies"))
+        .and_then(|d| d.as_array())
+        .into_iter()
+        .flatten()
+        .chain(
+            project
+                .and_then(|p| p.get("optional-dependencies"))
+                .and_then(|d| d.as_table())
+                .into_iter()
+                .flat_map(|groups| groups.values())
+                .filter_map(|group| group.as_array())
+                .flatten(),
+        )
+        .filter_map(|r| r.as_str())
+        .filter_map(requirement_name);
+    package.dependencies.extend(requirements);
+
+    // Poetry: dependency tables keyed by name
+    if let Some(poetry) = poetry {
+        let tables = ["dependencies", "dev-dependencies"]
+            .iter()
+            .filter_map(|section| poetry.get(*section)?.as_table());
+        package.dependencies.extend(
+            tables
+                .flat_map(|deps| deps.keys())
+                .filter(|name| name.as_str() != "python")
+                .cloned(),
+        );
+    }
+}
+
+fn read_setup_py(text: &str, package: &mut PackageInfo) {
+    let keyword = |key: &str| {
+        patterns()
+            .setup_keyword
+            .captures_iter(text)
+            .find(|c| &c[1] == key)
+            .map(|c| c[2].to_string())
+    };
+    package.name = keyword("name").unwrap_or_default();
+    package.version = keyword("version");
+    if let Some(list) = patterns().install_requires.captures(text) {
+        package.dependencies = patterns()
+            .quoted
+            .captures_iter(&list[1])
+            .filter_map(|c| requirement_name(&c[1]))
+            .collect();
+    }
+}
+
+fn read_pom(text: &str, package: &mut PackageInfo) {
+    // Drop comments and the sections whose coordinates are not the project's own
+    let text = patterns().xml_comment.replace_all(text, "");
+    let text = patterns().pom_nested.replace_all(&text, "");
+    let dependencies: Vec<String> = patterns()
+        .pom_dependency
+        .captures_iter(&text)
+        .filter_map(|c| {
+            let group = xml_element(&c[1], "groupId");
+            let artifact = xml_element(&c[1], "artifactId")?;
+            Some(match group {
+                Some(group) => format!("{}:{}", group, artifact),
+                None => artifact,
+            })
+        })
+        .collect();
+    let own = patterns().pom_dependencies.replace_all(&text, "");
+    if let Some(artifact) = xml_element(&own, "artifactId") {
+        package.name = match xml_element(&own, "groupId") {
+            Some(group) => format!("{}:{}", group, artifact),
+            None => artifact,
+        };
+    }
+    package.version = xml_element(&own, "version");
+    package.dependencies = dependencies;
+}
+
+fn read_gradle(text: &str, package: &mut PackageInfo) {
+    package.version = patterns()
+        .gradle_version
+        .captures(text)
+        .map(|c| c[1].to_string());
+    package.dependencies = patterns()
+        .gradle_dependency
+        .captures_iter(text)
+        .map(|c| format!("{}:{}", &c[1], &c[2]))
+        .collect();
+}
+
+/// Distribution name of a PEP 508 requirement (`requests[socks]>=2.0` -> `requests`)
+fn requirement_name(requirement: &str) -> Option<String> {
+    let name = requirement
+        .trim()
+        .split(|c: char| !(c.is_alphanumeric() || matches!(c, '-' | '_' | '.')))
+        .next()?;
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+/// Text of the first `<tag>` element in `xml`
+fn xml_element(xml: &str, tag: &str) -> Option<String> {
+    let start = xml.find(&format!("<{}>", tag))? + tag.len() + 2;
+    let end = start + xml[start..].find(&format!("</{}>", tag))?;
+    let value = xml[start..end].trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+/// Patterns for the manifests that are not parsed as JSON or TOML
+struct Patterns {
+    gem: Regex,
+    setup_keyword: Regex,
+    install_requires: Regex,
+    quoted: Regex,
+    xml_comment: Regex,
+    pom_nested: Regex,
+    pom_dependencies: Regex,
+    pom_dependency: Regex,
+    gradle_version: Regex,
+    gradle_dependency: Regex,
+}
+
+fn patterns() -> &'static Patterns {
+    static PATTERNS: OnceLock<Patterns> = OnceLock::new();
+    PATTERNS.get_or_init(|| {
+        let regex = |pattern: &str| Regex::new(pattern).expect("manifest pattern is valid");
+        let pom_nested = "parent|build|dependencyManagement|profiles|reporting|repositories|pluginRepositories";
+        Patterns {
+            gem: regex(r#"(?m)^\s*gem\s+["']([^"']+)["']"#),
+            setup_keyword: regex(r#"\b(name|version)\s*=\s*["']([^"']+)["']"#),
+            install_requires: regex(r"(?s)install_requires\s*=\s*\[(.*?)\]"),
+            quoted: regex(r#"["']([^"']+)["']"#),
+            xml_comment: regex(r"(?s)<!--.*?-->"),
+            pom_nested: regex(&format!(r"(?s)<({pom_nested})>.*?</({pom_nested})>")),
+            pom_dependencies: regex(r"(?s)<dependencies>.*?</dependencies>"),
+            pom_dependency: regex(r"(?s)<dependency>(.*?)</dependency>"),
+            gradle_version: regex(r#"(?m)^\s*version\s*=?\s*["']([^"']+)["']"#),
+            gradle_dependency: regex(
+                r#"\b(?:implementation|api|compileOnly|runtimeOnly|testImplementation|testRuntimeOnly|annotationProcessor|kapt|compile|testCompile)\s*\(?\s*["']([^:"'\s]+):([^:"'\s]+)"#,
+            ),
+        }
+    })
+}
+
+/// Split `pkg:` filters off a search query.
+///
+/// Returns the query without the filter tokens and the requested package
+/// names (`pkg:api,web` and repeated `pkg:` tokens are both accepted). A
+/// query without filters is returned unchanged.
+pub fn split_package_filters(query: &str) -> (String, Vec<String>) {
+    let is_filter = |token: &str| {
+        token
+            .get(..4)
+            .is_some_and(|prefix| prefix.eq_ignore_ascii_case("pkg:"))
+    };
+    if !query.split_whitespace().any(is_filter) {
+        return (query.to_string(), Vec::new());
+    }
+
+    let mut packages: Vec<String> = Vec::new();
+    let mut rest = Vec::new();
+    for token in query.split_whitespace() {
+        if is_filter(token) {
+            for name in token[4..].split(',').filter(|n| !n.is_empty()) {
+                if !packages.iter().any(|p| p == name) {
+                    packages.push(name.to_string());
+                }
+            }
+        } else {
+            rest.push(token);
+        }
+    }
+    (rest.join(" "), packages)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn package(manifest: &str, content: &str) -> PackageInfo {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path().join("pkgdir");
+        fs::create_dir_all(&root).unwrap();
+        fs::write(root.join(manifest), content).unwrap();
+        read_package(&root).unwrap()
+    }
+
+    #[test]
+    fn test_read_manifests() {
+        let cargo = package(
+            "Cargo.toml",
+            "[package]\nname = \"core\"\nversion = \"0.3.1\"\n\n[dependencies]\nserde = \"1\"\ntokio = { version = \"1\" }\n\n[dev-dependencies]\ntempfile = \"3\"\n",
+        );
+        assert_eq!(cargo.name, "core");
+        assert_eq!(cargo.version.as_deref(), Some("0.3.1"));
+        assert_eq!(cargo.ecosystem, "cargo");
+        assert_eq!(cargo.dependencies, vec!["serde", "tempfile", "tokio"]);
+
+        let npm = package(
+            "package.json",
+            r#"{ "name": "@acme/web", "version": "2.0.0", "dependencies": { "react": "^18" }, "devDependencies": { "vite": "5" } }"#,
+        );
+        assert_eq!(npm.name, "@acme/web");
+        assert_eq!(npm.dependencies, vec!["react", "vite"]);
+
+        let go = package(
+            "go.mod",
+            "module example.com/app\n\ngo 1.22\n\nrequire github.com/pkg/errors v0.9.1\n\nrequire (\n\tgolang.org/x/sync v0.7.0 // indirect\n)\n",
+        );
+        assert_eq!(go.name, "example.com/app");
+        assert_eq!(
+            go.dependencies,
+            vec!["github.com/pkg/errors", "golang.org/x/sync"]
+        );
+
+        let python = package(
+            "pyproject.toml",
+            "[project]\nname = \"svc\"\nversion = \"1.2\"\ndependencies = [\"requests[socks]>=2.0\", \"numpy\"]\n",
+        );
+        assert_eq!(python.dependencies, vec!["numpy", "requests"]);
+
+        let pom = package(
+            "pom.xml",
+            "<project><parent><groupId>org.parent</groupId><artifactId>base</artifactId><version>9</version></parent>\
+             <groupId>org.acme</groupId><artifactId>billing</artifactId><version>1.0.0</version>\
+             <dependencies><dependency><groupId>junit</groupId><artifactId>junit</artifactId><version>4.13</version></dependency></dependencies></project>",
+        );
+        assert_eq!(pom.name, "org.acme:billing");
+        assert_eq!(pom.version.as_deref(), Some("1.0.0"));
+        assert_eq!(pom.dependencies, vec!["junit:junit"]);
+
+        let gradle = package(
+            "build.gradle.kts",
+            "version = \"0.1\"\ndependencies {\n    implementation(\"com.squareup.okhttp3:okhttp:4.12.0\")\n    testImplementation 'junit:junit:4.13'\n}\n",
+        );
+        assert_eq!(gradle.name, "pkgdir");
+        assert_eq!(gradle.version.as_deref(), Some("0.1"));
+        assert_eq!(
+            gradle.dependencies,
+            vec!["com.squareup.okhttp3:okhttp", "junit:junit"]
+        );
+
+        let gems = package(
+            "Gemfile",
+            "source 'https://rubygems.org'\ngem 'rails', '~> 7'\n",
+        );
+        assert_eq!(
+            (gems.name.as_str(), gems.dependencies),
+            ("pkgdir", vec!["rails".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_split_package_filters() {
+        assert_eq!(
+            split_package_filters("retry pkg:api,web PKG:api"),
+            (
+                "retry".to_string(),
+                vec!["api".to_string(), "web".to_string()]
+            )
+        );
+        assert_eq!(
+            split_package_filters("no filters here"),
+            ("no filters here".to_string(), Vec::new())
+        );
+    }
+}
@@ -0,0 +1,598 @@
+//! Project configuration used to resolve non-relative imports.
+//!
+//! Manifests are read lazily from disk the first time an import needs them
+//! and cached per directory, so resolution stays `&self` and thread-safe:
+//! - `tsconfig.json` / `jsconfig.json`: `baseUrl`, `paths` and relative `extends`
+//! - `package.json` `workspaces` (npm/yarn): workspace package names and entry files
+//! - `Cargo.toml`: crate names, `[lib] path` and `[workspace] members`
+//! - `pyproject.toml` and `__init__.py`: Python source roots
+//! - `go.mod`: Go module paths
+//!
+//! Any of [`PACKAGE_MANIFESTS`] also marks the root of a package (see
+//! [`super::packages`]) for package-level views of the graph.
+
+use super::packages::{read_package, PackageInfo};
+use rustc_hash::FxHashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+
+/// Directory -> configuration governing it (None = no manifest at or above it)
+type DirCache<T> = RwLock<FxHashMap<PathBuf, Option<Arc<T>>>>;
+
+/// Files whose directory is the root of a package
+pub const PACKAGE_MANIFESTS: &[&str] = &[
+    "Cargo.toml",
+    "package.json",
+    "go.mod",
+    "pyproject.toml",
+    "setup.py",
+    "pom.xml",
+    "build.gradle",
+    "build.gradle.kts",
+    "composer.json",
+    "Gemfile",
+];
+
+/// Maximum `extends` chain followed when reading a tsconfig
+const MAX_TSCONFIG_EXTENDS: usize = 8;
+
+/// Compiler options of the `tsconfig.json` or `jsconfig.json` closest to a file
+#[derive(Debug, Default)]
+pub struct TsConfig {
+    /// Directory bare specifiers are resolved against (`baseUrl`)
+    pub base_url: Option<PathBuf>,
+    /// `paths` patterns and their substitutions
+    pub paths: Vec<(String, Vec<String>)>,
+    /// Directory `paths` substitutions are relative to
+    pub paths_base: PathBuf,
+}
+
+impl TsConfig {
+    /// Substitutions for `specifier` from the best `paths` pattern: an exact
+    /// pattern wins, otherwise the wildcard pattern with the longest prefix
+    pub fn substitutions(&self, specifier: &str) -> Vec<PathBuf> {
+        if let Some((_, targets)) = self.paths.iter().find(|(pattern, _)| pattern == specifier) {
+            return targets.iter().map(|t| self.paths_base.join(t)).collect();
+        }
+        let best = self
+            .paths
+            .iter()
+            .filter_map(|(pattern, targets)| {
+                let (prefix, suffix) = pattern.split_once('*')?;
+                let matched = specifier
+                    .strip_prefix(prefix)?
+                    .strip_suffix(suffix)
+                    .filter(|_| specifier.len() >= prefix.len() + suffix.len())?;
+                Some((prefix.len(), matched, targets))
+            })
+            .max_by_key(|(prefix_len, _, _)| *prefix_len);
+        match best {
+            Some((_, matched, targets)) => targets
+                .iter()
+                .map(|t| self.paths_base.join(t.replacen('*', matched, 1)))
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+}
+
+/// A package of an npm/yarn workspace
+#[derive(Debug)]
+pub struct NpmPackage {
+    /// Package name from its `package.json`
+    pub name: String,
+    /// Directory containing the `package.json`
+    pub dir: PathBuf,
+    /// Entry files named by `source`, `module`, `main` and `types`, in that order
+    pub entries: Vec<String>,
+}
+
+/// A Rust crate
+#[derive(Debug)]
+pub struct RustCrate {
+    /// Crate name as written in paths (`-` replaced by `_`)
+    pub name: String,
+    /// Directory of the crate root file (`src` for `src/lib.rs`)
+    pub src_dir: PathBuf,
+    /// Directory containing `Cargo.toml`
+    pub manifest_dir: PathBuf,
+}
+
+/// Go module declared by a `go.mod`
+#[derive(Debug)]
+pub struct GoModule {
+    /// Directory containing `go.mod`
+    pub root: PathBuf,
+    /// Module path (`example.com/app`)
+    pub path: String,
+}
+
+/// Lazily loaded, per-directory cache of project manifests
+#[derive(Debug, Default)]
+pub struct ProjectConfigs {
+    ts_configs: DirCache<TsConfig>,
+    npm_workspaces: DirCache<Vec<NpmPackage>>,
+    npm_packages: DirCache<NpmPackage>,
+    crates: DirCache<RustCrate>,
+    cargo_workspaces: DirCache<Vec<RustCrate>>,
+    pyprojects: DirCache<PathBuf>,
+    python_packages: DirCache<PathBuf>,
+    go_modules: DirCache<GoModule>,
+    packages: DirCache<PackageInfo>,
+    /// Indexed root directories; manifest lookups stop walking up at these
+    roots: Vec<PathBuf>,
+}
+
+impl ProjectConfigs {
+    /// Set the indexed root directories (canonical paths), so manifests above
+    /// an indexed root (e.g. a `package.json` in the home directory) are not
+    /// picked up
+    pub fn set_roots(&mut self, roots: Vec<PathBuf>) {
+        self.roots = roots;
+        self.clear();
+    }
+
+    /// The `tsconfig.json` (or `jsconfig.json`) closest to `dir`
+    pub fn ts_config(&self, dir: &Path) -> Option<Arc<TsConfig>> {
+        self.nearest(&self.ts_configs, dir, &|dir| {
+            ["tsconfig.json", "jsconfig.json"]
+                .iter()
+                .find_map(|name| read_ts_config(&dir.join(name), 0))
+        })
+    }
+
+    /// Packages of the npm/yarn workspace enclosing `dir`
+    pub fn npm_workspace(&self, dir: &Path) -> Option<Arc<Vec<NpmPackage>>> {
+        self.nearest(&self.npm_workspaces, dir, &|dir| {
+            let manifest = read_json(&dir.join("package.json"))?;
+            let workspaces = manifest.get("workspaces")?;
+            let patterns = workspaces
+                .as_array()
+                .or_else(|| workspaces.get("packages")?.as_array())?;
+            let patterns: Vec<&str> = patterns.iter().filter_map(|p| p.as_str()).collect();
+            Some(
+                expand_members(dir, &patterns)
+                    .iter()
+                    .filter_map(|member| npm_package(member))
+                    .collect(),
+            )
+        })
+    }
+
+    /// The npm package whose `package.json` is in `dir`
+    pub fn npm_package(&self, dir: &Path) -> Option<Arc<NpmPackage>> {
+        self.nearest(&self.npm_packages, dir, &npm_package)
+            .filter(|package| package.dir == dir)
+    }
+
+    /// The crate whose `Cargo.toml` is closest to `dir`
+    pub fn rust_crate(&self, dir: &Path) -> Option<Arc<RustCrate>> {
+        self.nearest(&self.crates, dir, &|dir| {
+            rust_crate(dir, &read_toml(&dir.join("Cargo.toml"))?)
+        })
+    }
+
+    /// Member crates of the Cargo workspace enclosing `dir`
+    pub fn cargo_workspace(&self, dir: &Path) -> Option<Arc<Vec<RustCrate>>> {
+        self.nearest(&self.cargo_workspaces, dir, &|dir| {
+            let manifest = read_toml(&dir.join("Cargo.toml"))?;
+            let members: Vec<&str> = manifest
+                .get("workspace")?
+                .get("members")
+                .and_then(|m| m.as_array())
+                .into_iter()
+                .flatten()
+                .filter_map(|m| m.as_str())
+                .collect();
+            let mut crates: Vec<RustCrate> = rust_crate(dir, &manifest).into_iter().collect();
+            crates.extend(
+                expand_members(dir, &members).iter().filter_map(|member| {
+                    rust_crate(member, &read_toml(&member.join("Cargo.toml"))?)
+                }),
+            );
+            Some(crates)
+        })
+    }
+
+    /// Source roots of the `pyproject.toml` project enclosing `dir`: the
+    /// project directory, then its `src` directory when present
+    pub fn python_project_roots(&self, dir: &Path) -> Vec<PathBuf> {
+        let Some(project) = self.nearest(&self.pyprojects, dir, &|dir| {
+            dir.join("pyproject.toml")
+                .is_file()
+                .then(|| dir.to_path_buf())
+        }) else {
+            return Vec::new();
+        };
+        let src = project.join("src");
+        let mut roots = vec![project.to_path_buf()];
+        if src.is_dir() {
+            roots.push(src);
+        }
+        roots
+    }
+
+    /// Directory containing the top-level package that `dir` belongs to
+    /// (None when `dir` has no `__init__.py`)
+    pub fn python_package_root(&self, dir: &Path) -> Option<PathBuf> {
+        if let Some(cached) = self.python_packages.read().ok()?.get(dir) {
+            return cached.as_deref().cloned();
+        }
+        let root = if dir.join("__init__.py").is_file() {
+            dir.parent().map(|parent| {
+                self.python_package_root(parent)
+                    .unwrap_or_else(|| parent.to_path_buf())
+            })
+        } else {
+            None
+        };
+        if let Ok(mut cache) = self.python_packages.write() {
+            cache.insert(dir.to_path_buf(), root.clone().map(Arc::new));
+        }
+        root
+    }
+
+    /// The Go module whose `go.mod` is closest to `dir`
+    pub fn go_module(&self, dir: &Path) -> Option<Arc<GoModule>> {
+        self.nearest(&self.go_modules, dir, &|dir| {
+            let go_mod = std::fs::read_to_string(dir.join("go.mod")).ok()?;
+            go_mod.lines().find_map(|line| {
+                let path = line.trim().strip_prefix("module")?.trim();
+                (!path.is_empty()).then(|| GoModule {
+                    root: dir.to_path_buf(),
+                    path: path.trim_matches('"').to_string(),
+                })
+            })
+        })
+    }
+
+    /// The package whose manifest is closest to `dir`
+    pub fn package(&self, dir: &Path) -> Option<Arc<PackageInfo>> {
+        self.nearest(&self.packages, dir, &read_package)
+    }
+
+    /// Forget cached answers that a created, edited or deleted `path` may
+    /// change. Per-directory entries at or below the manifest's directory
+    /// are dropped; workspaces and tsconfigs (which read manifests in other
+    /// directories through members and `extends`) are cleared outright.
+    pub fn invalidate(&self, path: &Path) {
+        fn clear_under<T>(cache: &DirCache<T>, dir: &Path) {
+            if let Ok(mut cache) = cache.write() {
+                cache.retain(|cached, _| !cached.starts_with(dir));
+            }
+        }
+        fn clear_cache<T>(cache: &DirCache<T>) {
+            if let Ok(mut cache) = cache.write() {
+                cache.clear();
+            }
+        }
+
+        let (Some(name), Some(dir)) = (path.file_name().and_then(|n| n.to_str()), path.parent())
+        else {
+            return;
+        };
+        match name {
+            "tsconfig.json" | "jsconfig.json" => clear_cache(&self.ts_configs),
+            "package.json" => {
+                clear_cache(&self.npm_workspaces);
+                clear_under(&self.npm_packages, dir);
+            }
+            "Cargo.toml" => {
+                clear_cache(&self.cargo_workspaces);
+                clear_under(&self.crates, dir);
+            }
+            "go.mod" => clear_under(&self.go_modules, dir),
+            "pyproject.toml" => clear_under(&self.pyprojects, dir),
+            _ => {}
+        }
+        if PACKAGE_MANIFESTS.contains(&name) {
+            clear_under(&self.packages, dir);
+        }
+    }
+
+    /// Walk up from `dir` to the first directory `load` finds a configuration
+    /// in, no further than an indexed root, caching the answer for every
+    /// directory visited
+    fn nearest<T>(
+        &self,
+        cache: &DirCache<T>,
+        dir: &Path,
+        load: &dyn Fn(&Path) -> Option<T>,
+    ) -> Option<Arc<T>> {
+        if let Some(cached) = cache.read().ok()?.get(dir) {
+            return cached.clone();
+        }
+        let found = match load(dir) {
+            Some(config) => Some(Arc::new(config)),
+            None if self.roots.iter().any(|root| root == dir) => None,
+            None => dir
+                .parent()
+                .and_then(|parent| self.nearest(cache, parent, load)),
+        };
+        if let Ok(mut cache) = cache.write() {
+            cache.insert(dir.to_path_buf(), found.clone());
+        }
+        found
+    }
+
+    /// Forget every cached manifest
+    pub fn clear(&self) {
+        fn clear_cache<T>(cache: &DirCache<T>) {
+            if let Ok(mut cache) = cache.write() {
+                cache.clear();
+            }
+        }
+        clear_cache(&self.ts_configs);
+        clear_cache(&self.npm_workspaces);
+        clear_cache(&self.npm_packages);
+        clear_cache(&self.crates);
+        clear_cache(&self.cargo_workspaces);
+        clear_cache(&self.pyprojects);
+        clear_cache(&self.python_packages);
+        clear_cache(&self.go_modules);
+        clear_cache(&self.packages);
+    }
+}
+
+/// Read a tsconfig, layering it over the config it `extends` (relative paths only)
+fn read_ts_config(file: &Path, depth: usize) -> Option<TsConfig> {
+    let json = read_json(file)?;
+    let dir = file.parent()?;
+
+    let mut config = json
+        .get("extends")
+        .and_then(|e| e.as_str())
+        .filter(|e| e.starts_with('.') && depth < MAX_TSCONFIG_EXTENDS)
+        .and_then(|extends| {
+            let mut base = dir.join(extends);
+            if base.extension().is_none() {
+                base.set_extension("json");
+            }
+            read_ts_config(&base, depth + 1)
+        })
+        .unwrap_or_default();
+
+    let options = json.get("compilerOptions");
+    if let Some(base_url) = options
+        .and_then(|o| o.get("baseUrl"))
+        .and_then(|b| b.as_str())
+    {
+        config.base_url = Some(dir.join(base_url));
+    }
+    if let Some(paths) = options
+        .and_then(|o| o.get("paths"))
+        .and_then(|p| p.as_object())
+    {
+        config.paths = paths
+            .iter()
+            .map(|(pattern, targets)| {
+                let targets = targets
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|t| t.as_str())
+                    .map(str::to_string)
+                    .collect();
+                (pattern.clone(), targets)
+            })
+            .collect();
+        config.paths_base = dir.to_path_buf();
+    }
+    // `paths` are relative to `baseUrl` when one is set
+    if let Some(base_url) = &config.base_url {
+        config.paths_base = base_url.clone();
+    }
+    Some(config)
+}
+
+/// Name and entry files of the package in `dir`
+fn npm_package(dir: &Path) -> Option<NpmPackage> {
+    let manifest = read_json(&dir.join("package.json"))?;
+    let name = manifest.get("name")?.as_str()?.to_string();
+    let entries = ["source", "module", "main", "types"]
+        .iter()
+        .filter_map(|field| manifest.get(*field)?.as_str())
+        .map(str::to_string)
+        .collect();
+    Some(NpmPackage {
+        name,
+        dir: dir.to_path_buf(),
+        entries,
+    })
+}
+
+/// Crate described by the manifest in `dir` (None for virtual manifests)
+fn rust_crate(dir: &Path, manifest: &toml::Value) -> Option<RustCrate> {
+    let package_name = manifest.get("package")?.get("name")?.as_str()?;
+    let lib = manifest.get("lib");
+    let name = lib
+        .and_then(|l| l.get("name"))
+        .and_then(|n| n.as_str())
+        .unwrap_or(package_name);
+    let lib_path = lib
+        .and_then(|l| l.get("path"))
+        .and_then(|p| p.as_str())
+        .unwrap_or("src/lib.rs");
+    let src_dir = dir
+        .join(lib_path)
+        .parent()
+        .map_or_else(|| dir.join("src"), Path::to_path_buf);
+    Some(RustCrate {
+        name: name.replace('-', "_"),
+        src_dir,
+        manifest_dir: dir.to_path_buf(),
+    })
+}
+
+/// Directories matched by workspace member globs (`packages/*`, `crates/core`);
+/// `!`-prefixed exclusions are skipped
+fn expand_members(root: &Path, patterns: &[&str]) -> Vec<PathBuf> {
+    let mut members = Vec::new();
+    for pattern in patterns.iter().filter(|p| !p.starts_with('!')) {
+        let pattern = root.join(pattern.trim_end_matches('/'));
+        let Ok(paths) = glob::glob(&pattern.to_string_lossy()) else {
+            continue;
+        };
+        for path in paths.flatten() {
+            if path.is_dir() && !members.contains(&path) {
+                members.push(path);
+            }
+        }
+    }
+    members
+}
+
+pub(super) fn read_toml(path: &Path) -> Option<toml::Value> {
+    std::fs::read_to_string(path).ok()?.parse().ok()
+}
+
+/// Parse JSON, tolerating the comments and trailing commas tsconfig files allow
+pub(super) fn read_json(path: &Path) -> Option<serde_json::Value> {
+    let text = std::fs::read_to_string(path).ok()?;
+    serde_json::from_str(&text)
+        .or_else(|_| serde_json::from_str(&strip_jsonc(&text)))
+        .ok()
+}
+
+/// Remove `//` and `/* */` comments and trailing commas outside of strings
+fn strip_jsonc(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    let mut in_string = false;
+    while let Some(c) = chars.next() {
+        if in_string {
+            out.push(c);
+            match c {
+                '\\' => out.extend(chars.next()),
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match (c, chars.peek()) {
+            ('"', _) => {
+                in_string = true;
+                out.push(c);
+            }
+            ('/', Some('/')) => while chars.next_if(|&c| c != '\n').is_some() {},
+            ('/', Some('*')) => {
+                chars.next();
+                let mut prev = '\0';
+                for c in chars.by_ref() {
+                    if prev == '*' && c == '/' {
+                        break;
+                    }
+                    prev = c;
+                }
+            }
+            (',', _) => {
+                let rest = chars.clone().find(|c| !c.is_whitespace());
+                if !matches!(rest, Some('}') | Some(']')) {
+                    out.push(c);
+                }
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_jsonc() {
+        let text = r#"{
+            // comment with "quotes"
+            "a": "http://x/*y*/", /* block */
+            "b": [1, 2,],
+        }"#;
+        let value: serde_json::Value = serde_json::from_str(&strip_jsonc(text)).unwrap();
+        assert_eq!(value["a"], "http://x/*y*/");
+        assert_eq!(value["b"], serde_json::json!([1, 2]));
+    }
+
+    #[test]
+    fn test_ts_paths_prefer_exact_then_longest_prefix() {
+        let config = TsConfig {
+            base_url: None,
+            paths: vec![
+                ("@app/*".to_string(), vec!["src/*".to_string()]),
+                (
+                    "@app/ui/*".to_string(),
+                    vec!["packages/ui/src/*".to_string()],
+                ),
+                ("config".to_string(), vec!["src/config/index".to_string()]),
+            ],
+            paths_base: PathBuf::from("/p"),
+        };
+        assert_eq!(
+            config.substitutions("@app/ui/button"),
+            vec![PathBuf::from("/p/packages/ui/src/button")]
+        );
+        assert_eq!(
+            config.substitutions("@app/utils"),
+            vec![PathBuf::from("/p/src/utils")]
+        );
+        assert_eq!(
+            config.substitutions("config"),
+            vec![PathBuf::from("/p/src/config/index")]
+        );
+        assert!(config.substitutions("lodash").is_empty());
+    }
+
+    #[test]
+    fn test_invalidate_rereads_changed_manifests() {
+        let root = tempfile::tempdir().unwrap();
+        let pkg = root.path().join("pkg");
+        std::fs::create_dir(&pkg).unwrap();
+        let go_mod = root.path().join("go.mod");
+        let tsconfig = root.path().join("tsconfig.json");
+        std::fs::write(&go_mod, "module example.com/old\n").unwrap();
+        std::fs::write(&tsconfig, r#"{"compilerOptions": {"baseUrl": "src"}}"#).unwrap();
+
+        let projects = ProjectConfigs::default();
+        assert_eq!(projects.go_module(&pkg).unwrap().path, "example.com/old");
+        assert!(projects.ts_config(&pkg).unwrap().base_url.is_some());
+
+        std::fs::write(&go_mod, "module example.com/new\n").unwrap();
+        std::fs::write(&tsconfig, "{}").unwrap();
+        // Cached until the watcher reports the change
+        assert_eq!(projects.go_module(&pkg).unwrap().path, "example.com/old");
+        projects.invalidate(&go_mod);
+        projects.invalidate(&tsconfig);
+        assert_eq!(projects.go_module(&pkg).unwrap().path, "example.com/new");
+        assert!(projects.ts_config(&pkg).unwrap().base_url.is_none());
+
+        // A new manifest below a cached directory takes over from its parent
+        std::fs::write(pkg.join("go.mod"), "module example.com/pkg\n").unwrap();
+        projects.invalidate(&pkg.join("go.mod"));
+        assert_eq!(projects.go_module(&pkg).unwrap().path, "example.com/pkg");
+    }
+
+    #[test]
+    fn test_manifest_lookup_stops_at_indexed_root() {
+        let outer = tempfile::tempdir().unwrap();
+        let repo = outer.path().join("repo");
+        let src = repo.join("src");
+        std::fs::create_dir_all(&src).unwrap();
+        std::fs::write(outer.path().join("go.mod"), "module example.com/outer\n").unwrap();
+        std::fs::write(
+            outer.path().join("tsconfig.json"),
+            r#"{"compilerOptions": {"baseUrl": "."}}"#,
+        )
+        .unwrap();
+
+        let mut projects = ProjectConfigs::default();
+        assert!(projects.go_module(&src).is_some());
+        projects.set_roots(vec![repo.clone()]);
+        assert!(projects.go_module(&src).is_none());
+        assert!(projects.ts_config(&src).is_none());
+
+        // Manifests at the root itself still count
+        std::fs::write(repo.join("go.mod"), "module example.com/repo\n").unwrap();
+        projects.invalidate(&repo.join("go.mod"));
+        assert_eq!(projects.go_module(&src).unwrap().path, "example.com/repo");
+    }
+}
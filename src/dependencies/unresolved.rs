@@ -0,0 +1,286 @@
+//! Imports that matched no indexed file.
+//!
+//! An unresolved import either names a third-party module (`react`, `numpy`,
+//! `serde`, `github.com/pkg/errors`) or can only refer to a project file —
+//! relative paths, `crate::` paths, `mod` declarations, `require_relative` —
+//! in which case a resolver gap or a missing file is the cause.
+
+use crate::symbols::extractor::ImportType;
+use rustc_hash::FxHashMap;
+use serde::{Deserialize, Serialize};
+
+/// An import left unresolved after all files were indexed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UnresolvedImport {
+    /// Import path as written in the source
+    pub path: String,
+    /// Line of the import statement (0-based)
+    pub line: usize,
+    /// Third-party module the import refers to; None when the import can
+    /// only be project-local
+    pub module: Option<String>,
+}
+
+impl UnresolvedImport {
+    pub fn new(path: String, line: usize, import_type: &ImportType) -> Self {
+        let module = external_module(&path, import_type);
+        Self { path, line, module }
+    }
+}
+
+/// Top-level module named by a non-local import: the npm package (with its
+/// scope), the first Python/Rust/C#/PHP segment, the Go module (`host/org/repo`
+/// or the standard library package), the first two Java segments, or the first
+/// directory of a C/C++ include
+pub fn external_module(path: &str, import_type: &ImportType) -> Option<String> {
+    let path = path.trim();
+    let module = match import_type {
+        ImportType::Rust => {
+            let path = path.split(" as ").next()?;
+            let segments: Vec<&str> = path.split("::").map(str::trim).collect();
+            // A single segment is a `mod name;` declaration
+            if segments.len() < 2 || ["crate", "self", "super"].contains(&segments[0]) {
+                return None;
+            }
+            segments[0].to_string()
+        }
+        ImportType::Python => {
+            if path.starts_with('.') {
+                return None;
+            }
+            path.split('.').next()?.to_string()
+        }
+        ImportType::JavaScript => {
+            if path.starts_with('.') || path.starts_with('/') {
+                return None;
+            }
+            let mut segments = path.split('/');
+            let first = segments.next()?;
+            match segments.next() {
+                Some(name) if first.starts_with('@') => format!("{}/{}", first, name),
+                _ => first.to_string(),
+            }
+        }
+        ImportType::Go => {
+            let segments: Vec<&str> = path.split('/').collect();
+            if segments[0].contains('.') {
+                segments[..segments.len().min(3)].join("/")
+            } else {
+                path.to_string()
+            }
+        }
+        ImportType::Java => {
+            let segments: Vec<&str> = path.split('.').filter(|s| *s != "*").collect();
+            segments[..segments.len().min(2)].join(".")
+        }
+        ImportType::Include => {
+            if path.starts_with('.') {
+                return None;
+            }
+            path.split('/').next()?.to_string()
+        }
+        ImportType::CSharp => path.split('.').next()?.to_string(),
+        // Only `require_relative` is extracted
+        ImportType::Ruby => return None,
+        ImportType::Php => {
+            if path.ends_with(".php") || path.contains('/') {
+                return None;
+            }
+            path.trim_start_matches('\\')
+                .split('\\')
+                .next()?
+                .to_string()
+        }
+    };
+    (!module.is_empty()).then_some(module)
+}
+
+/// Unresolved imports of one language
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UnresolvedLanguageCount {
+    /// Language of the importing files
+    pub language: String,
+    pub imports: usize,
+    /// Imports that can only be project-local
+    pub local: usize,
+}
+
+/// A third-party module and how many files import it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExternalModuleCount {
+    pub module: String,
+    /// Files importing the module
+    pub files: usize,
+}
+
+/// Summary of unresolved imports
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UnresolvedImportStats {
+    /// Imports that matched no indexed file
+    pub total: usize,
+    /// Files with at least one unresolved import
+    pub files: usize,
+    /// Imports that can only be project-local (resolver gaps or missing files)
+    pub local: usize,
+    /// Counts per language of the importing file, most imports first
+    pub by_language: Vec<UnresolvedLanguageCount>,
+    /// Third-party modules, most importing files first
+    pub external_modules: Vec<ExternalModuleCount>,
+}
+
+impl UnresolvedImportStats {
+    /// Build stats from each file's language name and unresolved imports,
+    /// listing at most `max_modules` external modules
+    pub fn from_files<'a>(
+        files: impl IntoIterator<Item = (&'a str, &'a [UnresolvedImport])>,
+        max_modules: usize,
+    ) -> Self {
+        let mut stats = Self::default();
+        let mut languages: FxHashMap<&str, (usize, usize)> = FxHashMap::default();
+        let mut modules: FxHashMap<&str, usize> = FxHashMap::default();
+        for (language, imports) in files {
+            if imports.is_empty() {
+                continue;
+            }
+            stats.files += 1;
+            stats.total += imports.len();
+            let local = imports.iter().filter(|i| i.module.is_none()).count();
+            stats.local += local;
+            let counts = languages.entry(language).or_default();
+            counts.0 += imports.len();
+            counts.1 += local;
+
+            let mut file_modules: Vec<&str> =
+                imports.iter().filter_map(|i| i.module.as_deref()).collect();
+            file_modules.sort_unstable();
+            file_modules.dedup();
+            for module in file_modules {
+                *modules.entry(module).or_default() += 1;
+            }
+        }
+
+        stats.by_language = languages
+            .into_iter()
+            .map(|(language, (imports, local))| UnresolvedLanguageCount {
+                language: language.to_string(),
+                imports,
+                local,
+            })
+            .collect();
+        stats.by_language.sort_by(|a, b| {
+            b.imports
+                .cmp(&a.imports)
+                .then_with(|| a.language.cmp(&b.language))
+        });
+        stats.external_modules = modules
+            .into_iter()
+            .map(|(module, files)| ExternalModuleCount {
+                module: module.to_string(),
+                files,
+            })
+            .collect();
+        stats
+            .external_modules
+            .sort_by(|a, b| b.files.cmp(&a.files).then_with(|| a.module.cmp(&b.module)));
+        stats.external_modules.truncate(max_modules);
+        stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_external_module_per_language() {
+        let cases = [
+            ("serde::Deserialize", ImportType::Rust, Some("serde")),
+            ("crate::config::Config", ImportType::Rust, None),
+            ("parser", ImportType::Rust, None),
+            ("numpy.linalg", ImportType::Python, Some("numpy")),
+            (".models", ImportType::Python, None),
+            (
+                "@angular/core/testing",
+                ImportType::JavaScript,
+                Some("@angular/core"),
+            ),
+            ("lodash/fp", ImportType::JavaScript, Some("lodash")),
+            ("./missing", ImportType::JavaScript, None),
+            (
+                "github.com/pkg/errors/sub",
+                ImportType::Go,
+                Some("github.com/pkg/errors"),
+            ),
+            ("net/http", ImportType::Go, Some("net/http")),
+            (
+                "org.junit.jupiter.api.*",
+                ImportType::Java,
+                Some("org.junit"),
+            ),
+            ("boost/asio.hpp", ImportType::Include, Some("boost")),
+            ("../local.h", ImportType::Include, None),
+            ("Newtonsoft.Json", ImportType::CSharp, Some("Newtonsoft")),
+            ("helpers", ImportType::Ruby, None),
+            (
+                "\\Illuminate\\Support\\Str",
+                ImportType::Php,
+                Some("Illuminate"),
+            ),
+            ("lib/boot.php", ImportType::Php, None),
+        ];
+        for (path, import_type, expected) in cases {
+            assert_eq!(
+                external_module(path, &import_type).as_deref(),
+                expected,
+                "{}",
+                path
+            );
+        }
+    }
+
+    #[test]
+    fn test_stats_count_modules_once_per_file() {
+        let import =
+            |path: &str, import_type| UnresolvedImport::new(path.to_string(), 0, &import_type);
+        let a = vec![
+            import("react", ImportType::JavaScript),
+            import("react/jsx-runtime", ImportType::JavaScript),
+            import("./gone", ImportType::JavaScript),
+        ];
+        let b = vec![import("react", ImportType::JavaScript)];
+        let c = vec![import("numpy", ImportType::Python)];
+        let stats = UnresolvedImportStats::from_files(
+            [
+                ("TypeScript", a.as_slice()),
+                ("TypeScript", b.as_slice()),
+                ("Python", c.as_slice()),
+            ],
+            10,
+        );
+
+        assert_eq!(stats.total, 5);
+        assert_eq!(stats.files, 3);
+        assert_eq!(stats.local, 1);
+        assert_eq!(
+            stats.by_language[0],
+            UnresolvedLanguageCount {
+                language: "TypeScript".to_string(),
+                imports: 4,
+                local: 1
+            }
+        );
+        assert_eq!(
+            stats.external_modules,
+            vec![
+                ExternalModuleCount {
+                    module: "react".to_string(),
+                    files: 2
+                },
+                ExternalModuleCount {
+                    module: "numpy".to_string(),
+                    files: 1
+                },
+            ]
+        );
+    }
+}
@@ -3,6 +3,8 @@
 //! Provides health checks, self-tests, and rich diagnostics information
 //! for both keyword and semantic search servers.
 
+use crate::dependencies::{CycleStats, UnresolvedImportStats};
+use crate::search::DuplicateStats;
 use serde::{Deserialize, Serialize};
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
@@ -154,6 +156,14 @@ pub struct ExtensionBreakdown {
     pub total_bytes: u64,
 }
 
+/// Detected language breakdown
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LanguageBreakdown {
+    pub language: String,
+    pub count: usize,
+    pub total_bytes: u64,
+}
+
 /// Common diagnostics query parameters
 #[derive(Debug, Deserialize)]
 pub struct DiagnosticsQuery {
@@ -209,8 +219,24 @@ pub struct KeywordIndexDiagnostics {
     pub num_trigrams: usize,
     /// Number of dependency edges (import relationships)
     pub dependency_edges: usize,
+    /// Imports that matched several files and were left unlinked
+    #[serde(default)]
+    pub ambiguous_imports: usize,
+    /// Groups of byte-identical files
+    #[serde(default)]
+    pub duplicates: DuplicateStats,
+    /// Import cycles, with the largest ones listed
+    #[serde(default)]
+    pub import_cycles: CycleStats,
+    /// Imports that matched no indexed file, per language, and the
+    /// third-party modules imported by the most files
+    #[serde(default)]
+    pub unresolved_imports: UnresolvedImportStats,
     /// Breakdown by file extension
     pub files_by_extension: Vec<ExtensionBreakdown>,
+    /// Breakdown by detected language (files of unknown language are `Other`)
+    #[serde(default)]
+    pub files_by_language: Vec<LanguageBreakdown>,
     /// Sample of indexed file paths (for verification)
     pub sample_files: Vec<String>,
 }
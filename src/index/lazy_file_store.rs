@@ -4,6 +4,7 @@
 //! but only memory-maps them when they are first accessed. This dramatically
 //! reduces startup time when loading from a persisted index.
 
+use crate::symbols::Language;
 use anyhow::{Context, Result};
 use memmap2::Mmap;
 use std::borrow::Cow;
@@ -298,6 +299,10 @@ pub struct LazyFileStore {
     mmap_safe_limit: Option<usize>,
     /// Guard so the mmap-limit warning is only emitted once
     mmap_limit_warned: AtomicBool,
+    /// Content hash per file ID (0 = unknown), used to detect duplicate files
+    content_hashes: Vec<u64>,
+    /// Detected language per file ID (None = unknown)
+    languages: Vec<Option<Language>>,
 }
 
 impl LazyFileStore {
@@ -320,6 +325,8 @@ impl LazyFileStore {
             total_content_bytes: AtomicU64::new(0),
             mmap_safe_limit,
             mmap_limit_warned: AtomicBool::new(false),
+            content_hashes: Vec::new(),
+            languages: Vec::new(),
         }
     }
 
@@ -516,6 +523,43 @@ impl LazyFileStore {
         self.files.iter().map(|f| f.path.clone()).collect()
     }
 
+    /// Record the content hash of a file (see `search::duplicates::content_hash`)
+    pub fn set_content_hash(&mut self, id: u32, hash: u64) {
+        let idx = id as usize;
+        if idx >= self.files.len() {
+            return;
+        }
+        if self.content_hashes.len() <= idx {
+            self.content_hashes.resize(idx + 1, 0);
+        }
+        self.content_hashes[idx] = hash;
+    }
+
+    /// Get the content hash of a file, if known
+    pub fn get_content_hash(&self, id: u32) -> Option<u64> {
+        self.content_hashes
+            .get(id as usize)
+            .copied()
+            .filter(|&hash| hash != 0)
+    }
+
+    /// Record the detected language of a file (see `symbols::Language::detect`)
+    pub fn set_language(&mut self, id: u32, language: Option<Language>) {
+        let idx = id as usize;
+        if idx >= self.files.len() {
+            return;
+        }
+        if self.languages.len() <= idx {
+            self.languages.resize(idx + 1, None);
+        }
+        self.languages[idx] = language;
+    }
+
+    /// Get the detected language of a file, if known
+    pub fn get_language(&self, id: u32) -> Option<Language> {
+        self.languages.get(id as usize).copied().flatten()
+    }
+
     /// Get the number of files that have been actually mapped
     pub fn mapped_count(&self) -> usize {
         self.mapped_count.load(Ordering::Relaxed)
@@ -539,6 +583,8 @@ impl LazyFileStore {
     pub fn reserve(&mut self, additional: usize) {
         self.files.reserve(additional);
         self.path_to_id.reserve(additional);
+        self.content_hashes.reserve(additional);
+        self.languages.reserve(additional);
     }
 }
 
@@ -581,6 +627,8 @@ impl LazyFileStore {
             total_content_bytes: AtomicU64::new(0),
             mmap_safe_limit,
             mmap_limit_warned: AtomicBool::new(false),
+            content_hashes: Vec::new(),
+            languages: Vec::new(),
         }
     }
 }
@@ -864,7 +912,7 @@ mod tests {
         // (we access internal test API via LazyMappedFile::with_mmap_failure)
         let fallback_files: Vec<LazyMappedFile> = paths
             .iter()
-            .map(|p| LazyMappedFile::with_mmap_failure(p))
+            .map(LazyMappedFile::with_mmap_failure)
             .collect();
 
         // Access all files to populate the Mutex caches
@@ -914,4 +962,21 @@ mod tests {
         // Content still accessible
         assert_eq!(lazy.as_str().unwrap(), "mmap content");
     }
+
+    #[test]
+    fn test_content_hash_roundtrip() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let file_path = temp_dir.path().join("a.txt");
+        std::fs::write(&file_path, "content").expect("Failed to write test file");
+
+        let mut store = LazyFileStore::new();
+        let id = store.add_file(&file_path).unwrap();
+        assert_eq!(store.get_content_hash(id), None);
+
+        store.set_content_hash(id, 7);
+        assert_eq!(store.get_content_hash(id), Some(7));
+        // Unknown IDs are ignored
+        store.set_content_hash(id + 1, 9);
+        assert_eq!(store.get_content_hash(id + 1), None);
+    }
 }
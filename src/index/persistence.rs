@@ -12,7 +12,10 @@ use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 
 use super::trigram::Trigram;
+use crate::dependencies::UnresolvedImport;
+use crate::search::references::PackedReference;
 use crate::symbols::extractor::Symbol;
+use crate::symbols::Language;
 use crate::utils::normalize_path_for_comparison;
 
 /// Serializable representation of the trigram index
@@ -46,6 +49,12 @@ pub struct PersistedFileMetadata {
     /// The base path from config that this file belongs to
     #[serde(default)]
     pub source_base_path: Option<String>,
+    /// Content hash for duplicate detection (0 = unknown)
+    #[serde(default)]
+    pub content_hash: u64,
+    /// Language detected at index time
+    #[serde(default)]
+    pub language: Option<Language>,
 }
 
 /// Complete persisted index state
@@ -70,11 +79,24 @@ pub struct PersistedIndex {
     /// where indices are positions in the `files` Vec
     #[serde(default)]
     pub dependency_edges: Vec<(u32, u32)>,
+    /// Lines (0-based) of the import statements behind each dependency edge
+    /// (parallel to `dependency_edges`)
+    #[serde(default)]
+    pub dependency_edge_lines: Vec<Vec<u32>>,
+    /// Per-file imports that matched no indexed file (parallel to `files`)
+    #[serde(default)]
+    pub unresolved_imports: Vec<Vec<UnresolvedImport>>,
+    /// Interned identifier names used by `references`
+    #[serde(default)]
+    pub reference_names: Vec<String>,
+    /// Per-file identifier references (parallel to `files`)
+    #[serde(default)]
+    pub references: Vec<Vec<PackedReference>>,
 }
 
 impl PersistedIndex {
     /// Current persistence format version (bump this when format changes)
-    pub const CURRENT_VERSION: u32 = 3;
+    pub const CURRENT_VERSION: u32 = 12;
 
     /// Create a new persisted index from the current state
     pub fn new(
@@ -103,6 +125,10 @@ impl PersistedIndex {
             },
             symbols,
             dependency_edges,
+            dependency_edge_lines: Vec::new(),
+            unresolved_imports: Vec::new(),
+            reference_names: Vec::new(),
+            references: Vec::new(),
         })
     }
 
@@ -330,6 +356,8 @@ mod tests {
             mtime: 12345,
             size: 100,
             source_base_path: Some("/test".to_string()),
+            content_hash: 42,
+            language: Some(Language::Python),
         }];
 
         let persisted = PersistedIndex::new(
@@ -351,6 +379,8 @@ mod tests {
         assert_eq!(loaded.version, PersistedIndex::CURRENT_VERSION);
         assert_eq!(loaded.files.len(), 1);
         assert_eq!(loaded.files[0].path, PathBuf::from("/test/file.rs"));
+        assert_eq!(loaded.files[0].content_hash, 42);
+        assert_eq!(loaded.files[0].language, Some(Language::Python));
 
         // Restore trigram index
         let restored = loaded
@@ -4,8 +4,8 @@ use fast_code_search::config::Config;
 use fast_code_search::diagnostics;
 use fast_code_search::search::{
     create_progress_broadcaster, run_background_indexer, save_on_watcher_update,
-    BackgroundIndexerConfig, FileChange, FileWatcher, IndexingProgress, ProgressBroadcaster,
-    SharedIndexingProgress, WatcherConfig,
+    BackgroundIndexerConfig, FeedbackStore, FileChange, FileWatcher, IndexingProgress,
+    ProgressBroadcaster, SharedIndexingProgress, WatcherConfig,
 };
 use fast_code_search::server;
 use fast_code_search::telemetry;
@@ -117,13 +117,30 @@ async fn main() -> Result<()> {
         std::thread::sleep(std::time::Duration::from_secs(3));
     }
 
+    if let Some(ref dir) = config.indexer.symbol_queries_dir {
+        fast_code_search::symbols::init_queries(Some(std::path::Path::new(dir)))?;
+        info!(dir = %dir, "Loaded custom symbol queries");
+    }
+
     let addr = config.server.address.parse()?;
 
     // Create shared engine (empty initially, will be indexed in background)
     // Using RwLock allows concurrent read access during searches while only blocking for writes (indexing)
-    let shared_engine = std::sync::Arc::new(std::sync::RwLock::new(
-        fast_code_search::search::SearchEngine::new(),
-    ));
+    let mut engine = fast_code_search::search::SearchEngine::new();
+    engine.ranking = config.ranking.clone();
+    if config.feedback.enabled {
+        let store = match config.feedback.store_path {
+            Some(ref path) => FeedbackStore::open(path, config.feedback.half_life_days)?,
+            None => FeedbackStore::in_memory(config.feedback.half_life_days),
+        };
+        info!(
+            store_path = ?config.feedback.store_path,
+            events = store.event_count(),
+            "Click feedback enabled"
+        );
+        engine.feedback = Some(std::sync::Arc::new(store));
+    }
+    let shared_engine = std::sync::Arc::new(std::sync::RwLock::new(engine));
 
     // Create shared indexing progress state for UI visibility
     let shared_progress: SharedIndexingProgress =
@@ -245,10 +262,11 @@ async fn main() -> Result<()> {
                                     );
                                 }
                             }
-                            Some(FileChange::Renamed { from: _, to }) => {
+                            Some(FileChange::Renamed { from, to }) => {
                                 tracing::debug!(path = %to.display(), "File renamed, indexing new path");
                                 let mut update_ok = false;
                                 if let Ok(mut engine) = watch_engine.write() {
+                                    engine.invalidate_project_config(&from);
                                     match engine.update_file(&to) {
                                         Ok(()) => update_ok = true,
                                         Err(e) => tracing::warn!(
@@ -269,7 +287,10 @@ async fn main() -> Result<()> {
                             }
                             Some(FileChange::Deleted(path)) => {
                                 // Engine does not yet support file removal from index;
-                                // log for observability and no-op.
+                                // only cached manifests read from it are dropped.
+                                if let Ok(engine) = watch_engine.read() {
+                                    engine.invalidate_project_config(&path);
+                                }
                                 tracing::debug!(
                                     path = %path.display(),
                                     "File deleted (removal from index not yet supported)"
@@ -182,6 +182,11 @@ pub fn run(config: BackgroundIndexerConfig) {
         for path in &indexer_config.paths {
             engine.add_root_path(path);
         }
+        engine.set_include_dirs(&indexer_config.include_dirs);
+        engine.set_package_roots(&indexer_config.package_roots);
+        if let Err(e) = engine.set_entrypoints(&indexer_config.entrypoints) {
+            tracing::warn!(error = %e, "Invalid entrypoints pattern, keeping the defaults");
+        }
     }
 
     // Log active persistence settings so the user knows what to expect
@@ -280,6 +285,9 @@ pub fn run(config: BackgroundIndexerConfig) {
     // Final import resolution
     finalize_imports(&index_engine, &index_progress, &index_progress_tx);
 
+    // Git commit times refine the recency signal once history has been read
+    spawn_git_recency(&index_engine);
+
     // Log completion stats
     let elapsed = total_start.elapsed();
     log_completion_stats(total_indexed, final_discovered, elapsed, &index_engine);
@@ -906,6 +914,30 @@ fn finalize_imports(
     );
 }
 
+/// Read git commit times for the recency signal on a separate thread.
+///
+/// The file list is collected under a read lock and history is read without
+/// any lock; only applying the result takes the write lock. Until then files
+/// rank by their mtime. No-op unless recency uses the git source.
+fn spawn_git_recency(index_engine: &Arc<RwLock<SearchEngine>>) {
+    let Some(request) = index_engine.read().ok().and_then(|e| e.git_recency()) else {
+        return;
+    };
+    let engine = Arc::clone(index_engine);
+    let spawned = std::thread::Builder::new()
+        .name("git-recency".to_string())
+        .spawn(move || {
+            let commit_times = request.resolve();
+            match engine.write() {
+                Ok(mut engine) => engine.apply_commit_times(commit_times),
+                Err(e) => tracing::error!(error = %e, "Failed to apply git commit times"),
+            }
+        });
+    if let Err(e) = spawned {
+        tracing::warn!(error = %e, "Failed to start git recency thread");
+    }
+}
+
 /// Log completion statistics.
 fn log_completion_stats(
     total_indexed: usize,
@@ -0,0 +1,258 @@
+//! Dead code candidates.
+//!
+//! A file is a candidate when no indexed file imports it and it is not an
+//! entry point (a binary, a package index, a test, ...). Only languages whose
+//! files import each other one by one are checked: Go, Java, C#, C and C++
+//! link files by package, namespace or compilation unit, so a file without
+//! importers is normal there.
+//!
+//! A symbol is a candidate when it is an exported top-level definition and its
+//! name appears in no other file: not in the reference index, and not in any
+//! other file's content once the trigram candidates are verified.
+
+use crate::symbols::{Language, Symbol, SymbolType};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Entry-point patterns used when none are configured. Matched like search
+/// `include` globs against display paths.
+pub const DEFAULT_ENTRYPOINTS: &[&str] = &[
+    "main.rs",
+    "lib.rs",
+    "build.rs",
+    "bin/*.rs",
+    "examples/**",
+    "benches/**",
+    "index.{js,jsx,mjs,cjs,ts,tsx}",
+    "main.{js,mjs,ts}",
+    "*.config.{js,mjs,cjs,ts}",
+    "__main__.py",
+    "__init__.py",
+    "setup.py",
+    "manage.py",
+    "conftest.py",
+    "tests/**",
+    "test/**",
+    "__tests__/**",
+    "spec/**",
+    "test_*.py",
+    "*_test.py",
+    "*.test.*",
+    "*.spec.*",
+    "*_spec.rb",
+];
+
+/// [`DEFAULT_ENTRYPOINTS`] as owned patterns
+pub fn default_entrypoints() -> Vec<String> {
+    DEFAULT_ENTRYPOINTS.iter().map(|p| p.to_string()).collect()
+}
+
+/// Whether dead files of `language` can be told apart from live ones by
+/// their importers
+pub fn checks_importers(language: Language) -> bool {
+    matches!(
+        language,
+        Language::Rust
+            | Language::Python
+            | Language::JavaScript
+            | Language::TypeScript
+            | Language::Ruby
+            | Language::Php
+    )
+}
+
+/// Whether `symbol` is visible outside its file. `source` is the file's
+/// content and `line` the source line of the definition, used for `export`
+/// in JavaScript/TypeScript.
+pub fn is_exported(language: Language, symbol: &Symbol, source: &str, line: &str) -> bool {
+    if !symbol.is_definition || !symbol.is_top_level() {
+        return false;
+    }
+    if matches!(symbol.symbol_type, SymbolType::FileName) {
+        return false;
+    }
+    let signature = symbol.signature(source);
+    let signature = signature.as_deref().unwrap_or(line).trim_start();
+    match language {
+        Language::Rust => signature.starts_with("pub"),
+        Language::JavaScript | Language::TypeScript => line.trim_start().starts_with("export"),
+        Language::Go => symbol.name.starts_with(|c: char| c.is_ascii_uppercase()),
+        Language::Java | Language::CSharp => signature.split_whitespace().any(|w| w == "public"),
+        Language::Python | Language::Ruby | Language::Php => !symbol.name.starts_with('_'),
+        _ => false,
+    }
+}
+
+/// A definition whose name appears in no other file
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct UnusedSymbol {
+    pub file: String,
+    pub name: String,
+    pub kind: SymbolType,
+    /// Line of the definition (1-based)
+    pub line: usize,
+}
+
+/// Dead code candidates of one directory
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct DeadCodeDirectory {
+    pub directory: String,
+    /// Files nothing imports
+    pub files: Vec<String>,
+    /// Exported symbols nothing references
+    pub symbols: Vec<UnusedSymbol>,
+}
+
+/// Dead code candidates grouped by directory
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct DeadCodeReport {
+    /// Files whose importers were checked (entry points and languages linked
+    /// by package excluded)
+    pub files_checked: usize,
+    pub unused_files: usize,
+    pub unused_symbols: usize,
+    /// Directories with the most candidates first
+    pub directories: Vec<DeadCodeDirectory>,
+}
+
+impl DeadCodeReport {
+    /// Group candidates by the directory of their display path, keeping the
+    /// `max_directories` directories with the most candidates
+    pub fn group(
+        files_checked: usize,
+        files: Vec<String>,
+        symbols: Vec<UnusedSymbol>,
+        max_directories: usize,
+    ) -> Self {
+        let directory = |path: &str| {
+            path.rsplit_once('/')
+                .map_or_else(String::new, |(dir, _)| dir.to_string())
+        };
+        let mut report = Self {
+            files_checked,
+            unused_files: files.len(),
+            unused_symbols: symbols.len(),
+            directories: Vec::new(),
+        };
+        let mut groups: BTreeMap<String, DeadCodeDirectory> = BTreeMap::new();
+        for file in files {
+            groups.entry(directory(&file)).or_default().files.push(file);
+        }
+        for symbol in symbols {
+            groups
+                .entry(directory(&symbol.file))
+                .or_default()
+                .symbols
+                .push(symbol);
+        }
+
+        report.directories = groups
+            .into_iter()
+            .map(|(name, mut group)| {
+                group.directory = name;
+                group.files.sort();
+                group
+                    .symbols
+                    .sort_by(|a, b| a.file.cmp(&b.file).then(a.line.cmp(&b.line)));
+                group
+            })
+            .collect();
+        // Stable sort keeps directories with equal counts in path order
+        report
+            .directories
+            .sort_by_key(|group| std::cmp::Reverse(group.files.len() + group.symbols.len()));
+        report.directories.truncate(max_directories);
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::symbols::SymbolExtractor;
+    use std::path::Path;
+
+    /// Whether each definition of `source` is exported, by name
+    fn exported(file: &str, source: &str) -> Vec<(String, bool)> {
+        let extractor = SymbolExtractor::new(Path::new(file));
+        let language = Language::detect(Path::new(file), None).unwrap();
+        let lines: Vec<&str> = source.lines().collect();
+        extractor
+            .extract(source)
+            .unwrap()
+            .iter()
+            .map(|s| {
+                let exported = is_exported(language, s, source, lines[s.line]);
+                (s.name.clone(), exported)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_is_exported() {
+        let rust = "pub fn load() -> Config {}\nfn helper() {}\nstruct Store;\nimpl Store {\n    pub fn save(&self) {}\n}\n";
+        assert_eq!(
+            exported("lib.rs", rust),
+            vec![
+                ("load".to_string(), true),
+                ("helper".to_string(), false),
+                ("Store".to_string(), false),
+                ("Store".to_string(), false),
+                // Public, but a method rather than a top-level definition
+                ("save".to_string(), false),
+            ]
+        );
+
+        let ts = "export function render() {}\nfunction draw() {}\n";
+        assert_eq!(
+            exported("view.ts", ts),
+            vec![("render".to_string(), true), ("draw".to_string(), false)]
+        );
+
+        let python = "def load():\n    pass\n\ndef _load():\n    pass\n";
+        assert_eq!(
+            exported("config.py", python),
+            vec![("load".to_string(), true), ("_load".to_string(), false)]
+        );
+
+        let go = "package main\n\nfunc Load() {}\n\nfunc load() {}\n";
+        assert_eq!(
+            exported("main.go", go),
+            vec![("Load".to_string(), true), ("load".to_string(), false)]
+        );
+    }
+
+    #[test]
+    fn test_group_by_directory() {
+        let unused = |file: &str, name: &str, line| UnusedSymbol {
+            file: file.to_string(),
+            name: name.to_string(),
+            kind: SymbolType::Function,
+            line,
+        };
+        let report = DeadCodeReport::group(
+            10,
+            vec!["repo/src/old.rs".to_string(), "repo/lib/a.py".to_string()],
+            vec![
+                unused("repo/src/util.rs", "unused_b", 9),
+                unused("repo/src/util.rs", "unused_a", 2),
+            ],
+            10,
+        );
+
+        assert_eq!(report.unused_files, 2);
+        assert_eq!(report.unused_symbols, 2);
+        let directories: Vec<&str> = report
+            .directories
+            .iter()
+            .map(|d| d.directory.as_str())
+            .collect();
+        assert_eq!(directories, vec!["repo/src", "repo/lib"]);
+        assert_eq!(report.directories[0].files, vec!["repo/src/old.rs"]);
+        assert_eq!(report.directories[0].symbols[0].name, "unused_a");
+
+        let truncated = DeadCodeReport::group(10, vec!["repo/lib/a.py".to_string()], vec![], 0);
+        assert!(truncated.directories.is_empty());
+        assert_eq!(truncated.unused_files, 1);
+    }
+}
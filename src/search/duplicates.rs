@@ -0,0 +1,140 @@
+//! Detection of byte-identical files.
+//!
+//! Vendored dependencies, generated code and copy-pasted configs often put the
+//! same file under many paths, which floods search results with identical
+//! hits. Every file's content is hashed at index time; files sharing a hash
+//! form a duplicate cluster. Searches can collapse a cluster into a single
+//! result that lists the other paths, and diagnostics report how much of the
+//! index is duplicated.
+
+use rustc_hash::FxHashMap;
+use serde::{Deserialize, Serialize};
+
+/// Number of clusters listed in [`DuplicateStats::largest_clusters`]
+const TOP_CLUSTERS: usize = 10;
+
+/// 64-bit content hash (first half of the MD5 digest).
+///
+/// Returns 0 — the "unknown" marker used by the file store — for empty
+/// content, so empty files are never treated as duplicates of each other.
+pub fn content_hash(content: &[u8]) -> u64 {
+    if content.is_empty() {
+        return 0;
+    }
+    let digest = md5::compute(content);
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&digest.0[..8]);
+    u64::from_le_bytes(bytes).max(1)
+}
+
+/// Group documents by content hash.
+///
+/// Only groups with at least two members are returned. Members are sorted by
+/// document ID and clusters are ordered by their first member, so the result
+/// is deterministic. A hash of 0 means "unknown" and is ignored.
+pub fn cluster_by_hash(hashes: impl IntoIterator<Item = (u32, u64)>) -> Vec<Vec<u32>> {
+    let mut groups: FxHashMap<u64, Vec<u32>> = FxHashMap::default();
+    for (doc_id, hash) in hashes {
+        if hash != 0 {
+            groups.entry(hash).or_default().push(doc_id);
+        }
+    }
+    let mut clusters: Vec<Vec<u32>> = groups
+        .into_values()
+        .filter(|members| members.len() > 1)
+        .map(|mut members| {
+            members.sort_unstable();
+            members
+        })
+        .collect();
+    clusters.sort_unstable_by_key(|members| members[0]);
+    clusters
+}
+
+/// A single duplicate cluster, as reported by diagnostics
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DuplicateCluster {
+    /// Size of one copy in bytes
+    pub size_bytes: u64,
+    /// Display paths of all copies
+    pub paths: Vec<String>,
+}
+
+/// Summary of duplicate content in the index
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DuplicateStats {
+    /// Number of groups of byte-identical files
+    pub clusters: usize,
+    /// Files that are a copy of another file (cluster size minus one, summed)
+    pub duplicate_files: usize,
+    /// Bytes that would be saved by keeping one copy per cluster
+    pub wasted_bytes: u64,
+    /// Clusters wasting the most bytes, largest first
+    pub largest_clusters: Vec<DuplicateCluster>,
+}
+
+impl DuplicateStats {
+    /// Build stats from clusters given as `(size of one copy, display paths)`
+    pub fn from_clusters(clusters: impl IntoIterator<Item = (u64, Vec<String>)>) -> Self {
+        let mut stats = DuplicateStats::default();
+        let mut all: Vec<DuplicateCluster> = Vec::new();
+        for (size_bytes, paths) in clusters {
+            let copies = paths.len().saturating_sub(1);
+            stats.clusters += 1;
+            stats.duplicate_files += copies;
+            stats.wasted_bytes += size_bytes * copies as u64;
+            all.push(DuplicateCluster { size_bytes, paths });
+        }
+        all.sort_by(|a, b| {
+            let wasted = |c: &DuplicateCluster| c.size_bytes * c.paths.len() as u64;
+            wasted(b)
+                .cmp(&wasted(a))
+                .then_with(|| a.paths.cmp(&b.paths))
+        });
+        all.truncate(TOP_CLUSTERS);
+        stats.largest_clusters = all;
+        stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_content_hash() {
+        assert_eq!(content_hash(b""), 0);
+        assert_eq!(content_hash(b"fn main() {}"), content_hash(b"fn main() {}"));
+        assert_ne!(
+            content_hash(b"fn main() {}"),
+            content_hash(b"fn main() { }")
+        );
+        assert_ne!(content_hash(b"x"), 0);
+    }
+
+    #[test]
+    fn test_cluster_by_hash() {
+        let clusters =
+            cluster_by_hash(vec![(4, 7), (1, 9), (2, 7), (3, 5), (0, 9), (5, 0), (6, 0)]);
+        assert_eq!(clusters, vec![vec![0, 1], vec![2, 4]]);
+    }
+
+    #[test]
+    fn test_duplicate_stats() {
+        let stats = DuplicateStats::from_clusters(vec![
+            (10, vec!["a/x.js".to_string(), "b/x.js".to_string()]),
+            (
+                100,
+                vec![
+                    "a/lib.js".to_string(),
+                    "b/lib.js".to_string(),
+                    "c/lib.js".to_string(),
+                ],
+            ),
+        ]);
+        assert_eq!(stats.clusters, 2);
+        assert_eq!(stats.duplicate_files, 3);
+        assert_eq!(stats.wasted_bytes, 210);
+        assert_eq!(stats.largest_clusters[0].size_bytes, 100);
+    }
+}
@@ -1,14 +1,35 @@
-use crate::dependencies::DependencyIndex;
+use crate::config::{RankingConfig, RecencySource};
+use crate::dependencies::{
+    AmbiguousImport, CycleEdge, CycleStats, DependencyDirection, DependencyGraph, DependencyIndex,
+    DependencyLayers, GraphLevel, ImpactAnalysis, ImportCycle, ImportResolution, PackageInfo,
+    PackageSummary, UnresolvedImport, UnresolvedImportStats,
+};
 use crate::index::{extract_unique_trigrams, LazyFileStore, Trigram, TrigramIndex};
+use crate::search::dead_code::{self, DeadCodeReport, UnusedSymbol};
+use crate::search::duplicates::{self, DuplicateStats};
+use crate::search::explain::{
+    BaseScoreBreakdown, FileBoostBreakdown, LineScoreFactors, RankingExplanation, ScoreExplanation,
+};
+use crate::search::facets::{FacetAccumulator, MatchCounts, QueryKind};
+use crate::search::feedback::{FeedbackEvent, FeedbackStore};
+use crate::search::fuzzy::FuzzyPattern;
 use crate::search::path_filter::PathFilter;
+use crate::search::recency;
+use crate::search::references::ReferenceIndex;
 use crate::search::regex_search::RegexAnalysis;
-use crate::symbols::{Symbol, SymbolExtractor, SymbolType};
+use crate::search::symbol_names::{FuzzyNameMatches, SymbolNameIndex};
+use crate::search::symbol_query::{SymbolField, SymbolQuery};
+use crate::symbols::{
+    build_outline, ImportStatement, Language, OutlineNode, Symbol, SymbolExtractor,
+    SymbolReference, SymbolType,
+};
 use anyhow::Result;
 use memchr::memmem;
 use rayon::prelude::*;
 use regex::Regex;
 use rustc_hash::{FxHashMap, FxHashSet};
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use tracing::warn;
 
 /// Case-insensitive substring search without heap allocation.
@@ -19,7 +40,7 @@ use tracing::warn;
 /// CJK) are compared byte-for-byte without case folding. This is acceptable for
 /// code identifiers but won't handle natural language in comments.
 #[inline]
-fn contains_case_insensitive(haystack: &str, needle_lower: &str) -> bool {
+pub(super) fn contains_case_insensitive(haystack: &str, needle_lower: &str) -> bool {
     if needle_lower.is_empty() {
         return true;
     }
@@ -158,6 +179,54 @@ fn truncate_around_match(line: &str, match_start: usize, match_end: usize) -> Tr
     }
 }
 
+/// Byte range of the identifier (`[A-Za-z0-9_$]` run) covering `column`.
+///
+/// A cursor placed just after the last character still selects the
+/// identifier, matching how editors report the caret position.
+fn identifier_at(line: &str, column: usize) -> Option<(usize, usize)> {
+    let bytes = line.as_bytes();
+    let is_ident = |b: u8| b.is_ascii_alphanumeric() || b == b'_' || b == b'$';
+    let pos = if bytes.get(column).copied().is_some_and(is_ident) {
+        column
+    } else if column > 0 && bytes.get(column - 1).copied().is_some_and(is_ident) {
+        column - 1
+    } else {
+        return None;
+    };
+    let start = bytes[..pos]
+        .iter()
+        .rposition(|&b| !is_ident(b))
+        .map_or(0, |i| i + 1);
+    let end = bytes[pos..]
+        .iter()
+        .position(|&b| !is_ident(b))
+        .map_or(bytes.len(), |i| pos + i);
+    // Identifiers cannot start with a digit: `123` is a literal
+    if bytes[start].is_ascii_digit() {
+        return None;
+    }
+    Some((start, end))
+}
+
+/// Byte offset of a 0-based UTF-16 column in `line`, clamped to the line end
+fn utf16_column_to_byte(line: &str, column: usize) -> usize {
+    let mut units = 0;
+    for (byte, ch) in line.char_indices() {
+        if units >= column {
+            return byte;
+        }
+        units += ch.len_utf16();
+    }
+    line.len()
+}
+
+/// 0-based UTF-16 column of a byte offset in `line`
+fn byte_to_utf16_column(line: &str, byte: usize) -> usize {
+    line[..find_char_boundary_floor(line, byte)]
+        .encode_utf16()
+        .count()
+}
+
 /// Find the largest valid char boundary <= pos
 #[inline]
 fn find_char_boundary_floor(s: &str, pos: usize) -> usize {
@@ -235,6 +304,7 @@ fn find_match_position_case_insensitive(
 ///
 /// `original_query` is the un-lowered query for exact case-sensitive match boosting.
 /// `query_lower` is the lowercased query for start-of-line checks.
+/// The individual factors are defined by [`LineScoreFactors::text`].
 #[inline]
 fn calculate_score_inline(
     line: &str,
@@ -242,76 +312,24 @@ fn calculate_score_inline(
     query_lower: &str,
     is_symbol_def: bool,
     is_src_lib: bool,
-    dependency_boost: f64,
+    file_boost: f64,
 ) -> f64 {
-    let mut score = 1.0;
-
-    // Boost for exact case-sensitive matches (using the original un-lowered query)
-    if line.contains(original_query) {
-        score *= 2.0;
-    }
-
-    // Boost for symbol definitions (pre-computed)
-    if is_symbol_def {
-        score *= 3.0;
-    }
-
-    // Boost for primary source directories (pre-computed)
-    if is_src_lib {
-        score *= 1.5;
-    }
-
-    // Boost for shorter lines (more relevant) — gentler logarithmic curve, floors at 0.3
-    let line_len_factor = (1.0 / (1.0 + (line.len() as f64 / 100.0).ln_1p())).max(0.3);
-    score *= line_len_factor;
-
-    // Boost for query appearing at the start of the line
-    let trimmed = line.trim_start();
-    if trimmed.len() >= query_lower.len()
-        && trimmed.as_bytes()[..query_lower.len()].eq_ignore_ascii_case(query_lower.as_bytes())
-    {
-        score *= 1.5;
-    }
-
-    // Apply pre-computed dependency boost
-    score * dependency_boost
+    // Apply pre-computed per-file boost (dependencies, recency, feedback)
+    LineScoreFactors::text(line, original_query, query_lower, is_symbol_def, is_src_lib).product()
+        * file_boost
 }
 
-/// Inline regex scoring function with pre-computed values
+/// Inline regex scoring function with pre-computed values.
+/// The individual factors are defined by [`LineScoreFactors::regex`].
 #[inline]
 fn calculate_score_regex_inline(
     line: &str,
     regex: &Regex,
     is_symbol_def: bool,
     is_src_lib: bool,
-    dependency_boost: f64,
+    file_boost: f64,
 ) -> f64 {
-    let mut score = 1.0;
-
-    // Boost for symbol definitions (pre-computed)
-    if is_symbol_def {
-        score *= 3.0;
-    }
-
-    // Boost for primary source directories (pre-computed)
-    if is_src_lib {
-        score *= 1.5;
-    }
-
-    // Boost for shorter lines (more relevant) — gentler logarithmic curve, floors at 0.3
-    let line_len_factor = (1.0 / (1.0 + (line.len() as f64 / 100.0).ln_1p())).max(0.3);
-    score *= line_len_factor;
-
-    // Boost for matches at the start of the line
-    let trimmed = line.trim_start();
-    if let Some(m) = regex.find(trimmed) {
-        if m.start() == 0 {
-            score *= 1.5;
-        }
-    }
-
-    // Apply pre-computed dependency boost
-    score * dependency_boost
+    LineScoreFactors::regex(line, regex, is_symbol_def, is_src_lib).product() * file_boost
 }
 
 #[derive(Debug, Clone)]
@@ -328,9 +346,72 @@ pub struct SearchMatch {
     pub content_truncated: bool,
     pub score: f64,
     pub is_symbol: bool,
+    /// Whether this is an identifier reference (from `find_references`)
+    pub is_reference: bool,
+    /// Innermost container of a matched symbol, e.g. `impl Parser`
+    pub container: Option<String>,
+    /// Signature of a matched symbol
+    pub signature: Option<String>,
+    /// Doc comment of a matched symbol
+    pub doc: Option<String>,
+    /// Kind of a matched symbol (symbol searches and definitions)
+    pub symbol_type: Option<SymbolType>,
     pub dependency_count: u32,
 }
 
+/// How a go-to-definition candidate relates to the file it was requested from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DefinitionScope {
+    /// Defined in the requesting file itself
+    SameFile,
+    /// Defined in a file the requesting file imports
+    Imported,
+    /// Defined in a file in the same directory
+    SameDirectory,
+    /// Defined anywhere else in the index
+    Global,
+}
+
+impl DefinitionScope {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            DefinitionScope::SameFile => "same_file",
+            DefinitionScope::Imported => "imported",
+            DefinitionScope::SameDirectory => "same_directory",
+            DefinitionScope::Global => "global",
+        }
+    }
+
+    /// Score multiplier: closer scopes always outrank farther ones
+    fn weight(self) -> f64 {
+        match self {
+            DefinitionScope::SameFile => 8.0,
+            DefinitionScope::Imported => 4.0,
+            DefinitionScope::SameDirectory => 2.0,
+            DefinitionScope::Global => 1.0,
+        }
+    }
+}
+
+/// A go-to-definition candidate
+#[derive(Debug, Clone)]
+pub struct DefinitionCandidate {
+    pub result: SearchMatch,
+    pub scope: DefinitionScope,
+    /// 1-based column of the defined name, in UTF-16 code units (as used by
+    /// LSP positions)
+    pub column: usize,
+}
+
+/// Result of a go-to-definition lookup
+#[derive(Debug, Clone)]
+pub struct DefinitionLookup {
+    /// Identifier found at the requested position
+    pub identifier: String,
+    /// Candidate definitions, best first
+    pub candidates: Vec<DefinitionCandidate>,
+}
+
 /// Information about how a search was ranked
 #[derive(Debug, Clone)]
 pub struct SearchRankingInfo {
@@ -342,17 +423,36 @@ pub struct SearchRankingInfo {
     pub candidates_searched: usize,
 }
 
+/// A document's cached symbols with its content, which symbol signatures and
+/// docs are read from
+#[derive(Clone, Copy)]
+struct DocumentSymbols<'a> {
+    doc_id: u32,
+    symbols: &'a [Symbol],
+    source: &'a str,
+}
+
+/// Where fuzzy symbol scores come from: the name index during a search, or
+/// the pattern itself when explaining a single match.
+#[derive(Clone, Copy)]
+enum FuzzyScores<'a> {
+    Index(&'a FuzzyNameMatches),
+    Pattern(&'a FuzzyPattern),
+}
+
 /// Result of attempting to resolve imports for a single file.
-/// Used internally by resolve_imports_incremental.
+/// Used internally by resolve_imports and resolve_imports_incremental.
 struct ImportResolutionResult {
     /// ID of the file that has the imports
     file_id: u32,
     /// Path to the file
     file_path: PathBuf,
-    /// Import paths that could not be resolved (target not indexed yet)
-    unresolved_paths: Vec<String>,
-    /// Successfully resolved edges (from_id, to_id)
-    resolved_edges: Vec<(u32, u32)>,
+    /// Imports that could not be resolved (target not indexed yet)
+    unresolved_imports: Vec<ImportStatement>,
+    /// Successfully resolved edges (from_id, to_id, import line)
+    resolved_edges: Vec<(u32, u32, u32)>,
+    /// Imports that matched several files
+    ambiguous_imports: Vec<(u32, AmbiguousImport)>,
 }
 
 /// Intermediate result from phase 1 (parallel, pure-Rust, no FFI).
@@ -363,6 +463,10 @@ pub struct PartialIndexedFile {
     pub filename_stem: String,
     /// Raw file content kept for phase 2 symbol extraction
     pub content: String,
+    /// Hash of the (UTF-8) content, used to detect duplicate files
+    pub content_hash: u64,
+    /// Language detected from the path and content
+    pub language: Option<Language>,
 }
 
 impl PartialIndexedFile {
@@ -436,6 +540,8 @@ impl PartialIndexedFile {
         // trigrams are generated at all, which is strictly better.
         let mut trigrams = extract_unique_trigrams(&filename_stem.to_lowercase());
         trigrams.extend(extract_unique_trigrams(&content.to_lowercase()));
+        let content_hash = duplicates::content_hash(content.as_bytes());
+        let language = Language::detect(path, Some(&content));
 
         Some((
             PartialIndexedFile {
@@ -443,6 +549,8 @@ impl PartialIndexedFile {
                 trigrams,
                 filename_stem,
                 content,
+                content_hash,
+                language,
             },
             transcoded,
         ))
@@ -458,14 +566,21 @@ pub struct PreIndexedFile {
     pub trigrams: FxHashSet<Trigram>,
     /// Extracted symbols
     pub symbols: Vec<Symbol>,
-    /// Extracted import paths
-    pub imports: Vec<String>,
+    /// Extracted import statements
+    pub imports: Vec<ImportStatement>,
+    /// Hash of the file content (0 = unknown)
+    pub content_hash: u64,
+    /// Language detected from the path and content
+    pub language: Option<Language>,
+    /// Identifier references (call sites, type uses)
+    pub references: Vec<SymbolReference>,
 }
 
 impl PreIndexedFile {
     /// Phase 2: run tree-sitter symbol/import extraction on an already-processed partial.
     ///
-    /// Uses `extract_all` to parse the source a single time for both symbols and imports.
+    /// Uses `extract_with_references` to parse the source a single time for symbols,
+    /// imports and identifier references.
     /// Safe to call from multiple rayon threads simultaneously — tree-sitter `Parser` is
     /// `Send + Sync` in tree-sitter v0.26+, and each call creates an independent `Parser`
     /// instance with no shared mutable state.
@@ -477,14 +592,14 @@ impl PreIndexedFile {
     ///   entirely to reduce CPU and memory usage. The filename symbol is always added
     ///   regardless, since it is used for path-based search scoring without tree-sitter.
     pub fn from_partial(partial: PartialIndexedFile, enable_symbols: bool) -> Self {
-        let (mut symbols, imports) = if enable_symbols {
-            let extractor = SymbolExtractor::new(&partial.path);
+        let (mut symbols, imports, references) = if enable_symbols {
+            let extractor = SymbolExtractor::for_language(partial.language);
 
-            // Extract symbols and imports in a single parse with panic protection.
+            // Extract symbols, imports and references in a single parse with panic protection.
             // tree-sitter can stack overflow on deeply nested or malformed files.
             std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
                 extractor
-                    .extract_all(&partial.content)
+                    .extract_with_references(&partial.content)
                     .unwrap_or_default()
             }))
             .unwrap_or_else(|_| {
@@ -492,28 +607,25 @@ impl PreIndexedFile {
                     "Symbol/import extraction panicked for file '{}'. This typically occurs with deeply nested or malformed syntax. Continuing without symbols.",
                     partial.path.display()
                 );
-                (Vec::new(), Vec::new())
+                (Vec::new(), Vec::new(), Vec::new())
             })
         } else {
-            (Vec::new(), Vec::new())
+            (Vec::new(), Vec::new(), Vec::new())
         };
 
         // Add filename as a FileName symbol (line 0, gets symbol scoring boost)
         if !partial.filename_stem.is_empty() {
-            symbols.push(Symbol {
-                name: partial.filename_stem.clone(),
-                symbol_type: SymbolType::FileName,
-                line: 0,
-                column: 0,
-                is_definition: true,
-            });
+            symbols.push(Symbol::file_name(&partial.filename_stem));
         }
 
         PreIndexedFile {
             path: partial.path,
             trigrams: partial.trigrams,
             symbols,
-            imports: imports.into_iter().map(|i| i.path).collect(),
+            imports,
+            content_hash: partial.content_hash,
+            language: partial.language,
+            references,
         }
     }
 }
@@ -541,6 +653,39 @@ impl RankMode {
     }
 }
 
+/// Per-request restrictions applied to the candidate set after path filtering
+#[derive(Debug, Clone, Default)]
+pub struct SearchScope {
+    /// Keep a single copy of byte-identical files; the other copies can be
+    /// listed with [`SearchEngine::duplicate_paths`]
+    pub collapse_duplicates: bool,
+    /// Symbol searches only: keep symbols of these kinds (empty = any kind)
+    pub symbol_kinds: Vec<SymbolType>,
+    /// Symbol searches only: keep real definitions, dropping file-name matches
+    pub definition_only: bool,
+    /// Symbol searches only: match names fuzzily (`usrsvc` finds `UserService`)
+    pub fuzzy: bool,
+    /// Keep files detected as one of these languages (empty = any language)
+    pub languages: Vec<Language>,
+    /// Keep only these files, e.g. a file's dependency neighborhood from
+    /// [`SearchEngine::dependency_neighborhood`] (None = any file)
+    pub within: Option<roaring::RoaringBitmap>,
+    /// Keep files of the packages with these names (empty = any package)
+    pub packages: Vec<String>,
+}
+
+impl SearchScope {
+    /// Whether a symbol passes the kind and definition filters
+    fn accepts_symbol(&self, symbol: &Symbol) -> bool {
+        if self.definition_only
+            && (!symbol.is_definition || symbol.symbol_type == SymbolType::FileName)
+        {
+            return false;
+        }
+        self.symbol_kinds.is_empty() || self.symbol_kinds.contains(&symbol.symbol_type)
+    }
+}
+
 /// Pre-computed file metadata for fast ranking without file reads.
 /// Populated once during finalize(), used during search.
 #[derive(Debug, Clone, Default)]
@@ -553,53 +698,21 @@ pub struct FileMetadata {
     pub base_score: f32,
     /// Lowercase filename stem for efficient query matching (avoids per-query allocation)
     pub lowercase_stem: String,
+    /// Recency signal in [0, 1] (1 = changed just now, 0 = unknown or recency disabled)
+    pub freshness: f32,
+    /// Index into `SearchEngine::duplicate_clusters` when identical copies of this file exist
+    pub duplicate_cluster: Option<u32>,
 }
 
 impl FileMetadata {
     /// Compute metadata for a file at index time
-    fn compute(path: &Path, symbol_count: usize, dependency_count: u32) -> Self {
-        let mut base_score: f32 = 1.0;
-
-        let path_str = path.to_string_lossy();
-        let path_lower = path_str.to_lowercase();
-
-        // Check if in source directories
-        let is_src_lib = path_lower.contains("/src/")
-            || path_lower.contains("\\src\\")
-            || path_lower.contains("/lib/")
-            || path_lower.contains("\\lib\\");
-
-        if is_src_lib {
-            base_score += 2.0;
-        }
-
-        // Boost for high-value extensions
-        if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
-            match ext.to_lowercase().as_str() {
-                "rs" | "py" | "ts" | "js" | "go" | "java" | "c" | "cpp" | "h" => base_score += 1.5,
-                "md" | "txt" | "json" | "toml" | "yaml" | "yml" => base_score += 0.5,
-                _ => {}
-            }
-        }
-
-        // Boost for files with symbols (more likely to be important code)
-        if symbol_count > 0 {
-            base_score += (symbol_count as f32).log2().min(4.0); // Up to +4 for 16+ symbols
-        }
-
-        // Boost for dependency count (files imported by others are important)
-        if dependency_count > 0 {
-            base_score += (dependency_count as f32).log2().min(5.0);
-        }
-
-        // Penalty for test/example directories
-        if path_lower.contains("/test")
-            || path_lower.contains("\\test")
-            || path_lower.contains("/example")
-            || path_lower.contains("\\example")
-        {
-            base_score *= 0.7;
-        }
+    fn compute(
+        path: &Path,
+        language: Option<Language>,
+        symbol_count: usize,
+        dependency_count: u32,
+    ) -> Self {
+        let breakdown = BaseScoreBreakdown::compute(path, language, symbol_count, dependency_count);
 
         // Pre-compute lowercase stem for efficient filename matching during search
         let lowercase_stem = path
@@ -610,24 +723,40 @@ impl FileMetadata {
 
         FileMetadata {
             symbol_count: symbol_count.min(u16::MAX as usize) as u16,
-            is_src_lib,
-            base_score,
+            is_src_lib: breakdown.is_src_lib(),
+            base_score: breakdown.total,
             lowercase_stem,
+            freshness: 0.0,
+            duplicate_cluster: None,
         }
     }
 
-    /// Compute ranking score for a specific query
+    /// Compute ranking score for a specific query, with the recency boost
+    /// for `recency_weight` and the file's click feedback boost (1.0 when it
+    /// has none).
     /// This is called during search but doesn't require reading file content
     #[inline]
-    fn query_score(&self, query_lower: &str) -> f32 {
-        let mut score = self.base_score;
+    fn query_score(&self, query_lower: &str, recency_weight: f32, feedback: f32) -> f32 {
+        self.base_score
+            * self.filename_stem_boost(query_lower)
+            * self.recency_boost(recency_weight)
+            * feedback
+    }
+
+    /// Multiplier for recently changed files (1.0 when the weight is 0)
+    #[inline]
+    fn recency_boost(&self, recency_weight: f32) -> f32 {
+        1.0 + recency_weight * self.freshness
+    }
 
-        // Big boost if query matches filename (using pre-computed lowercase stem)
+    /// Big boost if query matches filename (using pre-computed lowercase stem)
+    #[inline]
+    fn filename_stem_boost(&self, query_lower: &str) -> f32 {
         if !query_lower.is_empty() && self.lowercase_stem.contains(query_lower) {
-            score *= 5.0;
+            5.0
+        } else {
+            1.0
         }
-
-        score
     }
 }
 
@@ -636,14 +765,26 @@ pub struct SearchEngine {
     pub trigram_index: TrigramIndex,
     pub dependency_index: DependencyIndex,
     symbol_cache: Vec<Vec<Symbol>>,
+    /// Distinct symbol names with their files, for fuzzy symbol search
+    symbol_names: SymbolNameIndex,
+    /// Identifier references per file, for find-usages
+    reference_index: ReferenceIndex,
     /// Pre-computed file metadata for fast ranking
     file_metadata: Vec<FileMetadata>,
+    /// Groups of byte-identical files (sorted document IDs), built in `finalize()`
+    duplicate_clusters: Vec<Vec<u32>>,
     /// Pending imports to resolve after all files are indexed
-    pending_imports: Vec<(u32, std::path::PathBuf, Vec<String>)>,
+    pending_imports: Vec<(u32, std::path::PathBuf, Vec<ImportStatement>)>,
     /// Whether tree-sitter symbol extraction is enabled (default: true)
     pub enable_symbols: bool,
     /// Canonical root paths used to produce root-relative display paths
     root_paths: Vec<PathBuf>,
+    /// Ranking configuration (profile, recency signal)
+    pub ranking: RankingConfig,
+    /// Click feedback store (None when feedback is disabled)
+    pub feedback: Option<std::sync::Arc<FeedbackStore>>,
+    /// Files never reported as dead code (binaries, package indexes, tests)
+    entrypoints: PathFilter,
 }
 
 impl SearchEngine {
@@ -653,10 +794,17 @@ impl SearchEngine {
             trigram_index: TrigramIndex::new(),
             dependency_index: DependencyIndex::new(),
             symbol_cache: Vec::new(),
+            symbol_names: SymbolNameIndex::new(),
+            reference_index: ReferenceIndex::new(),
             file_metadata: Vec::new(),
+            duplicate_clusters: Vec::new(),
             pending_imports: Vec::new(),
             enable_symbols: true,
             root_paths: Vec::new(),
+            ranking: RankingConfig::default(),
+            feedback: None,
+            entrypoints: PathFilter::new(&dead_code::default_entrypoints(), &[])
+                .expect("default entry-point patterns are valid"),
         }
     }
 
@@ -665,7 +813,8 @@ impl SearchEngine {
     /// `/workspace/project/src/main.rs`).
     ///
     /// The path is canonicalized on registration so that it matches the
-    /// canonical paths stored in `LazyMappedFile.path`.
+    /// canonical paths stored in `LazyMappedFile.path`. Project manifest
+    /// lookups during import resolution stop at registered roots.
     pub fn add_root_path(&mut self, path: impl AsRef<Path>) {
         let canonical = path
             .as_ref()
@@ -673,7 +822,71 @@ impl SearchEngine {
             .unwrap_or_else(|_| path.as_ref().to_path_buf());
         if !self.root_paths.contains(&canonical) {
             self.root_paths.push(canonical);
+            self.dependency_index.set_roots(self.root_paths.clone());
+        }
+    }
+
+    /// Set the directories searched for C/C++ `#include` targets.
+    ///
+    /// Absolute entries are used as-is; relative ones are resolved against each
+    /// registered root path, so call this after [`Self::add_root_path`].
+    /// Directories that do not exist are skipped.
+    pub fn set_include_dirs(&mut self, dirs: &[String]) {
+        let mut resolved = Vec::new();
+        for dir in dirs {
+            let dir = Path::new(dir);
+            let candidates: Vec<PathBuf> = if dir.is_absolute() {
+                vec![dir.to_path_buf()]
+            } else {
+                self.root_paths.iter().map(|root| root.join(dir)).collect()
+            };
+            for candidate in candidates {
+                if let Ok(canonical) = candidate.canonicalize() {
+                    if canonical.is_dir() && !resolved.contains(&canonical) {
+                        resolved.push(canonical);
+                    }
+                }
+            }
+        }
+        self.dependency_index.set_include_dirs(resolved);
+    }
+
+    /// Map import prefixes to package directories so imports resolve across
+    /// indexed roots (`[indexer] package_roots`).
+    ///
+    /// Absolute directories are used as-is; relative ones are resolved against
+    /// each registered root path (the first that exists wins), so call this
+    /// after [`Self::add_root_path`]. Directories that do not exist are skipped.
+    pub fn set_package_roots(&mut self, roots: &std::collections::BTreeMap<String, String>) {
+        let mut resolved = Vec::new();
+        for (package, dir) in roots {
+            let dir = Path::new(dir);
+            let candidates: Vec<PathBuf> = if dir.is_absolute() {
+                vec![dir.to_path_buf()]
+            } else {
+                self.root_paths.iter().map(|root| root.join(dir)).collect()
+            };
+            let found = candidates
+                .into_iter()
+                .filter_map(|candidate| candidate.canonicalize().ok())
+                .find(|canonical| canonical.is_dir());
+            match found {
+                Some(dir) => resolved.push((package.clone(), dir)),
+                None => tracing::warn!(
+                    package = %package,
+                    dir = %dir.display(),
+                    "Package root not found, imports of it stay unresolved"
+                ),
+            }
         }
+        self.dependency_index.set_package_roots(resolved);
+    }
+
+    /// Set the glob patterns of entry-point files, which the dead code report
+    /// never lists (see [`dead_code::DEFAULT_ENTRYPOINTS`])
+    pub fn set_entrypoints(&mut self, patterns: &[String]) -> Result<()> {
+        self.entrypoints = PathFilter::new(patterns, &[])?;
+        Ok(())
     }
 
     /// Convert a (canonical) stored file path into a workspace-relative display
@@ -754,43 +967,49 @@ impl SearchEngine {
         let mut trigrams = extract_unique_trigrams(&filename_stem.to_lowercase());
         trigrams.extend(extract_unique_trigrams(&content.to_lowercase()));
         self.trigram_index.add_document_trigrams(file_id, trigrams);
+        let content_hash = duplicates::content_hash(content.as_bytes());
+        let language = Language::detect(path, Some(content));
 
         // Extract symbols (only when symbol extraction is enabled)
         let mut symbols = Vec::new();
+        let mut references = Vec::new();
         if self.enable_symbols {
-            let extractor = SymbolExtractor::new(path);
-            symbols = extractor.extract(content).unwrap_or_default();
-
-            // Extract imports and store for later resolution
-            if let Ok(imports) = extractor.extract_imports(content) {
-                if !imports.is_empty() {
-                    let import_paths: Vec<String> = imports.into_iter().map(|i| i.path).collect();
-                    self.pending_imports
-                        .push((file_id, path.to_path_buf(), import_paths));
-                }
+            let extractor = SymbolExtractor::for_language(language);
+            let (extracted_symbols, imports, extracted_references) = extractor
+                .extract_with_references(content)
+                .unwrap_or_default();
+            symbols = extracted_symbols;
+            references = extracted_references;
+
+            // Store imports for later resolution
+            if !imports.is_empty() {
+                self.pending_imports
+                    .push((file_id, path.to_path_buf(), imports));
             }
         }
 
         // Add filename as a FileName symbol (line 0, gets symbol scoring boost)
         if !filename_stem.is_empty() {
-            symbols.push(Symbol {
-                name: filename_stem.to_string(),
-                symbol_type: SymbolType::FileName,
-                line: 0,
-                column: 0,
-                is_definition: true,
-            });
+            symbols.push(Symbol::file_name(filename_stem));
         }
 
-        // Ensure symbol_cache is large enough
-        while self.symbol_cache.len() <= file_id as usize {
-            self.symbol_cache.push(Vec::new());
-        }
-        self.symbol_cache[file_id as usize] = symbols;
+        self.set_symbols(file_id, symbols);
+        self.reference_index.set_file(file_id, &references);
+        self.file_store.set_content_hash(file_id, content_hash);
+        self.file_store.set_language(file_id, language);
 
         Ok(())
     }
 
+    /// Store a file's symbols and register their names for fuzzy search
+    fn set_symbols(&mut self, file_id: u32, symbols: Vec<Symbol>) {
+        if self.symbol_cache.len() <= file_id as usize {
+            self.symbol_cache.resize(file_id as usize + 1, Vec::new());
+        }
+        self.symbol_names.set_file(file_id, &symbols);
+        self.symbol_cache[file_id as usize] = symbols;
+    }
+
     /// Index a batch of pre-processed files.
     /// This is the merge step after parallel processing - only this needs the write lock.
     /// Returns the number of files successfully indexed.
@@ -811,12 +1030,14 @@ impl SearchEngine {
             // Add trigrams to index (using pre-computed trigrams)
             self.trigram_index
                 .add_document_trigrams(file_id, pre_indexed.trigrams);
+            self.file_store
+                .set_content_hash(file_id, pre_indexed.content_hash);
+            self.file_store.set_language(file_id, pre_indexed.language);
 
             // Store symbols
-            while self.symbol_cache.len() <= file_id as usize {
-                self.symbol_cache.push(Vec::new());
-            }
-            self.symbol_cache[file_id as usize] = pre_indexed.symbols;
+            self.set_symbols(file_id, pre_indexed.symbols);
+            self.reference_index
+                .set_file(file_id, &pre_indexed.references);
 
             // Store imports for later resolution
             if !pre_indexed.imports.is_empty() {
@@ -845,24 +1066,31 @@ impl SearchEngine {
 
         // Phase 1: Parallel path resolution - collect (from_id, to_id) pairs
         // Uses &self on dependency_index (thread-safe read-only methods)
-        let edges: Vec<(u32, u32)> = pending
-            .par_iter()
-            .flat_map(|(file_id, file_path, import_paths)| {
-                import_paths
-                    .par_iter()
-                    .filter_map(|import_path| {
-                        let resolved = self
-                            .dependency_index
-                            .resolve_import_path(file_path, import_path)?;
-                        let to_id = self.dependency_index.get_file_id(&resolved)?;
-                        Some((*file_id, to_id))
-                    })
-                    .collect::<Vec<_>>()
+        let results: Vec<ImportResolutionResult> = pending
+            .into_par_iter()
+            .map(|(file_id, file_path, imports)| {
+                self.resolve_file_imports(file_id, file_path, imports)
             })
             .collect();
 
-        // Phase 2: Sequential batch insert (requires &mut self)
-        self.dependency_index.add_imports_batch(edges);
+        // Phase 2: Sequential batch insert (requires &mut self). Imports still
+        // unresolved now are kept for the unresolved import report.
+        let mut edges = Vec::new();
+        let mut ambiguous = Vec::new();
+        let mut unresolved = Vec::new();
+        for result in results {
+            edges.extend(result.resolved_edges);
+            ambiguous.extend(result.ambiguous_imports);
+            unresolved.extend(result.unresolved_imports.into_iter().map(|import| {
+                (
+                    result.file_id,
+                    UnresolvedImport::new(import.path, import.line, &import.import_type),
+                )
+            }));
+        }
+        self.dependency_index.add_imports_with_lines_batch(edges);
+        self.dependency_index.add_ambiguous_batch(ambiguous);
+        self.dependency_index.add_unresolved_batch(unresolved);
     }
 
     /// Incrementally resolve pending imports that can be resolved now.
@@ -883,44 +1111,24 @@ impl SearchEngine {
         // Collect resolved edges and unresolved imports separately
         let results: Vec<ImportResolutionResult> = pending
             .into_par_iter()
-            .map(|(file_id, file_path, import_paths)| {
-                let mut resolved_edges = Vec::new();
-                let mut unresolved_paths = Vec::new();
-
-                for import_path in import_paths {
-                    if let Some(resolved) = self
-                        .dependency_index
-                        .resolve_import_path(&file_path, &import_path)
-                    {
-                        if let Some(to_id) = self.dependency_index.get_file_id(&resolved) {
-                            resolved_edges.push((file_id, to_id));
-                            continue;
-                        }
-                    }
-                    // Could not resolve - keep for later
-                    unresolved_paths.push(import_path);
-                }
-
-                ImportResolutionResult {
-                    file_id,
-                    file_path,
-                    unresolved_paths,
-                    resolved_edges,
-                }
+            .map(|(file_id, file_path, imports)| {
+                self.resolve_file_imports(file_id, file_path, imports)
             })
             .collect();
 
         // Phase 2: Sequential processing - insert resolved edges and collect unresolved
         let mut all_edges = Vec::new();
+        let mut ambiguous = Vec::new();
         for result in results {
             all_edges.extend(result.resolved_edges);
+            ambiguous.extend(result.ambiguous_imports);
 
             // Re-add unresolved imports to pending
-            if !result.unresolved_paths.is_empty() {
+            if !result.unresolved_imports.is_empty() {
                 self.pending_imports.push((
                     result.file_id,
                     result.file_path,
-                    result.unresolved_paths,
+                    result.unresolved_imports,
                 ));
             }
         }
@@ -929,12 +1137,68 @@ impl SearchEngine {
 
         // Batch insert all resolved edges
         if !all_edges.is_empty() {
-            self.dependency_index.add_imports_batch(all_edges);
+            self.dependency_index
+                .add_imports_with_lines_batch(all_edges);
         }
+        self.dependency_index.add_ambiguous_batch(ambiguous);
 
         edge_count
     }
 
+    /// Resolve one file's imports into edges, ambiguous imports and imports
+    /// with no match yet. Thread-safe: only reads the dependency index.
+    fn resolve_file_imports(
+        &self,
+        file_id: u32,
+        file_path: PathBuf,
+        imports: Vec<ImportStatement>,
+    ) -> ImportResolutionResult {
+        let mut resolved_edges = Vec::new();
+        let mut unresolved_imports = Vec::new();
+        let mut ambiguous_imports = Vec::new();
+        let file_ids = |paths: Vec<PathBuf>| -> Vec<u32> {
+            paths
+                .iter()
+                .filter_map(|path| self.dependency_index.get_file_id(path))
+                .collect()
+        };
+
+        for import in imports {
+            match self.dependency_index.resolve_import_path(
+                &file_path,
+                &import.path,
+                &import.import_type,
+            ) {
+                ImportResolution::Resolved(paths) => {
+                    let line = import.line as u32;
+                    resolved_edges.extend(
+                        file_ids(paths)
+                            .into_iter()
+                            .map(|to_id| (file_id, to_id, line)),
+                    );
+                }
+                ImportResolution::Ambiguous(paths) => ambiguous_imports.push((
+                    file_id,
+                    AmbiguousImport {
+                        path: import.path,
+                        line: import.line,
+                        candidates: file_ids(paths),
+                    },
+                )),
+                // Could not resolve - keep for later
+                ImportResolution::Unresolved => unresolved_imports.push(import),
+            }
+        }
+
+        ImportResolutionResult {
+            file_id,
+            file_path,
+            unresolved_imports,
+            resolved_edges,
+            ambiguous_imports,
+        }
+    }
+
     /// Get the number of pending imports that still need resolution.
     pub fn pending_imports_count(&self) -> usize {
         self.pending_imports
@@ -959,6 +1223,8 @@ impl SearchEngine {
             syms.shrink_to_fit();
         }
         self.symbol_cache.shrink_to_fit();
+        self.symbol_names.shrink_to_fit();
+        self.reference_index.shrink_to_fit();
         self.pending_imports.shrink_to_fit();
         self.trigram_index.shrink_to_fit();
     }
@@ -991,13 +1257,18 @@ impl SearchEngine {
                     .map(|s| s.len())
                     .unwrap_or(0);
                 let dep_count = self.dependency_index.get_import_count(file_id);
-                FileMetadata::compute(&file.path, symbol_count, dep_count)
+                let language = self.file_store.get_language(file_id);
+                FileMetadata::compute(&file.path, language, symbol_count, dep_count)
             } else {
                 FileMetadata::default()
             };
             self.file_metadata.push(metadata);
         }
 
+        self.compute_duplicate_clusters();
+        self.compute_freshness();
+        self.refresh_feedback_boosts();
+
         tracing::info!(
             num_files = num_files,
             "Computed file metadata for fast ranking"
@@ -1017,6 +1288,270 @@ impl SearchEngine {
         self.symbol_cache.shrink_to_fit();
     }
 
+    /// Group files by content hash and record each file's cluster in its metadata
+    fn compute_duplicate_clusters(&mut self) {
+        let hashes = (0..self.file_store.len() as u32)
+            .filter_map(|id| Some((id, self.file_store.get_content_hash(id)?)));
+        self.duplicate_clusters = duplicates::cluster_by_hash(hashes);
+
+        for (cluster_idx, members) in self.duplicate_clusters.iter().enumerate() {
+            for &doc_id in members {
+                if let Some(meta) = self.file_metadata.get_mut(doc_id as usize) {
+                    meta.duplicate_cluster = Some(cluster_idx as u32);
+                }
+            }
+        }
+
+        if !self.duplicate_clusters.is_empty() {
+            tracing::info!(
+                clusters = self.duplicate_clusters.len(),
+                "Detected groups of identical files"
+            );
+        }
+    }
+
+    /// Display paths of the other byte-identical copies of a file (empty when unique)
+    pub fn duplicate_paths(&self, file_id: u32) -> Vec<String> {
+        let Some(cluster) = self.get_file_metadata(file_id).duplicate_cluster else {
+            return Vec::new();
+        };
+        self.duplicate_clusters
+            .get(cluster as usize)
+            .map(|members| {
+                members
+                    .iter()
+                    .filter(|&&id| id != file_id)
+                    .filter_map(|&id| self.file_store.get_path(id))
+                    .map(|path| self.make_display_path(path))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Summary of duplicate content for diagnostics
+    pub fn duplicate_stats(&self) -> DuplicateStats {
+        DuplicateStats::from_clusters(self.duplicate_clusters.iter().map(|members| {
+            let size = members
+                .first()
+                .and_then(|&id| self.file_store.get(id))
+                .and_then(|f| {
+                    f.len_if_mapped()
+                        .map(|len| len as u64)
+                        .or_else(|| std::fs::metadata(&f.path).ok().map(|m| m.len()))
+                })
+                .unwrap_or(0);
+            let paths = members
+                .iter()
+                .filter_map(|&id| self.file_store.get_path(id))
+                .map(|path| self.make_display_path(path))
+                .collect();
+            (size, paths)
+        }))
+    }
+
+    /// Keep one document per duplicate cluster: the copy with the highest base
+    /// score (e.g. `src/` over `vendor/`), ties going to the lowest document ID.
+    fn collapse_duplicate_candidates(
+        &self,
+        candidate_docs: roaring::RoaringBitmap,
+    ) -> roaring::RoaringBitmap {
+        if self.duplicate_clusters.is_empty() {
+            return candidate_docs;
+        }
+        let mut representatives: FxHashMap<u32, (u32, f32)> = FxHashMap::default();
+        let mut collapsed = roaring::RoaringBitmap::new();
+        for doc_id in candidate_docs.iter() {
+            let meta = self.get_file_metadata(doc_id);
+            match meta.duplicate_cluster {
+                None => {
+                    collapsed.insert(doc_id);
+                }
+                Some(cluster) => {
+                    let best = representatives
+                        .entry(cluster)
+                        .or_insert((doc_id, meta.base_score));
+                    if meta.base_score > best.1 {
+                        *best = (doc_id, meta.base_score);
+                    }
+                }
+            }
+        }
+        collapsed.extend(representatives.values().map(|&(doc_id, _)| doc_id));
+        collapsed
+    }
+
+    /// Apply path filtering and per-request scope to a candidate set
+    fn apply_filters(
+        &self,
+        mut candidate_docs: roaring::RoaringBitmap,
+        path_filter: &PathFilter,
+        scope: &SearchScope,
+    ) -> roaring::RoaringBitmap {
+        if let Some(within) = &scope.within {
+            candidate_docs &= within;
+        }
+        let mut filtered = self.apply_path_filter(candidate_docs, path_filter);
+        if !scope.languages.is_empty() {
+            filtered = filtered
+                .into_iter()
+                .filter(|&doc_id| {
+                    self.file_store
+                        .get_language(doc_id)
+                        .is_some_and(|language| scope.languages.contains(&language))
+                })
+                .collect();
+        }
+        if !scope.packages.is_empty() {
+            filtered = filtered
+                .into_iter()
+                .filter(|&doc_id| {
+                    self.file_package(doc_id)
+                        .is_some_and(|package| scope.packages.contains(&package.name))
+                })
+                .collect();
+        }
+        if scope.collapse_duplicates {
+            self.collapse_duplicate_candidates(filtered)
+        } else {
+            filtered
+        }
+    }
+
+    /// Fill in `FileMetadata::freshness` from mtimes.
+    /// Skipped entirely (no stat calls) when the recency weight is zero.
+    ///
+    /// With the git recency source this is the fallback for files git does not
+    /// know; commit times are applied afterwards by [`Self::apply_commit_times`]
+    /// so that reading history stays off the indexing critical path.
+    fn compute_freshness(&mut self) {
+        if self.ranking.effective_recency_weight() <= 0.0 {
+            return;
+        }
+
+        let now = recency::now_secs();
+        let half_life = self.ranking.recency_half_life_days;
+        for (file_id, meta) in self.file_metadata.iter_mut().enumerate() {
+            let Some(file) = self.file_store.get(file_id as u32) else {
+                continue;
+            };
+            let changed = crate::index::persistence::get_mtime(&file.path).unwrap_or(0);
+            meta.freshness = recency::freshness(changed, now, half_life);
+        }
+
+        tracing::info!(
+            source = ?self.ranking.recency_source,
+            "Computed recency signal for ranking"
+        );
+    }
+
+    /// Files to look up in git history, or `None` unless recency is enabled
+    /// with the git source. Resolve the result without holding the engine
+    /// lock, then pass it to [`Self::apply_commit_times`].
+    pub fn git_recency(&self) -> Option<recency::GitRecency> {
+        if self.ranking.effective_recency_weight() <= 0.0
+            || self.ranking.recency_source != RecencySource::Git
+        {
+            return None;
+        }
+        let files = (0..self.file_store.len() as u32)
+            .filter_map(|file_id| Some((file_id, self.file_store.get_path(file_id)?.to_path_buf())))
+            .collect();
+        Some(recency::GitRecency {
+            roots: self.root_paths.clone(),
+            files,
+        })
+    }
+
+    /// Replace the mtime-based freshness of files with their last commit time.
+    /// Entries whose document no longer has the same path (re-indexed in the
+    /// meantime) are skipped.
+    pub fn apply_commit_times(&mut self, commit_times: Vec<(u32, PathBuf, u64)>) {
+        let now = recency::now_secs();
+        let half_life = self.ranking.recency_half_life_days;
+        let mut applied = 0;
+        for (file_id, path, changed) in commit_times {
+            if self.file_store.get_path(file_id) != Some(path.as_path()) {
+                continue;
+            }
+            if let Some(meta) = self.file_metadata.get_mut(file_id as usize) {
+                meta.freshness = recency::freshness(changed, now, half_life);
+                applied += 1;
+            }
+        }
+        tracing::info!(
+            git_files = applied,
+            "Applied git commit times to recency signal"
+        );
+    }
+
+    /// Per-document multiplier applied to every match score in a file:
+    /// dependency popularity combined with the (optional) recency and feedback boosts.
+    #[inline]
+    fn file_boost(&self, doc_id: u32, dependency_count: u32) -> f64 {
+        self.file_boost_breakdown(doc_id, dependency_count)
+            .product()
+    }
+
+    fn file_boost_breakdown(&self, doc_id: u32, dependency_count: u32) -> FileBoostBreakdown {
+        let dependency = if dependency_count > 0 {
+            1.0 + (dependency_count as f64).log10() * 0.5
+        } else {
+            1.0
+        };
+        let recency = self
+            .get_file_metadata(doc_id)
+            .recency_boost(self.ranking.effective_recency_weight()) as f64;
+        let feedback = self.feedback_boost(doc_id) as f64;
+        FileBoostBreakdown {
+            dependency,
+            recency,
+            feedback,
+        }
+    }
+
+    /// Learned click-feedback multiplier of a document (1.0 without feedback)
+    fn feedback_boost(&self, doc_id: u32) -> f32 {
+        self.feedback
+            .as_ref()
+            .and_then(|f| f.doc_boosts().get(&doc_id).copied())
+            .unwrap_or(1.0)
+    }
+
+    /// Record a click-feedback event and refresh the boost of the document it
+    /// names. Directory boosts reach the document's siblings at the next full
+    /// [`Self::refresh_feedback_boosts`].
+    ///
+    /// Returns false (and records nothing) when the path is not an indexed
+    /// document. Fails when feedback is disabled (no store configured) or the
+    /// event log cannot be written.
+    pub fn record_feedback(&self, event: FeedbackEvent) -> Result<bool> {
+        let Some(feedback) = self.feedback.as_ref() else {
+            anyhow::bail!("Click feedback is disabled");
+        };
+        let Some(doc_id) = feedback.document_id(&event.file_path) else {
+            return Ok(false);
+        };
+        let path = event.file_path.clone();
+        feedback.record(event)?;
+        feedback.refresh_document(&path, doc_id, recency::now_secs());
+        Ok(true)
+    }
+
+    /// Hand the indexed documents to the feedback store and rebuild every
+    /// per-document boost.
+    ///
+    /// Feedback is keyed by display path (stable across re-indexing), so the
+    /// store keeps the sorted display paths to map later events to documents.
+    pub fn refresh_feedback_boosts(&self) {
+        let Some(feedback) = self.feedback.as_ref() else {
+            return;
+        };
+        let documents = (0..self.file_store.len() as u32)
+            .filter_map(|file_id| Some((self.get_file_path(file_id)?, file_id)))
+            .collect();
+        feedback.set_documents(documents, recency::now_secs());
+    }
+
     pub fn rebuild_symbols_and_dependencies(&mut self) -> RebuildCacheStats {
         self.rebuild_symbols_and_dependencies_with_progress(|_, _| {})
     }
@@ -1035,6 +1570,7 @@ impl SearchEngine {
 
         // Allocate symbol cache sized for the newly registered files
         self.symbol_cache = vec![Vec::new(); total_new_files];
+        self.symbol_names.clear();
 
         // Build a mapping: original persisted file index → new file ID
         let orig_to_new_id: rustc_hash::FxHashMap<u32, u32> = valid_file_indices
@@ -1044,29 +1580,56 @@ impl SearchEngine {
             .collect();
 
         // Register files in dependency_index for future import resolution
-        // and restore per-file symbol caches
+        // and restore per-file symbol caches and references
+        let mut reference_name_map = FxHashMap::default();
         for (new_id, &orig_idx) in valid_file_indices.iter().enumerate() {
             let new_id = new_id as u32;
             if let Some(path) = self.file_store.get_path(new_id) {
                 self.dependency_index.register_file(new_id, path);
             }
             if let Some(syms) = persisted.symbols.get(orig_idx) {
-                self.symbol_cache[new_id as usize] = syms.clone();
+                self.set_symbols(new_id, syms.clone());
+            }
+            if let Some(meta) = persisted.files.get(orig_idx) {
+                self.file_store.set_content_hash(new_id, meta.content_hash);
+                self.file_store.set_language(new_id, meta.language);
+            }
+            if let Some(refs) = persisted.references.get(orig_idx) {
+                self.reference_index.restore_file(
+                    new_id,
+                    refs,
+                    &persisted.reference_names,
+                    &mut reference_name_map,
+                );
+            }
+            if let Some(unresolved) = persisted.unresolved_imports.get(orig_idx) {
+                self.dependency_index.add_unresolved_batch(
+                    unresolved
+                        .iter()
+                        .map(|import| (new_id, import.clone()))
+                        .collect(),
+                );
             }
         }
 
-        // Restore dependency edges, remapping original indices to new file IDs
-        // and dropping edges whose endpoints were stale/removed
-        let remapped_edges: Vec<(u32, u32)> = persisted
-            .dependency_edges
-            .iter()
-            .filter_map(|&(from_orig, to_orig)| {
-                let new_from = orig_to_new_id.get(&from_orig)?;
-                let new_to = orig_to_new_id.get(&to_orig)?;
-                Some((*new_from, *new_to))
-            })
-            .collect();
-        self.dependency_index.add_imports_batch(remapped_edges);
+        // Restore dependency edges and their import lines, remapping original
+        // indices to new file IDs and dropping edges whose endpoints were stale/removed
+        let mut remapped_edges: Vec<(u32, u32, u32)> = Vec::new();
+        for (i, &(from_orig, to_orig)) in persisted.dependency_edges.iter().enumerate() {
+            let (Some(&new_from), Some(&new_to)) =
+                (orig_to_new_id.get(&from_orig), orig_to_new_id.get(&to_orig))
+            else {
+                continue;
+            };
+            match persisted.dependency_edge_lines.get(i) {
+                Some(lines) if !lines.is_empty() => {
+                    remapped_edges.extend(lines.iter().map(|&line| (new_from, new_to, line)))
+                }
+                _ => self.dependency_index.add_import(new_from, new_to),
+            }
+        }
+        self.dependency_index
+            .add_imports_with_lines_batch(remapped_edges);
     }
 
     pub fn rebuild_symbols_and_dependencies_with_progress<F>(
@@ -1083,6 +1646,8 @@ impl SearchEngine {
 
         // Reset derived state
         self.symbol_cache = vec![Vec::new(); total_files];
+        self.symbol_names.clear();
+        self.reference_index.clear();
         self.pending_imports.clear();
         self.dependency_index.clear();
 
@@ -1105,11 +1670,16 @@ impl SearchEngine {
 
                 let mut symbols = Vec::new();
                 let mut imports = Vec::new();
+                let mut references = Vec::new();
                 let mut had_content = false;
+                let mut content_hash = 0;
+                let mut language = Language::detect(&path, None);
 
                 if let Some(file) = file_store.get(file_id) {
                     if let Ok(content) = file.as_str() {
                         had_content = true;
+                        content_hash = duplicates::content_hash(content.as_bytes());
+                        language = Language::detect(&path, Some(&content));
 
                         // Skip tree-sitter extraction when symbols are disabled
                         if enable_symbols {
@@ -1122,25 +1692,25 @@ impl SearchEngine {
                                 );
                                 // Fall through — filename symbol is still added below
                             } else {
-                                let extractor = SymbolExtractor::new(&path);
+                                let extractor = SymbolExtractor::for_language(language);
 
-                                let (extracted_symbols, extracted_imports) =
+                                let (extracted_symbols, extracted_imports, extracted_references) =
                                     std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
-                                        extractor.extract_all(&content).unwrap_or_default()
+                                        extractor
+                                            .extract_with_references(&content)
+                                            .unwrap_or_default()
                                     }))
                                     .unwrap_or_else(|_| {
                                         warn!(
                                             "Symbol/import extraction panicked for file '{}'. Continuing without symbols.",
                                             path.display()
                                         );
-                                        (Vec::new(), Vec::new())
+                                        (Vec::new(), Vec::new(), Vec::new())
                                     });
 
                                 symbols = extracted_symbols;
-                                imports = extracted_imports
-                                    .into_iter()
-                                    .map(|i| i.path)
-                                    .collect();
+                                references = extracted_references;
+                                imports = extracted_imports;
                             }
                         }
                     }
@@ -1149,13 +1719,7 @@ impl SearchEngine {
                 // Always add filename symbol for filename-only matches.
                 if let Some(stem) = path.file_stem().and_then(|s| s.to_str()) {
                     if !stem.is_empty() {
-                        symbols.push(Symbol {
-                            name: stem.to_string(),
-                            symbol_type: SymbolType::FileName,
-                            line: 0,
-                            column: 0,
-                            is_definition: true,
-                        });
+                        symbols.push(Symbol::file_name(stem));
                     }
                 }
 
@@ -1165,6 +1729,9 @@ impl SearchEngine {
                     symbols,
                     imports,
                     had_content,
+                    content_hash,
+                    language,
+                    references,
                 })
             })
             .collect();
@@ -1181,11 +1748,12 @@ impl SearchEngine {
                 stats.files_skipped += 1;
             }
 
-            if entry.file_id as usize >= self.symbol_cache.len() {
-                self.symbol_cache
-                    .resize(entry.file_id as usize + 1, Vec::new());
-            }
-            self.symbol_cache[entry.file_id as usize] = entry.symbols;
+            self.set_symbols(entry.file_id, entry.symbols);
+            self.file_store
+                .set_content_hash(entry.file_id, entry.content_hash);
+            self.file_store.set_language(entry.file_id, entry.language);
+            self.reference_index
+                .set_file(entry.file_id, &entry.references);
 
             if !entry.imports.is_empty() {
                 self.pending_imports
@@ -1321,24 +1889,7 @@ impl SearchEngine {
         candidate_docs: &roaring::RoaringBitmap,
         max_results: usize,
     ) -> Vec<SearchMatch> {
-        // Score all candidates by file metadata (no file reads, no allocations)
-        let mut scored_candidates: Vec<(u32, f32)> = candidate_docs
-            .iter()
-            .map(|doc_id| {
-                let meta = self.get_file_metadata(doc_id);
-                let score = meta.query_score(query_lower);
-                (doc_id, score)
-            })
-            .collect();
-
-        scored_candidates
-            .sort_unstable_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
-
-        let top_n = Self::FAST_RANKING_TOP_N.min(scored_candidates.len());
-        let top_candidates: Vec<u32> = scored_candidates[..top_n]
-            .iter()
-            .map(|(id, _)| *id)
-            .collect();
+        let top_candidates = self.fast_ranked_candidates(candidate_docs, query_lower);
 
         let mut matches: Vec<SearchMatch> = top_candidates
             .par_iter()
@@ -1352,6 +1903,32 @@ impl SearchEngine {
         matches
     }
 
+    /// The `FAST_RANKING_TOP_N` candidates with the highest file-level score
+    /// (no file reads). `query_lower` is empty for regex and symbol searches,
+    /// which get no filename boost.
+    fn fast_ranked_candidates(
+        &self,
+        candidate_docs: &roaring::RoaringBitmap,
+        query_lower: &str,
+    ) -> Vec<u32> {
+        let recency_weight = self.ranking.effective_recency_weight();
+        let feedback = self.feedback.as_ref().map(|f| f.doc_boosts());
+        let mut scored: Vec<(u32, f32)> = candidate_docs
+            .iter()
+            .map(|doc_id| {
+                let boost = feedback
+                    .as_ref()
+                    .and_then(|boosts| boosts.get(&doc_id).copied())
+                    .unwrap_or(1.0);
+                let meta = self.get_file_metadata(doc_id);
+                (doc_id, meta.query_score(query_lower, recency_weight, boost))
+            })
+            .collect();
+        scored.sort_unstable_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        scored.truncate(Self::FAST_RANKING_TOP_N);
+        scored.into_iter().map(|(id, _)| id).collect()
+    }
+
     /// Full ranking with original query for exact-match scoring.
     ///
     /// `original_query` is passed to line-level scoring for case-sensitive match boost.
@@ -1434,6 +2011,7 @@ impl SearchEngine {
             exclude_patterns,
             max_results,
             RankMode::Auto,
+            &SearchScope::default(),
         )?;
         Ok(matches)
     }
@@ -1447,6 +2025,7 @@ impl SearchEngine {
         exclude_patterns: &str,
         max_results: usize,
         rank_mode: RankMode,
+        scope: &SearchScope,
     ) -> Result<(Vec<SearchMatch>, SearchRankingInfo)> {
         // Build path filter from patterns
         let path_filter = PathFilter::from_delimited(include_patterns, exclude_patterns)?;
@@ -1461,15 +2040,7 @@ impl SearchEngine {
         };
 
         // Apply path filter
-        let filtered_docs = if path_filter.is_empty() {
-            candidate_docs
-        } else {
-            path_filter.filter_documents_with(&candidate_docs, |doc_id| {
-                self.file_store
-                    .get(doc_id)
-                    .map(|f| self.make_display_path(&f.path))
-            })
-        };
+        let filtered_docs = self.apply_filters(candidate_docs, &path_filter, scope);
 
         let total_candidates = filtered_docs.len() as usize;
 
@@ -1544,6 +2115,26 @@ impl SearchEngine {
         exclude_patterns: &str,
         max_results: usize,
     ) -> Result<Vec<SearchMatch>> {
+        let (matches, _) = self.search_regex_ranked(
+            pattern,
+            include_patterns,
+            exclude_patterns,
+            max_results,
+            &SearchScope::default(),
+        )?;
+        Ok(matches)
+    }
+
+    /// Like [`Self::search_regex`], also returning how the candidates were ranked.
+    #[tracing::instrument(skip(self))]
+    pub fn search_regex_ranked(
+        &self,
+        pattern: &str,
+        include_patterns: &str,
+        exclude_patterns: &str,
+        max_results: usize,
+        scope: &SearchScope,
+    ) -> Result<(Vec<SearchMatch>, SearchRankingInfo)> {
         // Analyze the regex pattern
         let analysis = RegexAnalysis::analyze(pattern)?;
 
@@ -1567,15 +2158,7 @@ impl SearchEngine {
         };
 
         // Apply path filter
-        let filtered_docs = if path_filter.is_empty() {
-            candidate_docs
-        } else {
-            path_filter.filter_documents_with(&candidate_docs, |doc_id| {
-                self.file_store
-                    .get(doc_id)
-                    .map(|f| self.make_display_path(&f.path))
-            })
-        };
+        let filtered_docs = self.apply_filters(candidate_docs, &path_filter, scope);
 
         let total_candidates = filtered_docs.len() as usize;
         let use_fast =
@@ -1583,18 +2166,7 @@ impl SearchEngine {
 
         let doc_ids: Vec<u32> = if use_fast {
             // Fast ranking: sort by file score, take top N
-            let mut scored: Vec<(u32, f32)> = filtered_docs
-                .iter()
-                .map(|id| (id, self.get_file_metadata(id).base_score))
-                .collect();
-            scored.sort_unstable_by(|a, b| {
-                b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal)
-            });
-            scored
-                .iter()
-                .take(Self::FAST_RANKING_TOP_N)
-                .map(|(id, _)| *id)
-                .collect()
+            self.fast_ranked_candidates(&filtered_docs, "")
         } else {
             filtered_docs.iter().collect()
         };
@@ -1609,7 +2181,16 @@ impl SearchEngine {
 
         self.sort_and_truncate(&mut matches, max_results);
         self.file_store.evict_all_fallbacks();
-        Ok(matches)
+        let info = SearchRankingInfo {
+            mode: if use_fast {
+                RankMode::Fast
+            } else {
+                RankMode::Full
+            },
+            total_candidates,
+            candidates_searched: doc_ids.len(),
+        };
+        Ok((matches, info))
     }
 
     /// Search only in discovered symbols (functions, classes, methods, types, etc.).
@@ -1632,31 +2213,48 @@ impl SearchEngine {
         exclude_patterns: &str,
         max_results: usize,
     ) -> Result<Vec<SearchMatch>> {
+        let (matches, _) = self.search_symbols_ranked(
+            query,
+            include_patterns,
+            exclude_patterns,
+            max_results,
+            &SearchScope::default(),
+        )?;
+        Ok(matches)
+    }
+
+    /// Like [`Self::search_symbols`], also returning how the candidates were ranked.
+    #[tracing::instrument(skip(self))]
+    pub fn search_symbols_ranked(
+        &self,
+        query: &str,
+        include_patterns: &str,
+        exclude_patterns: &str,
+        max_results: usize,
+        scope: &SearchScope,
+    ) -> Result<(Vec<SearchMatch>, SearchRankingInfo)> {
         // Build path filter from patterns
         let path_filter = PathFilter::from_delimited(include_patterns, exclude_patterns)?;
 
-        // Pre-compute lowercase query ONCE
-        let query_lower = query.to_lowercase();
+        // Parse the query ONCE (name, qualified name, `sig:` or `doc:`)
+        let symbol_query = SymbolQuery::parse(query);
 
         // Use trigram index to narrow candidates if the query is long enough for trigrams (>= 3 chars).
         // This avoids scanning every file when the trigram index can pre-filter.
-        let candidate_docs = if query_lower.len() >= 3 {
-            self.trigram_index.search(&query_lower)
+        // Qualified queries (`Parser::parse`) are narrowed by the symbol name alone.
+        // Fuzzy queries take their candidates from the symbol name index instead.
+        let fuzzy = self.fuzzy_symbol_matches(&symbol_query, scope);
+        let candidate_docs = if let Some(fuzzy) = &fuzzy {
+            fuzzy.files.clone()
+        } else if let Some(literal) = symbol_query.trigram_literal() {
+            self.trigram_index.search(literal)
         } else {
             // Query too short for trigrams — fall back to all documents
             self.trigram_index.all_documents()
         };
 
         // Apply path filter if it has any patterns
-        let filtered_docs = if path_filter.is_empty() {
-            candidate_docs
-        } else {
-            path_filter.filter_documents_with(&candidate_docs, |doc_id| {
-                self.file_store
-                    .get(doc_id)
-                    .map(|f| self.make_display_path(&f.path))
-            })
-        };
+        let filtered_docs = self.apply_filters(candidate_docs, &path_filter, scope);
 
         let total_candidates = filtered_docs.len() as usize;
         let use_fast =
@@ -1664,18 +2262,7 @@ impl SearchEngine {
 
         let doc_ids: Vec<u32> = if use_fast {
             // Fast ranking: sort by file score (prioritize files with more symbols)
-            let mut scored: Vec<(u32, f32)> = filtered_docs
-                .iter()
-                .map(|id| (id, self.get_file_metadata(id).base_score))
-                .collect();
-            scored.sort_unstable_by(|a, b| {
-                b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal)
-            });
-            scored
-                .iter()
-                .take(Self::FAST_RANKING_TOP_N)
-                .map(|(id, _)| *id)
-                .collect()
+            self.fast_ranked_candidates(&filtered_docs, "")
         } else {
             filtered_docs.iter().collect()
         };
@@ -1683,13 +2270,389 @@ impl SearchEngine {
         // Search symbols in parallel
         let mut matches: Vec<SearchMatch> = doc_ids
             .par_iter()
-            .filter_map(|&doc_id| self.search_symbols_in_document(doc_id, query, &query_lower))
+            .filter_map(|&doc_id| {
+                self.search_symbols_in_document(doc_id, &symbol_query, fuzzy.as_ref(), scope)
+            })
             .flatten()
             .collect();
 
         self.sort_and_truncate(&mut matches, max_results);
         self.file_store.evict_all_fallbacks();
-        Ok(matches)
+        let info = SearchRankingInfo {
+            mode: if use_fast {
+                RankMode::Fast
+            } else {
+                RankMode::Full
+            },
+            total_candidates,
+            candidates_searched: doc_ids.len(),
+        };
+        Ok((matches, info))
+    }
+
+    /// Count every match of a query across all candidate documents.
+    ///
+    /// Unlike the ranked searches this never stops early: each candidate that
+    /// survives trigram and path filtering is verified, matches are counted
+    /// without the per-document cap, and the totals are broken down into
+    /// facets (extension, language, root path, top-level directory).
+    ///
+    /// Matching semantics follow the corresponding search flavour, including
+    /// the filename fallback (a file whose only match is its name counts once).
+    #[tracing::instrument(skip(self))]
+    pub fn count_matches(
+        &self,
+        query: &str,
+        include_patterns: &str,
+        exclude_patterns: &str,
+        kind: QueryKind,
+        scope: &SearchScope,
+    ) -> Result<MatchCounts> {
+        let path_filter = PathFilter::from_delimited(include_patterns, exclude_patterns)?;
+        let query_lower = query.to_lowercase();
+
+        let regex = match kind {
+            QueryKind::Regex => Some(RegexAnalysis::analyze(query)?),
+            _ => None,
+        };
+        let symbol_query = (kind == QueryKind::Symbols).then(|| SymbolQuery::parse(query));
+        let fuzzy = symbol_query
+            .as_ref()
+            .and_then(|q| self.fuzzy_symbol_matches(q, scope));
+
+        let candidate_docs = match (&regex, &fuzzy) {
+            (_, Some(fuzzy)) => fuzzy.files.clone(),
+            (Some(analysis), None) => match analysis.best_literal() {
+                Some(literal) if analysis.is_accelerated => {
+                    self.trigram_index.search(&literal.to_lowercase())
+                }
+                _ => self.trigram_index.all_documents(),
+            },
+            (None, None) => {
+                let literal = match &symbol_query {
+                    Some(q) => q.trigram_literal(),
+                    None => Some(query_lower.as_str()).filter(|q| q.len() >= 3),
+                };
+                match literal {
+                    Some(literal) => self.trigram_index.search(literal),
+                    None => self.trigram_index.all_documents(),
+                }
+            }
+        };
+        let filtered_docs = self.apply_filters(candidate_docs, &path_filter, scope);
+
+        let doc_ids: Vec<u32> = filtered_docs.iter().collect();
+        let per_doc: Vec<(u32, usize)> = doc_ids
+            .par_iter()
+            .map(|&doc_id| {
+                let count = match (&regex, &symbol_query) {
+                    (Some(analysis), _) => {
+                        self.count_in_document(doc_id, |line| analysis.regex.is_match(line))
+                    }
+                    (None, Some(symbol_query)) => {
+                        let file = self.file_store.get(doc_id);
+                        let source = file.as_ref().and_then(|file| file.as_str().ok());
+                        match (self.symbol_cache.get(doc_id as usize), source) {
+                            (Some(symbols), Some(source)) => {
+                                let doc = DocumentSymbols {
+                                    doc_id,
+                                    symbols,
+                                    source: &source,
+                                };
+                                (0..symbols.len())
+                                    .filter(|&idx| {
+                                        self.symbol_match_factor(
+                                            doc,
+                                            idx,
+                                            symbol_query,
+                                            fuzzy.as_ref().map(FuzzyScores::Index),
+                                            scope,
+                                        )
+                                        .is_some()
+                                    })
+                                    .count()
+                            }
+                            _ => 0,
+                        }
+                    }
+                    (None, None) => self.count_in_document(doc_id, |line| {
+                        contains_case_insensitive(line, &query_lower)
+                    }),
+                };
+                (doc_id, count)
+            })
+            .filter(|(_, count)| *count > 0)
+            .collect();
+
+        let mut facets = FacetAccumulator::default();
+        for (doc_id, count) in per_doc {
+            if let Some(file) = self.file_store.get(doc_id) {
+                let display_path = self.make_display_path(&file.path);
+                let root_name = self.root_name_for(&file.path);
+                facets.add(
+                    &file.path,
+                    &display_path,
+                    root_name.as_deref(),
+                    self.file_store.get_language(doc_id),
+                    count,
+                );
+            }
+        }
+
+        self.file_store.evict_all_fallbacks();
+        Ok(facets.finish())
+    }
+
+    /// Count matching lines in a document, falling back to a single match when
+    /// only the file name matches (mirrors the synthetic filename results).
+    fn count_in_document(&self, doc_id: u32, is_match: impl Fn(&str) -> bool) -> usize {
+        let Some(file) = self.file_store.get(doc_id) else {
+            return 0;
+        };
+        let Ok(content) = file.as_str() else {
+            return 0;
+        };
+        let count = content.lines().filter(|line| is_match(line)).count();
+        if count > 0 {
+            return count;
+        }
+        let filename_match = self
+            .symbol_cache
+            .get(doc_id as usize)
+            .map(|symbols| {
+                symbols
+                    .iter()
+                    .any(|s| s.symbol_type == SymbolType::FileName && is_match(&s.name))
+            })
+            .unwrap_or(false);
+        usize::from(filename_match)
+    }
+
+    /// Narrow a candidate set with a path filter (no-op for an empty filter)
+    fn apply_path_filter(
+        &self,
+        candidate_docs: roaring::RoaringBitmap,
+        path_filter: &PathFilter,
+    ) -> roaring::RoaringBitmap {
+        if path_filter.is_empty() {
+            candidate_docs
+        } else {
+            path_filter.filter_documents_with(&candidate_docs, |doc_id| {
+                self.file_store
+                    .get(doc_id)
+                    .map(|f| self.make_display_path(&f.path))
+            })
+        }
+    }
+
+    /// Name of the registered root folder containing `path`, if any
+    fn root_name_for(&self, path: &Path) -> Option<String> {
+        self.root_paths
+            .iter()
+            .find(|root| path.starts_with(root))
+            .and_then(|root| root.file_name())
+            .map(|name| name.to_string_lossy().into_owned())
+    }
+
+    /// Break down the score of a search result into its ranking factors.
+    ///
+    /// The breakdown is recomputed from the index (file metadata, symbols,
+    /// dependency counts and the matched line), so it is only intended for the
+    /// handful of results actually returned to the caller. `scope` must be the
+    /// one the search ran with (symbol filters and fuzzy matching change
+    /// which symbol matched and its score). `ranking` is the info returned
+    /// alongside the results and tells whether fast or full ranking selected
+    /// the documents.
+    pub fn explain_match(
+        &self,
+        m: &SearchMatch,
+        query: &str,
+        kind: QueryKind,
+        scope: &SearchScope,
+        ranking: Option<&SearchRankingInfo>,
+    ) -> Option<ScoreExplanation> {
+        let file = self.file_store.get(m.file_id)?;
+        let content = file.as_str().ok()?;
+        let query_lower = query.to_lowercase();
+        let symbols = self
+            .symbol_cache
+            .get(m.file_id as usize)
+            .map(|s| s.as_slice())
+            .unwrap_or(&[]);
+        let dependency_count = self.dependency_index.get_import_count(m.file_id);
+
+        let symbol_count = symbols.len();
+        let base_score = BaseScoreBreakdown::compute(
+            &file.path,
+            self.file_store.get_language(m.file_id),
+            symbol_count,
+            dependency_count,
+        );
+        let meta = self.get_file_metadata(m.file_id);
+        let file_boost = self.file_boost_breakdown(m.file_id, dependency_count);
+        let filename_stem_boost = meta.filename_stem_boost(&query_lower);
+
+        let ranking = RankingExplanation {
+            mode: match ranking.map(|r| r.mode) {
+                Some(RankMode::Fast) => "fast",
+                _ => "full",
+            },
+            // Regex and symbol searches get no filename boost
+            file_score: {
+                let query_lower = match kind {
+                    QueryKind::Text => query_lower.as_str(),
+                    QueryKind::Regex | QueryKind::Symbols => "",
+                };
+                meta.query_score(
+                    query_lower,
+                    self.ranking.effective_recency_weight(),
+                    file_boost.feedback as f32,
+                )
+            },
+            filename_stem_boost,
+            total_candidates: ranking.map(|r| r.total_candidates),
+            candidates_searched: ranking.map(|r| r.candidates_searched),
+        };
+
+        // The symbol a symbol search matched on this line (the file-name
+        // symbol for filename matches): whether its name equals the query
+        // and its match factor (the fuzzy score for fuzzy searches)
+        let symbol_query = (kind == QueryKind::Symbols).then(|| SymbolQuery::parse(query));
+        let symbol_match = symbol_query.as_ref().and_then(|symbol_query| {
+            let pattern = (scope.fuzzy && symbol_query.field == SymbolField::Name)
+                .then(|| FuzzyPattern::new(&symbol_query.text))
+                .flatten();
+            let fuzzy = pattern.as_ref().map(FuzzyScores::Pattern);
+            let doc = DocumentSymbols {
+                doc_id: m.file_id,
+                symbols,
+                source: &content,
+            };
+            symbols
+                .iter()
+                .enumerate()
+                .filter(|(_, s)| match m.line_number {
+                    0 => s.symbol_type == SymbolType::FileName,
+                    line => s.line + 1 == line && s.symbol_type != SymbolType::FileName,
+                })
+                .filter_map(|(idx, s)| {
+                    let factor = self.symbol_match_factor(doc, idx, symbol_query, fuzzy, scope)?;
+                    Some((symbol_query.is_exact_name(s), factor))
+                })
+                .max_by(|a, b| a.0.cmp(&b.0).then(a.1.total_cmp(&b.1)))
+        });
+        let fuzzy_match = symbol_match
+            .filter(|_| scope.fuzzy)
+            .map(|(_, factor)| factor);
+
+        // Filename-only matches skip line scoring entirely
+        if m.line_number == 0 {
+            return Some(ScoreExplanation {
+                ranking,
+                base_score,
+                line: None,
+                filename_match: Some(3.0),
+                exact_symbol_name: None,
+                fuzzy_match,
+                file_boost,
+                score: 3.0 * fuzzy_match.unwrap_or(1.0) * file_boost.product(),
+            });
+        }
+
+        let line_idx = m.line_number - 1;
+        let line = content.lines().nth(line_idx)?;
+        let is_src_lib = base_score.is_src_lib();
+        let is_symbol_def = symbols
+            .iter()
+            .any(|s| s.is_definition && s.line == line_idx);
+
+        let (line_factors, exact_symbol_name) = match kind {
+            QueryKind::Text => (
+                LineScoreFactors::text(line, query, &query_lower, is_symbol_def, is_src_lib),
+                None,
+            ),
+            QueryKind::Regex => {
+                let analysis = RegexAnalysis::analyze(query).ok()?;
+                (
+                    LineScoreFactors::regex(line, &analysis.regex, is_symbol_def, is_src_lib),
+                    None,
+                )
+            }
+            QueryKind::Symbols => {
+                // Symbol results always get the definition boost, plus ×2 when
+                // the symbol name equals the query (its name segment if qualified)
+                let symbol_query = symbol_query.as_ref()?;
+                let factors = LineScoreFactors::text(
+                    line,
+                    &symbol_query.text,
+                    &symbol_query.text_lower,
+                    true,
+                    is_src_lib,
+                );
+                let exact = symbol_match.is_some_and(|(exact, _)| exact);
+                (factors, exact.then_some(2.0))
+            }
+        };
+        // An exact name replaces the fuzzy score
+        let fuzzy_match = fuzzy_match.filter(|_| exact_symbol_name.is_none());
+
+        let score = line_factors.product()
+            * exact_symbol_name.or(fuzzy_match).unwrap_or(1.0)
+            * file_boost.product();
+        Some(ScoreExplanation {
+            ranking,
+            base_score,
+            line: Some(line_factors),
+            filename_match: None,
+            exact_symbol_name,
+            fuzzy_match,
+            file_boost,
+            score,
+        })
+    }
+
+    /// Names matching a fuzzy symbol query, or `None` when the search is not fuzzy
+    /// (fuzzy matching applies to symbol names, not `sig:`/`doc:` queries)
+    fn fuzzy_symbol_matches(
+        &self,
+        symbol_query: &SymbolQuery,
+        scope: &SearchScope,
+    ) -> Option<FuzzyNameMatches> {
+        if !scope.fuzzy || symbol_query.field != SymbolField::Name {
+            return None;
+        }
+        // A query without letters or digits matches nothing fuzzily
+        let pattern = FuzzyPattern::new(&symbol_query.text)?;
+        Some(self.symbol_names.fuzzy_search(&pattern))
+    }
+
+    /// Score factor for a symbol matching the query, `None` if it does not match.
+    /// Substring matches count fully; fuzzy matches are scaled by their fuzzy score.
+    fn symbol_match_factor(
+        &self,
+        doc: DocumentSymbols,
+        symbol_idx: usize,
+        symbol_query: &SymbolQuery,
+        fuzzy: Option<FuzzyScores>,
+        scope: &SearchScope,
+    ) -> Option<f64> {
+        let symbol = &doc.symbols[symbol_idx];
+        if !scope.accepts_symbol(symbol) {
+            return None;
+        }
+        match fuzzy {
+            Some(fuzzy) if symbol_query.matches_qualifiers(symbol, doc.symbols) => match fuzzy {
+                FuzzyScores::Index(matches) => self
+                    .symbol_names
+                    .symbol_score(matches, doc.doc_id, symbol_idx),
+                FuzzyScores::Pattern(pattern) => pattern.score(&symbol.name),
+            }
+            .map(f64::from),
+            Some(_) => None,
+            None => symbol_query
+                .matches(symbol, doc.symbols, doc.source)
+                .then_some(1.0),
+        }
     }
 
     /// Search for symbols matching the query in a document.
@@ -1697,8 +2660,9 @@ impl SearchEngine {
     fn search_symbols_in_document(
         &self,
         doc_id: u32,
-        original_query: &str,
-        query_lower: &str,
+        symbol_query: &SymbolQuery,
+        fuzzy: Option<&FuzzyNameMatches>,
+        scope: &SearchScope,
     ) -> Option<Vec<SearchMatch>> {
         let file = self.file_store.get(doc_id)?;
         let content = file.as_str().ok()?;
@@ -1706,10 +2670,21 @@ impl SearchEngine {
         // Get symbols for this file
         let symbols = self.symbol_cache.get(doc_id as usize)?;
 
-        // Find symbols matching the query
-        let matching_symbols: Vec<&Symbol> = symbols
+        // Find symbols matching the query: by name, qualified name (`Parser::parse`),
+        // signature (`sig:`), doc comment (`doc:`) or fuzzy name, with a score factor
+        let doc = DocumentSymbols {
+            doc_id,
+            symbols,
+            source: &content,
+        };
+        let matching_symbols: Vec<(&Symbol, f64)> = symbols
             .iter()
-            .filter(|s| contains_case_insensitive(&s.name, query_lower))
+            .enumerate()
+            .filter_map(|(idx, s)| {
+                let fuzzy = fuzzy.map(FuzzyScores::Index);
+                let factor = self.symbol_match_factor(doc, idx, symbol_query, fuzzy, scope)?;
+                Some((s, factor))
+            })
             .collect();
 
         if matching_symbols.is_empty() {
@@ -1719,12 +2694,8 @@ impl SearchEngine {
         // Get dependency count for this file
         let dependency_count = self.dependency_index.get_import_count(doc_id);
 
-        // Pre-compute dependency boost
-        let dependency_boost = if dependency_count > 0 {
-            1.0 + (dependency_count as f64).log10() * 0.5
-        } else {
-            1.0
-        };
+        // Pre-compute dependency and recency boost (done once per document, not per match)
+        let file_boost = self.file_boost(doc_id, dependency_count);
 
         // Lazy-compute path info
         let raw_path_str = file.path.to_string_lossy().into_owned();
@@ -1735,18 +2706,24 @@ impl SearchEngine {
             || contains_bytes(path_bytes, b"\\lib\\");
         let display_path = self.make_display_path(&file.path);
 
+        // Lines are highlighted and scored against the query text (the name
+        // segment of a qualified query)
+        let query_text = symbol_query.text.as_str();
+        let query_text_lower = symbol_query.text_lower.as_str();
+
         // Collect lines into a vector for indexed access
         let lines: Vec<&str> = content.lines().collect();
 
         // Build matches from matching symbols
         let mut matches = Vec::with_capacity(matching_symbols.len());
 
-        for symbol in matching_symbols {
+        for (symbol, match_factor) in matching_symbols {
             // FileName symbols are synthetic (not from file content) — show the file path
             if symbol.symbol_type == SymbolType::FileName {
                 let display = display_path.clone();
                 let (match_start, match_end) =
-                    find_match_position_case_insensitive(&display, query_lower).unwrap_or((0, 0));
+                    find_match_position_case_insensitive(&display, query_text_lower)
+                        .unwrap_or((0, 0));
                 matches.push(SearchMatch {
                     file_id: doc_id,
                     file_path: display_path.clone(),
@@ -1755,8 +2732,13 @@ impl SearchEngine {
                     match_start,
                     match_end,
                     content_truncated: false,
-                    score: 3.0 * dependency_boost,
+                    score: 3.0 * file_boost * match_factor,
                     is_symbol: true,
+                    is_reference: false,
+                    container: None,
+                    signature: None,
+                    doc: None,
+                    symbol_type: None,
                     dependency_count,
                 });
                 continue;
@@ -1770,7 +2752,7 @@ impl SearchEngine {
 
             // Find where the symbol name appears in the line
             let (match_start, match_end) =
-                match find_match_position_case_insensitive(line, query_lower) {
+                match find_match_position_case_insensitive(line, query_text_lower) {
                     Some(pos) => pos,
                     None => {
                         // Try to find the symbol name instead
@@ -1782,21 +2764,20 @@ impl SearchEngine {
             // Calculate score - symbols always get the symbol definition boost
             let base_score = calculate_score_inline(
                 line,
-                original_query,
-                query_lower,
+                query_text,
+                query_text_lower,
                 true,
                 is_src_lib,
-                dependency_boost,
+                file_boost,
             );
 
             // Boost exact symbol name matches over partial/subset matches.
             // An exact match (symbol.name == query, case-insensitive) scores 2x higher
             // than a partial match (e.g. query "calc" matching symbol "calculate").
-            let name_lower = symbol.name.to_lowercase();
-            let score = if name_lower == query_lower {
+            let score = if symbol_query.is_exact_name(symbol) {
                 base_score * 2.0
             } else {
-                base_score
+                base_score * match_factor
             };
 
             // Truncate long lines around the match
@@ -1812,6 +2793,11 @@ impl SearchEngine {
                 content_truncated: truncated.was_truncated,
                 score,
                 is_symbol: true,
+                is_reference: false,
+                container: symbol.container(symbols),
+                signature: symbol.signature(&content),
+                doc: symbol.doc(&content),
+                symbol_type: Some(symbol.symbol_type.clone()),
                 dependency_count,
             });
         }
@@ -1834,12 +2820,8 @@ impl SearchEngine {
         // Get dependency count for this file (cached lookup - done once per document)
         let dependency_count = self.dependency_index.get_import_count(doc_id);
 
-        // Pre-compute dependency boost (done once per document, not per match)
-        let dependency_boost = if dependency_count > 0 {
-            1.0 + (dependency_count as f64).log10() * 0.5
-        } else {
-            1.0
-        };
+        // Pre-compute dependency and recency boost (done once per document, not per match)
+        let file_boost = self.file_boost(doc_id, dependency_count);
 
         // Use a simple Vec to store symbol definition lines - faster than HashSet for small N
         // Most files have <100 symbols, linear scan is faster than hash overhead
@@ -1909,7 +2891,7 @@ impl SearchEngine {
                     regex,
                     is_symbol_def,
                     is_src_lib,
-                    dependency_boost,
+                    file_boost,
                 );
 
                 // Check if this is a symbol match using the pre-computed per-line map (O(1) lookup)
@@ -1931,6 +2913,11 @@ impl SearchEngine {
                     content_truncated: truncated.was_truncated,
                     score,
                     is_symbol,
+                    is_reference: false,
+                    container: None,
+                    signature: None,
+                    doc: None,
+                    symbol_type: None,
                     dependency_count,
                 });
             }
@@ -1958,8 +2945,13 @@ impl SearchEngine {
                     match_start,
                     match_end,
                     content_truncated: false,
-                    score: 3.0 * dependency_boost,
+                    score: 3.0 * file_boost,
                     is_symbol: true,
+                    is_reference: false,
+                    container: None,
+                    signature: None,
+                    doc: None,
+                    symbol_type: None,
                     dependency_count,
                 });
             }
@@ -1999,12 +2991,8 @@ impl SearchEngine {
         // Get dependency count for this file (cached lookup - done once per document)
         let dependency_count = self.dependency_index.get_import_count(doc_id);
 
-        // Pre-compute dependency boost (done once per document, not per match)
-        let dependency_boost = if dependency_count > 0 {
-            1.0 + (dependency_count as f64).log10() * 0.5
-        } else {
-            1.0
-        };
+        // Pre-compute dependency and recency boost (done once per document, not per match)
+        let file_boost = self.file_boost(doc_id, dependency_count);
 
         // Use a simple Vec to store symbol definition lines - faster than HashSet for small N
         // Most files have <100 symbols, linear scan is faster than hash overhead
@@ -2081,7 +3069,7 @@ impl SearchEngine {
                     query_lower,
                     is_symbol_def,
                     is_src_lib,
-                    dependency_boost,
+                    file_boost,
                 );
 
                 // Check if this is a symbol match using the pre-computed per-line map (O(1) lookup)
@@ -2107,6 +3095,11 @@ impl SearchEngine {
                     content_truncated: truncated.was_truncated,
                     score,
                     is_symbol,
+                    is_reference: false,
+                    container: None,
+                    signature: None,
+                    doc: None,
+                    symbol_type: None,
                     dependency_count,
                 });
             }
@@ -2135,8 +3128,13 @@ impl SearchEngine {
                     match_start,
                     match_end,
                     content_truncated: false,
-                    score: 3.0 * dependency_boost, // Symbol def boost (3×) for filename matches
+                    score: 3.0 * file_boost, // Symbol def boost (3×) for filename matches
                     is_symbol: true,
+                    is_reference: false,
+                    container: None,
+                    signature: None,
+                    doc: None,
+                    symbol_type: None,
                     dependency_count,
                 });
             }
@@ -2169,33 +3167,665 @@ impl SearchEngine {
         self.dependency_index.get_dependencies(file_id)
     }
 
-    /// Get file path by ID as a root-relative display string.
-    pub fn get_file_path(&self, file_id: u32) -> Option<String> {
-        self.file_store
-            .get(file_id)
-            .map(|f| self.make_display_path(&f.path))
+    /// Files up to `max_depth` import hops away from the given file in `direction`,
+    /// grouped by distance and capped at `max_files`
+    pub fn dependency_layers(
+        &self,
+        file_id: u32,
+        direction: DependencyDirection,
+        max_depth: usize,
+        max_files: usize,
+    ) -> DependencyLayers {
+        self.dependency_index
+            .walk(file_id, direction, max_depth, max_files)
     }
 
-    /// Find file ID by path.
-    ///
-    /// Tries an exact match first (O(1)), then falls back to suffix matching
-    /// (O(n)) so that partial paths like `"src/main.rs"` resolve correctly.
-    /// Suffix matching uses `ends_with` to avoid false positives from substring
-    /// matches (e.g. looking for `"bar.rs"` will NOT match `"bar_extra.rs"`).
-    pub fn find_file_id(&self, path: &str) -> Option<u32> {
-        // 1. Exact path match (O(1))
-        if let Some(id) = self
-            .file_store
-            .find_by_exact_path(std::path::Path::new(path))
-        {
-            return Some(id);
-        }
-        // 2. Suffix / partial-path match (O(n))
-        self.file_store.find_by_path_suffix(path)
+    /// The given file plus every file up to `hops` import hops away in
+    /// `direction`, for scoping searches with [`SearchScope::within`]
+    pub fn dependency_neighborhood(
+        &self,
+        file_id: u32,
+        direction: DependencyDirection,
+        hops: usize,
+    ) -> roaring::RoaringBitmap {
+        let mut files: roaring::RoaringBitmap = self
+            .dependency_index
+            .walk(file_id, direction, hops, usize::MAX)
+            .files()
+            .collect();
+        files.insert(file_id);
+        files
     }
 
-    /// Save the index to a file for persistence
-    pub fn save_index(
+    /// Blast radius of a change to the given file: its transitive dependents
+    /// by distance, and those that form an import cycle with it
+    pub fn impact(&self, file_id: u32, max_depth: usize, max_files: usize) -> ImpactAnalysis {
+        self.dependency_index.impact(file_id, max_depth, max_files)
+    }
+
+    /// The import graph at `level` granularity, limited to files whose display
+    /// path starts with `prefix` (both ends of an edge must match)
+    pub fn dependency_graph(&self, prefix: Option<&str>, level: GraphLevel) -> DependencyGraph {
+        let prefix = prefix.map(|p| p.trim_start_matches("./")).unwrap_or("");
+        let mut positions: FxHashMap<u32, Option<usize>> = FxHashMap::default();
+        let mut files: Vec<(String, String)> = Vec::new();
+        // Package name -> manifest of the first package seen with that name
+        let mut package_names: FxHashMap<String, PathBuf> = FxHashMap::default();
+        let mut position = |file_id: u32| -> Option<usize> {
+            *positions.entry(file_id).or_insert_with(|| {
+                let path = self.file_store.get_path(file_id)?;
+                let display = self.make_display_path(path);
+                if !display.starts_with(prefix) {
+                    return None;
+                }
+                let parent = |display: &str| {
+                    display
+                        .rsplit_once('/')
+                        .map_or_else(String::new, |(dir, _)| dir.to_string())
+                };
+                let node = match level {
+                    GraphLevel::File => display.clone(),
+                    GraphLevel::Directory => parent(&display),
+                    GraphLevel::Package => match self.dependency_index.package(path) {
+                        Some(package) => {
+                            let owner = package_names
+                                .entry(package.name.clone())
+                                .or_insert_with(|| package.manifest.clone());
+                            if *owner == package.manifest {
+                                package.name.clone()
+                            } else {
+                                self.make_display_path(package.dir())
+                            }
+                        }
+                        None => parent(&display),
+                    },
+                };
+                files.push((display, node));
+                Some(files.len() - 1)
+            })
+        };
+
+        let mut edges = self.dependency_index.get_all_edges();
+        edges.sort_unstable();
+        let edges: Vec<(usize, usize)> = edges
+            .into_iter()
+            .filter_map(|(from, to)| Some((position(from)?, position(to)?)))
+            .collect();
+        DependencyGraph::build(level, &files, &edges)
+    }
+
+    /// The package containing a file, from its nearest package manifest
+    pub fn file_package(&self, file_id: u32) -> Option<Arc<PackageInfo>> {
+        self.dependency_index
+            .package(self.file_store.get_path(file_id)?)
+    }
+
+    /// Packages of the indexed files whose manifest display path starts with
+    /// `prefix`, sorted by name. Declared dependencies are split into those on
+    /// other indexed packages and third-party ones.
+    pub fn packages(&self, prefix: Option<&str>) -> Vec<PackageSummary> {
+        let prefix = prefix.map(|p| p.trim_start_matches("./")).unwrap_or("");
+        let mut packages: FxHashMap<PathBuf, (Arc<PackageInfo>, usize)> = FxHashMap::default();
+        for file_id in 0..self.file_store.len() as u32 {
+            if let Some(package) = self.file_package(file_id) {
+                packages
+                    .entry(package.manifest.clone())
+                    .or_insert((package, 0))
+                    .1 += 1;
+            }
+        }
+        let names: FxHashSet<&str> = packages.values().map(|(p, _)| p.name.as_str()).collect();
+
+        let mut summaries: Vec<PackageSummary> = packages
+            .values()
+            .map(|(package, files)| {
+                let (workspace_dependencies, external_dependencies) = package
+                    .dependencies
+                    .iter()
+                    .cloned()
+                    .partition(|dependency| names.contains(dependency.as_str()));
+                PackageSummary {
+                    name: package.name.clone(),
+                    version: package.version.clone(),
+                    ecosystem: package.ecosystem,
+                    manifest: self.make_display_path(&package.manifest),
+                    files: *files,
+                    workspace_dependencies,
+                    external_dependencies,
+                }
+            })
+            .filter(|summary| summary.manifest.starts_with(prefix))
+            .collect();
+        summaries.sort_by(|a, b| {
+            a.name
+                .cmp(&b.name)
+                .then_with(|| a.manifest.cmp(&b.manifest))
+        });
+        summaries
+    }
+
+    /// Dead code candidates under `prefix`: files nothing imports and, with
+    /// `symbols`, exported definitions whose name appears in no other file,
+    /// grouped by directory (at most `max_directories`)
+    pub fn dead_code(
+        &self,
+        prefix: Option<&str>,
+        symbols: bool,
+        max_directories: usize,
+    ) -> DeadCodeReport {
+        let prefix = prefix.map(|p| p.trim_start_matches("./")).unwrap_or("");
+        let per_file: Vec<(bool, Option<String>, Vec<UnusedSymbol>)> = (0..self.file_store.len()
+            as u32)
+            .into_par_iter()
+            .filter_map(|file_id| {
+                let display = self.make_display_path(self.file_store.get_path(file_id)?);
+                let language = self.file_store.get_language(file_id)?;
+                if !display.starts_with(prefix)
+                    || (!self.entrypoints.is_empty() && self.entrypoints.matches(&display))
+                {
+                    return None;
+                }
+                let checked = dead_code::checks_importers(language);
+                if checked && self.dependency_index.get_import_count(file_id) == 0 {
+                    return Some((true, Some(display), Vec::new()));
+                }
+                let unused = if symbols {
+                    self.unused_symbols(file_id, language, &display)
+                } else {
+                    Vec::new()
+                };
+                Some((checked, None, unused))
+            })
+            .collect();
+
+        let files_checked = per_file.iter().filter(|(checked, _, _)| *checked).count();
+        let mut files = Vec::new();
+        let mut unused = Vec::new();
+        for (_, file, symbols) in per_file {
+            files.extend(file);
+            unused.extend(symbols);
+        }
+        DeadCodeReport::group(files_checked, files, unused, max_directories)
+    }
+
+    /// Exported top-level definitions of a file whose name appears in no other file
+    fn unused_symbols(&self, file_id: u32, language: Language, display: &str) -> Vec<UnusedSymbol> {
+        let Some(symbols) = self.symbol_cache.get(file_id as usize) else {
+            return Vec::new();
+        };
+        if !symbols.iter().any(|s| s.is_definition && s.is_top_level()) {
+            return Vec::new();
+        }
+        let Some(content) = self
+            .file_store
+            .get(file_id)
+            .and_then(|file| file.as_str().ok())
+        else {
+            return Vec::new();
+        };
+        let lines: Vec<&str> = content.lines().collect();
+        symbols
+            .iter()
+            .filter(|symbol| {
+                let line = lines.get(symbol.line).copied().unwrap_or_default();
+                dead_code::is_exported(language, symbol, &content, line)
+                    && !self.name_appears_elsewhere(&symbol.name, file_id)
+            })
+            .map(|symbol| UnusedSymbol {
+                file: display.to_string(),
+                name: symbol.name.clone(),
+                kind: symbol.symbol_type.clone(),
+                line: symbol.line + 1,
+            })
+            .collect()
+    }
+
+    /// Whether `name` is referenced by, or occurs as a whole word in, any file
+    /// other than `file_id`. Names too short for the trigram index count as used.
+    fn name_appears_elsewhere(&self, name: &str, file_id: u32) -> bool {
+        if name.len() < 3 {
+            return true;
+        }
+        let mut referencing = self.reference_index.files_referencing(name);
+        referencing.remove(file_id);
+        if !referencing.is_empty() {
+            return true;
+        }
+
+        // Trigrams are stored lowercased; the word-boundary pass below
+        // confirms the exact case
+        let mut candidates = self.trigram_index.search(&name.to_lowercase());
+        candidates.remove(file_id);
+        let finder = memmem::Finder::new(name.as_bytes());
+        let is_word_byte = |b: u8| b.is_ascii_alphanumeric() || b == b'_';
+        candidates.iter().any(|doc_id| {
+            let Some(content) = self
+                .file_store
+                .get(doc_id)
+                .and_then(|file| file.as_str().ok())
+            else {
+                return false;
+            };
+            let bytes = content.as_bytes();
+            finder.find_iter(bytes).any(|start| {
+                let end = start + name.len();
+                (start == 0 || !is_word_byte(bytes[start - 1]))
+                    && bytes.get(end).is_none_or(|&b| !is_word_byte(b))
+            })
+        })
+    }
+
+    /// Import cycles with at least one file whose display path starts with
+    /// `prefix`: counts and size distribution over all of them, and the
+    /// `limit` largest with the lines of the imports that form them
+    pub fn cycle_stats(&self, prefix: Option<&str>, limit: usize) -> CycleStats {
+        let prefix = prefix.map(|p| p.trim_start_matches("./")).unwrap_or("");
+        let display = |id: u32| {
+            self.file_store
+                .get_path(id)
+                .map(|path| self.make_display_path(path))
+                .unwrap_or_default()
+        };
+        let mut cycles: Vec<(Vec<u32>, Vec<String>)> = self
+            .dependency_index
+            .import_cycles()
+            .into_iter()
+            .map(|members| {
+                let mut paths: Vec<String> = members.iter().map(|&id| display(id)).collect();
+                paths.sort();
+                (members, paths)
+            })
+            .filter(|(_, paths)| paths.iter().any(|p| p.starts_with(prefix)))
+            .collect();
+        cycles.sort_by(|a, b| b.0.len().cmp(&a.0.len()).then_with(|| a.1.cmp(&b.1)));
+
+        let mut stats = CycleStats::from_sizes(cycles.iter().map(|(members, _)| members.len()));
+        stats.largest_cycles = cycles
+            .into_iter()
+            .take(limit)
+            .map(|(members, files)| {
+                let mut edges: Vec<CycleEdge> = members
+                    .iter()
+                    .flat_map(|&from| {
+                        self.dependency_index
+                            .get_dependencies(from)
+                            .into_iter()
+                            .filter(|to| members.binary_search(to).is_ok())
+                            .map(move |to| (from, to))
+                    })
+                    .map(|(from, to)| CycleEdge {
+                        from: display(from),
+                        to: display(to),
+                        lines: self
+                            .dependency_index
+                            .import_lines(from, to)
+                            .iter()
+                            .map(|&line| line as usize + 1)
+                            .collect(),
+                    })
+                    .collect();
+                edges.sort_by(|a, b| (&a.from, &a.to).cmp(&(&b.from, &b.to)));
+                ImportCycle { files, edges }
+            })
+            .collect();
+        stats
+    }
+
+    /// Imports of the given file that matched no indexed file
+    pub fn get_unresolved_imports(&self, file_id: u32) -> &[UnresolvedImport] {
+        self.dependency_index.get_unresolved_imports(file_id)
+    }
+
+    /// Files with unresolved imports whose display path starts with `prefix`,
+    /// sorted by display path
+    pub fn files_with_unresolved_imports(&self, prefix: Option<&str>) -> Vec<(u32, String)> {
+        let prefix = prefix.map(|p| p.trim_start_matches("./")).unwrap_or("");
+        let mut files: Vec<(u32, String)> = self
+            .dependency_index
+            .files_with_unresolved_imports()
+            .filter_map(|id| {
+                let display = self.make_display_path(self.file_store.get_path(id)?);
+                display.starts_with(prefix).then_some((id, display))
+            })
+            .collect();
+        files.sort_by(|a, b| a.1.cmp(&b.1));
+        files
+    }
+
+    /// Unresolved import counts per language of the importing file and the
+    /// `max_modules` third-party modules imported by the most files
+    pub fn unresolved_import_stats(
+        &self,
+        file_ids: impl IntoIterator<Item = u32>,
+        max_modules: usize,
+    ) -> UnresolvedImportStats {
+        UnresolvedImportStats::from_files(
+            file_ids.into_iter().map(|id| {
+                let language = self
+                    .file_store
+                    .get_language(id)
+                    .map_or("Other", Language::name);
+                (language, self.dependency_index.get_unresolved_imports(id))
+            }),
+            max_modules,
+        )
+    }
+
+    /// Imports of the given file that matched several files and were not linked
+    pub fn get_ambiguous_imports(&self, file_id: u32) -> &[AmbiguousImport] {
+        self.dependency_index.get_ambiguous_imports(file_id)
+    }
+
+    /// Import hops followed from the defining file when ranking references
+    const MAX_REFERENCE_HOPS: u32 = 3;
+
+    /// Find usages of a symbol: identifier references recorded at index time.
+    ///
+    /// `defining_file` pins the definition whose usages are wanted; without it
+    /// every file that defines `symbol_name` counts as a definition site.
+    /// References are ranked by proximity to the definition in the dependency
+    /// graph — same file first, then files importing it directly, then
+    /// transitively (up to `MAX_REFERENCE_HOPS`), then unrelated files, with
+    /// files in the definition's directory ahead of other unrelated ones.
+    #[tracing::instrument(skip(self))]
+    pub fn find_references(
+        &self,
+        symbol_name: &str,
+        defining_file: Option<&str>,
+        max_results: usize,
+    ) -> Result<Vec<SearchMatch>> {
+        let definition_files: Vec<u32> = match defining_file {
+            Some(path) => match self.find_file_id(path) {
+                Some(id) => vec![id],
+                None => anyhow::bail!("File not found in index: {}", path),
+            },
+            None => self
+                .symbol_cache
+                .iter()
+                .enumerate()
+                .filter(|(_, symbols)| {
+                    symbols.iter().any(|s| {
+                        s.is_definition
+                            && s.symbol_type != SymbolType::FileName
+                            && s.name == symbol_name
+                    })
+                })
+                .map(|(id, _)| id as u32)
+                .collect(),
+        };
+
+        // Breadth-first walk over dependents: distance = import hops to a definition
+        let mut distances: FxHashMap<u32, u32> = FxHashMap::default();
+        let mut frontier = definition_files.clone();
+        for &id in &frontier {
+            distances.insert(id, 0);
+        }
+        for hop in 1..=Self::MAX_REFERENCE_HOPS {
+            let mut next = Vec::new();
+            for id in frontier {
+                for dependent in self.dependency_index.get_dependents(id) {
+                    if let std::collections::hash_map::Entry::Vacant(e) = distances.entry(dependent)
+                    {
+                        e.insert(hop);
+                        next.push(dependent);
+                    }
+                }
+            }
+            frontier = next;
+        }
+
+        let definition_dirs: FxHashSet<&Path> = definition_files
+            .iter()
+            .filter_map(|&id| self.file_store.get_path(id)?.parent())
+            .collect();
+
+        let doc_ids: Vec<u32> = self
+            .reference_index
+            .files_referencing(symbol_name)
+            .iter()
+            .collect();
+        let mut matches: Vec<SearchMatch> = doc_ids
+            .par_iter()
+            .filter_map(|&doc_id| {
+                let file = self.file_store.get(doc_id)?;
+                let content = file.as_str().ok()?;
+                let proximity = match distances.get(&doc_id) {
+                    Some(&hops) => 1.0 + 3.0 / (1.0 + hops as f64),
+                    None if file
+                        .path
+                        .parent()
+                        .is_some_and(|dir| definition_dirs.contains(dir)) =>
+                    {
+                        1.5
+                    }
+                    None => 1.0,
+                };
+                let dependency_count = self.dependency_index.get_import_count(doc_id);
+                let score = proximity * self.file_boost(doc_id, dependency_count);
+                let display_path = self.make_display_path(&file.path);
+                let lines: Vec<&str> = content.lines().collect();
+
+                let file_matches: Vec<SearchMatch> = self
+                    .reference_index
+                    .references_in(doc_id, symbol_name)
+                    .into_iter()
+                    .take(Self::MAX_MATCHES_PER_DOC)
+                    .filter_map(|(line, column)| {
+                        let text = lines.get(line as usize)?;
+                        let start = column as usize;
+                        let end = start + symbol_name.len();
+                        if text.get(start..end) != Some(symbol_name) {
+                            return None; // Stale position (file changed since indexing)
+                        }
+                        let truncated = truncate_around_match(text, start, end);
+                        Some(SearchMatch {
+                            file_id: doc_id,
+                            file_path: display_path.clone(),
+                            line_number: line as usize + 1,
+                            content: truncated.content,
+                            match_start: truncated.match_start,
+                            match_end: truncated.match_end,
+                            content_truncated: truncated.was_truncated,
+                            score,
+                            is_symbol: false,
+                            is_reference: true,
+                            container: None,
+                            signature: None,
+                            doc: None,
+                            symbol_type: None,
+                            dependency_count,
+                        })
+                    })
+                    .collect();
+                Some(file_matches)
+            })
+            .flatten()
+            .collect();
+
+        // Rank, keeping a stable reading order among equally-ranked references
+        matches.sort_unstable_by(|a, b| {
+            b.score
+                .partial_cmp(&a.score)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| a.file_path.cmp(&b.file_path))
+                .then_with(|| a.line_number.cmp(&b.line_number))
+        });
+        matches.truncate(max_results);
+        self.file_store.evict_all_fallbacks();
+        Ok(matches)
+    }
+
+    /// Go to definition: resolve the identifier at `line`/`column` (both
+    /// 1-based) in `path` to the symbols defining it. `column` counts UTF-16
+    /// code units, like LSP positions, and so do the candidates' columns.
+    ///
+    /// Candidates are looked up by exact name in the symbol name index. When a name
+    /// is defined in several places, definitions in the file itself come
+    /// first, then those in files it imports (resolved dependencies), then
+    /// the same directory, then the rest of the index.
+    pub fn find_definition(
+        &self,
+        path: &str,
+        line: usize,
+        column: usize,
+        max_results: usize,
+    ) -> Result<DefinitionLookup> {
+        let file_id = self
+            .find_file_id(path)
+            .ok_or_else(|| anyhow::anyhow!("File not found in index: {}", path))?;
+        let file = self
+            .file_store
+            .get(file_id)
+            .ok_or_else(|| anyhow::anyhow!("File not found in index: {}", path))?;
+        let content = file.as_str()?;
+        let text = content
+            .lines()
+            .nth(line.saturating_sub(1))
+            .filter(|_| line > 0)
+            .ok_or_else(|| anyhow::anyhow!("Line {} is out of range for {}", line, path))?;
+        let byte_column = utf16_column_to_byte(text, column.saturating_sub(1));
+        let (start, end) = identifier_at(text, byte_column)
+            .ok_or_else(|| anyhow::anyhow!("No identifier at {}:{}:{}", path, line, column))?;
+        let identifier = text[start..end].to_string();
+        let file_dir = file.path.parent();
+
+        let imported: FxHashSet<u32> = self
+            .dependency_index
+            .get_dependencies(file_id)
+            .into_iter()
+            .collect();
+
+        let doc_ids: Vec<u32> = self
+            .symbol_names
+            .files_with_name(&identifier)
+            .map(|files| files.iter().collect())
+            .unwrap_or_default();
+        let mut candidates: Vec<DefinitionCandidate> = doc_ids
+            .par_iter()
+            .filter_map(|&doc_id| {
+                let symbols = self.symbol_cache.get(doc_id as usize)?;
+                let doc = self.file_store.get(doc_id)?;
+                let doc_content = doc.as_str().ok()?;
+                let scope = if doc_id == file_id {
+                    DefinitionScope::SameFile
+                } else if imported.contains(&doc_id) {
+                    DefinitionScope::Imported
+                } else if doc.path.parent() == file_dir {
+                    DefinitionScope::SameDirectory
+                } else {
+                    DefinitionScope::Global
+                };
+                let dependency_count = self.dependency_index.get_import_count(doc_id);
+                let score = scope.weight() * self.file_boost(doc_id, dependency_count);
+                let display_path = self.make_display_path(&doc.path);
+                let lines: Vec<&str> = doc_content.lines().collect();
+
+                let doc_matches: Vec<DefinitionCandidate> = symbols
+                    .iter()
+                    .filter(|s| {
+                        s.is_definition
+                            && s.symbol_type != SymbolType::FileName
+                            && s.name == identifier
+                    })
+                    .filter_map(|symbol| {
+                        let text = lines.get(symbol.line)?;
+                        // Symbol positions point at the start of the definition
+                        // node; locate the name itself from there
+                        let (match_start, match_end) = text
+                            .get(symbol.column..)
+                            .and_then(|rest| rest.find(identifier.as_str()))
+                            .map(|offset| symbol.column + offset)
+                            .or_else(|| text.find(identifier.as_str()))
+                            .map(|pos| (pos, pos + identifier.len()))
+                            .unwrap_or((0, 0));
+                        let truncated = truncate_around_match(text, match_start, match_end);
+                        Some(DefinitionCandidate {
+                            result: SearchMatch {
+                                file_id: doc_id,
+                                file_path: display_path.clone(),
+                                line_number: symbol.line + 1,
+                                content: truncated.content,
+                                match_start: truncated.match_start,
+                                match_end: truncated.match_end,
+                                content_truncated: truncated.was_truncated,
+                                score,
+                                is_symbol: true,
+                                is_reference: false,
+                                container: symbol.container(symbols),
+                                signature: symbol.signature(&doc_content),
+                                doc: symbol.doc(&doc_content),
+                                symbol_type: Some(symbol.symbol_type.clone()),
+                                dependency_count,
+                            },
+                            scope,
+                            column: byte_to_utf16_column(text, match_start) + 1,
+                        })
+                    })
+                    .collect();
+                Some(doc_matches)
+            })
+            .flatten()
+            .collect();
+
+        candidates.sort_by(|a, b| {
+            a.scope
+                .cmp(&b.scope)
+                .then_with(|| {
+                    b.result
+                        .score
+                        .partial_cmp(&a.result.score)
+                        .unwrap_or(std::cmp::Ordering::Equal)
+                })
+                .then_with(|| a.result.file_path.cmp(&b.result.file_path))
+                .then_with(|| a.result.line_number.cmp(&b.result.line_number))
+        });
+        candidates.truncate(max_results);
+        self.file_store.evict_all_fallbacks();
+
+        Ok(DefinitionLookup {
+            identifier,
+            candidates,
+        })
+    }
+
+    /// Get file path by ID as a root-relative display string.
+    pub fn get_file_path(&self, file_id: u32) -> Option<String> {
+        self.file_store
+            .get(file_id)
+            .map(|f| self.make_display_path(&f.path))
+    }
+
+    /// Outline of a file: its symbol definitions as a tree, built from the
+    /// symbol cache without re-parsing
+    pub fn file_outline(&self, file_id: u32) -> Vec<OutlineNode> {
+        let Some(symbols) = self.symbol_cache.get(file_id as usize) else {
+            return Vec::new();
+        };
+        let file = self.file_store.get(file_id);
+        let source = file.as_ref().and_then(|file| file.as_str().ok());
+        build_outline(symbols, source.as_deref().unwrap_or(""))
+    }
+
+    /// Find file ID by path.
+    ///
+    /// Tries an exact match first (O(1)), then falls back to suffix matching
+    /// (O(n)) so that partial paths like `"src/main.rs"` resolve correctly.
+    /// Suffix matching uses `ends_with` to avoid false positives from substring
+    /// matches (e.g. looking for `"bar.rs"` will NOT match `"bar_extra.rs"`).
+    pub fn find_file_id(&self, path: &str) -> Option<u32> {
+        // 1. Exact path match (O(1))
+        if let Some(id) = self
+            .file_store
+            .find_by_exact_path(std::path::Path::new(path))
+        {
+            return Some(id);
+        }
+        // 2. Suffix / partial-path match (O(n))
+        self.file_store.find_by_path_suffix(path)
+    }
+
+    /// Save the index to a file for persistence
+    pub fn save_index(
         &self,
         path: &std::path::Path,
         config: &crate::config::IndexerConfig,
@@ -2237,6 +3867,8 @@ impl SearchEngine {
                     mtime,
                     size: size as u64,
                     source_base_path: source_base,
+                    content_hash: self.file_store.get_content_hash(id).unwrap_or(0),
+                    language: self.file_store.get_language(id),
                 });
             }
         }
@@ -2250,7 +3882,7 @@ impl SearchEngine {
         let dependency_edges = self.dependency_index.get_all_edges();
 
         // Create persisted index with config fingerprint
-        let persisted = PersistedIndex::new(
+        let mut persisted = PersistedIndex::new(
             config.fingerprint(),
             config.paths.clone(),
             files,
@@ -2258,6 +3890,18 @@ impl SearchEngine {
             symbols,
             dependency_edges,
         )?;
+        persisted.dependency_edge_lines = persisted
+            .dependency_edges
+            .iter()
+            .map(|&(from, to)| self.dependency_index.import_lines(from, to).to_vec())
+            .collect();
+        persisted.unresolved_imports = (0..self.file_store.len() as u32)
+            .map(|id| self.dependency_index.get_unresolved_imports(id).to_vec())
+            .collect();
+        persisted.reference_names = self.reference_index.names();
+        persisted.references = (0..self.file_store.len() as u32)
+            .map(|id| self.reference_index.file_references(id).to_vec())
+            .collect();
         persisted.save(path)?;
 
         tracing::info!(
@@ -2672,8 +4316,15 @@ impl SearchEngine {
         Ok(stale_files)
     }
 
+    /// Forget cached project configuration read from `path` (a manifest or
+    /// tsconfig that was deleted or renamed away)
+    pub fn invalidate_project_config(&self, path: &std::path::Path) {
+        self.dependency_index.invalidate_project_config(path);
+    }
+
     /// Update the index for a single file (for incremental indexing)
     pub fn update_file(&mut self, path: &std::path::Path) -> anyhow::Result<()> {
+        self.dependency_index.invalidate_project_config(path);
         // For now, just re-index the file
         // A more sophisticated implementation could track document IDs
         // and update only the affected trigrams
@@ -2709,8 +4360,11 @@ struct RebuildEntry {
     file_id: u32,
     path: std::path::PathBuf,
     symbols: Vec<Symbol>,
-    imports: Vec<String>,
+    imports: Vec<ImportStatement>,
     had_content: bool,
+    content_hash: u64,
+    language: Option<Language>,
+    references: Vec<SymbolReference>,
 }
 
 /// Result of loading a persisted index with reconciliation
@@ -3512,8 +5166,8 @@ fn calculate(x: f64, y: f64) -> f64 { x + y }
         );
 
         // Query score should boost when query matches the filename stem
-        let score_match = metadata.query_score("mymodule");
-        let score_nomatch = metadata.query_score("unrelated");
+        let score_match = metadata.query_score("mymodule", 0.0, 1.0);
+        let score_nomatch = metadata.query_score("unrelated", 0.0, 1.0);
         assert!(
             score_match > score_nomatch,
             "Query matching filename stem ({:.3}) should score higher than non-match ({:.3})",
@@ -3963,4 +5617,1766 @@ fn calculate(x: f64, y: f64) -> f64 { x + y }
         assert_eq!(display_a, "alpha/utils.rs");
         assert_eq!(display_b, "beta/utils.rs");
     }
+
+    /// Exact counting is not capped per document and reports facets.
+    #[test]
+    fn test_count_matches_exact_with_facets() {
+        let temp_dir = TempDir::new().unwrap();
+        let project_dir = temp_dir.path().join("project");
+        let src_dir = project_dir.join("src");
+        let docs_dir = project_dir.join("docs");
+        fs::create_dir_all(&src_dir).unwrap();
+        fs::create_dir_all(&docs_dir).unwrap();
+
+        let dense: String = (0..150)
+            .map(|i| format!("let needle_{} = {};\n", i, i))
+            .collect();
+        fs::write(src_dir.join("dense.rs"), dense).unwrap();
+        fs::write(
+            docs_dir.join("notes.md"),
+            "needle one\nno match\nNEEDLE two\n",
+        )
+        .unwrap();
+
+        let mut engine = SearchEngine::new();
+        engine.add_root_path(&project_dir);
+        engine.index_file(src_dir.join("dense.rs")).unwrap();
+        engine.index_file(docs_dir.join("notes.md")).unwrap();
+        engine.finalize();
+
+        // Ranked search caps matches per document
+        let results = engine.search("needle", 1000);
+        assert_eq!(results.len(), SearchEngine::MAX_MATCHES_PER_DOC + 2);
+
+        let counts = engine
+            .count_matches("needle", "", "", QueryKind::Text, &SearchScope::default())
+            .unwrap();
+        assert_eq!(counts.total_matches, 152);
+        assert_eq!(counts.matched_files, 2);
+        assert_eq!(counts.facets.extensions[0].value, "rs");
+        assert_eq!(counts.facets.extensions[0].matches, 150);
+        assert_eq!(counts.facets.roots[0].value, "project");
+        let dirs: Vec<&str> = counts
+            .facets
+            .directories
+            .iter()
+            .map(|f| f.value.as_str())
+            .collect();
+        assert_eq!(dirs, vec!["project/src", "project/docs"]);
+
+        let filtered = engine
+            .count_matches(
+                "needle",
+                "*.md",
+                "",
+                QueryKind::Text,
+                &SearchScope::default(),
+            )
+            .unwrap();
+        assert_eq!(filtered.total_matches, 2);
+
+        let regex = engine
+            .count_matches(
+                r"needle_1\d\b",
+                "",
+                "",
+                QueryKind::Regex,
+                &SearchScope::default(),
+            )
+            .unwrap();
+        assert_eq!(regex.total_matches, 10);
+    }
+
+    /// With a recency weight, recently modified files outrank stale ones.
+    #[test]
+    fn test_recency_boost_prefers_recent_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let old_path = temp_dir.path().join("old.txt");
+        let new_path = temp_dir.path().join("new.txt");
+        fs::write(&old_path, "shared_marker here\n").unwrap();
+        fs::write(&new_path, "shared_marker here\n").unwrap();
+
+        let year_ago = std::time::SystemTime::now() - std::time::Duration::from_secs(365 * 86_400);
+        fs::File::options()
+            .write(true)
+            .open(&old_path)
+            .unwrap()
+            .set_modified(year_ago)
+            .unwrap();
+
+        let mut engine = SearchEngine::new();
+        engine.ranking.recency_weight = Some(1.0);
+        engine.index_file(&old_path).unwrap();
+        engine.index_file(&new_path).unwrap();
+        engine.finalize();
+
+        let results = engine.search("shared_marker", 10);
+        assert_eq!(results.len(), 2);
+        assert!(results[0].file_path.ends_with("new.txt"));
+        assert!(results[0].score > results[1].score * 1.5);
+
+        // Without a weight the two identical files tie
+        let mut engine = SearchEngine::new();
+        engine.index_file(&old_path).unwrap();
+        engine.index_file(&new_path).unwrap();
+        engine.finalize();
+        let results = engine.search("shared_marker", 10);
+        assert_eq!(results[0].score, results[1].score);
+    }
+
+    /// Git commit times are found for files indexed through a symlinked root.
+    #[cfg(unix)]
+    #[test]
+    fn test_git_recency_through_symlinked_root() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = temp_dir.path().join("repo");
+        fs::create_dir_all(&repo).unwrap();
+        fs::write(repo.join("old.rs"), "fn committed_long_ago() {}\n").unwrap();
+        let git = |args: &[&str]| {
+            std::process::Command::new("git")
+                .arg("-C")
+                .arg(&repo)
+                .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+                .args(args)
+                .env("GIT_COMMITTER_DATE", "2000-01-01T00:00:00Z")
+                .output()
+                .is_ok_and(|o| o.status.success())
+        };
+        if !(git(&["init", "-q"]) && git(&["add", "."]) && git(&["commit", "-q", "-m", "old"])) {
+            return; // git unavailable
+        }
+        let link = temp_dir.path().join("link");
+        std::os::unix::fs::symlink(&repo, &link).unwrap();
+
+        let mut engine = SearchEngine::new();
+        engine.ranking.recency_weight = Some(1.0);
+        engine.ranking.recency_source = RecencySource::Git;
+        engine.add_root_path(&link);
+        engine.index_file(link.join("old.rs")).unwrap();
+        engine.finalize();
+        // Just written, so the mtime fallback makes the file fresh
+        assert!(engine.get_file_metadata(0).freshness > 0.9);
+
+        let request = engine.git_recency().unwrap();
+        engine.apply_commit_times(request.resolve());
+        assert!(engine.get_file_metadata(0).freshness < 0.01);
+    }
+
+    /// Click feedback boosts the opened file above an otherwise identical one.
+    #[test]
+    fn test_feedback_boosts_clicked_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let project_dir = temp_dir.path().join("project");
+        fs::create_dir_all(&project_dir).unwrap();
+        let a = project_dir.join("a.txt");
+        let b = project_dir.join("b.txt");
+        fs::write(&a, "feedback_marker\n").unwrap();
+        fs::write(&b, "feedback_marker\n").unwrap();
+
+        let mut engine = SearchEngine::new();
+        engine.add_root_path(&project_dir);
+        engine.index_file(&a).unwrap();
+        engine.index_file(&b).unwrap();
+        engine.finalize();
+
+        // Disabled by default
+        let event = FeedbackEvent {
+            query: "feedback_marker".to_string(),
+            file_path: "project/b.txt".to_string(),
+            rank: 1,
+            clicked: true,
+            timestamp: 0,
+        };
+        assert!(engine.record_feedback(event.clone()).is_err());
+
+        engine.feedback = Some(std::sync::Arc::new(FeedbackStore::in_memory(14.0)));
+        engine.refresh_feedback_boosts();
+        // Paths that are not indexed documents are ignored
+        let unknown = FeedbackEvent {
+            file_path: "project/missing.txt".to_string(),
+            ..event.clone()
+        };
+        assert!(!engine.record_feedback(unknown).unwrap());
+        assert_eq!(engine.feedback.as_ref().unwrap().event_count(), 0);
+        assert!(engine.record_feedback(event).unwrap());
+
+        let results = engine.search("feedback_marker", 10);
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].file_path, "project/b.txt");
+        assert!(results[0].score > results[1].score);
+    }
+
+    /// Above the fast-ranking threshold only the top files by file-level
+    /// score are read, so a recently changed file must be promoted before
+    /// that cut, not only afterwards.
+    #[test]
+    fn test_recency_promotes_file_into_fast_ranking() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = temp_dir.path().join("repo");
+        fs::create_dir_all(repo.join("docs")).unwrap();
+        fs::create_dir_all(repo.join("tests")).unwrap();
+        let year_ago = std::time::SystemTime::now() - std::time::Duration::from_secs(365 * 86_400);
+        let mut paths = Vec::new();
+        for i in 0..=SearchEngine::FAST_RANKING_THRESHOLD {
+            let path = repo.join(format!("docs/note_{i}.txt"));
+            fs::write(&path, "uses recency_marker\n").unwrap();
+            fs::File::options()
+                .write(true)
+                .open(&path)
+                .unwrap()
+                .set_modified(year_ago)
+                .unwrap();
+            paths.push(path);
+        }
+        // Changed just now, but the test-directory penalty ranks it below every note
+        let recent = repo.join("tests/recent.txt");
+        fs::write(&recent, "uses recency_marker\n").unwrap();
+        paths.push(recent);
+
+        let position = |recency_weight: Option<f32>| {
+            let mut engine = SearchEngine::new();
+            engine.ranking.recency_weight = recency_weight;
+            engine.add_root_path(&repo);
+            for path in &paths {
+                engine.index_file(path).unwrap();
+            }
+            engine.finalize();
+            let (matches, info) = engine.search_ranked("recency_marker", 10_000, RankMode::Auto);
+            assert_eq!(info.mode, RankMode::Fast);
+            matches
+                .iter()
+                .position(|m| m.file_path == "repo/tests/recent.txt")
+        };
+        assert_eq!(position(None), None);
+        assert_eq!(position(Some(1.0)), Some(0));
+    }
+
+    /// Fast ranking reads only the top files by file-level score, so a
+    /// clicked file must be promoted before that cut, not only afterwards.
+    #[test]
+    fn test_feedback_promotes_file_into_fast_ranking() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = temp_dir.path().join("repo");
+        fs::create_dir_all(repo.join("docs")).unwrap();
+        fs::create_dir_all(repo.join("tests")).unwrap();
+        let mut paths: Vec<PathBuf> = (0..2100)
+            .map(|i| repo.join(format!("docs/note_{i}.txt")))
+            .collect();
+        // The test-directory penalty ranks it below every note
+        paths.push(repo.join("tests/clicked.txt"));
+        for path in &paths {
+            fs::write(path, "uses needle_marker\n").unwrap();
+        }
+
+        let mut engine = SearchEngine::new();
+        engine.add_root_path(&repo);
+        for path in &paths {
+            engine.index_file(path).unwrap();
+        }
+        engine.finalize();
+        engine.feedback = Some(std::sync::Arc::new(FeedbackStore::in_memory(14.0)));
+        engine.refresh_feedback_boosts();
+
+        let clicked = |engine: &SearchEngine| {
+            let (matches, info) = engine.search_ranked("needle_marker", 5000, RankMode::Fast);
+            assert_eq!(info.candidates_searched, SearchEngine::FAST_RANKING_TOP_N);
+            matches
+                .iter()
+                .position(|m| m.file_path == "repo/tests/clicked.txt")
+        };
+        // Structural signals alone leave it outside the files read
+        assert_eq!(clicked(&engine), None);
+
+        for _ in 0..50 {
+            engine
+                .record_feedback(FeedbackEvent {
+                    query: "needle_marker".to_string(),
+                    file_path: "repo/tests/clicked.txt".to_string(),
+                    rank: 0,
+                    clicked: true,
+                    timestamp: 0,
+                })
+                .unwrap();
+        }
+        assert_eq!(clicked(&engine), Some(0));
+    }
+
+    /// The explanation's factors multiply back to the reported score.
+    #[test]
+    fn test_explain_match_reproduces_scores() {
+        let temp_dir = TempDir::new().unwrap();
+        let src_dir = temp_dir.path().join("src");
+        fs::create_dir_all(&src_dir).unwrap();
+        let parser = src_dir.join("parser.rs");
+        fs::write(
+            &parser,
+            "pub struct Parser {}\n\nimpl Parser {\n    pub fn parse(&self) {}\n}\n// parser notes\n",
+        )
+        .unwrap();
+
+        let mut engine = SearchEngine::new();
+        engine.index_file(&parser).unwrap();
+        engine.finalize();
+
+        let (matches, info) = engine.search_ranked("Parser", 10, RankMode::Auto);
+        assert!(!matches.is_empty());
+        for m in &matches {
+            let explanation = engine
+                .explain_match(
+                    m,
+                    "Parser",
+                    QueryKind::Text,
+                    &SearchScope::default(),
+                    Some(&info),
+                )
+                .unwrap();
+            assert!((explanation.score - m.score).abs() < 1e-9);
+            assert_eq!(explanation.ranking.mode, "full");
+            assert_eq!(explanation.ranking.filename_stem_boost, 5.0);
+            assert_eq!(explanation.base_score.src_lib, 2.0);
+        }
+
+        let regex_matches = engine.search_regex(r"fn\s+parse", "", "", 10).unwrap();
+        let explanation = engine
+            .explain_match(
+                &regex_matches[0],
+                r"fn\s+parse",
+                QueryKind::Regex,
+                &SearchScope::default(),
+                None,
+            )
+            .unwrap();
+        assert!((explanation.score - regex_matches[0].score).abs() < 1e-9);
+
+        let symbol_matches = engine.search_symbols("parse", "", "", 10).unwrap();
+        let exact = symbol_matches
+            .iter()
+            .find(|m| m.content.contains("fn parse"))
+            .unwrap();
+        let explanation = engine
+            .explain_match(
+                exact,
+                "parse",
+                QueryKind::Symbols,
+                &SearchScope::default(),
+                None,
+            )
+            .unwrap();
+        assert_eq!(explanation.exact_symbol_name, Some(2.0));
+        assert!((explanation.score - exact.score).abs() < 1e-9);
+    }
+
+    /// Fuzzy symbol hits are explained with their fuzzy score.
+    #[test]
+    fn test_explain_fuzzy_symbol_match() {
+        let temp_dir = TempDir::new().unwrap();
+        let service_path = temp_dir.path().join("service.rs");
+        fs::write(
+            &service_path,
+            "pub struct UserService;\n\npub fn parse_config(text: &str) {}\n",
+        )
+        .unwrap();
+
+        let mut engine = SearchEngine::new();
+        engine.index_file(&service_path).unwrap();
+        engine.finalize();
+
+        let fuzzy = SearchScope {
+            fuzzy: true,
+            ..SearchScope::default()
+        };
+        let (results, info) = engine
+            .search_symbols_ranked("usrsvc", "", "", 10, &fuzzy)
+            .unwrap();
+        assert_eq!(results.len(), 1);
+        let explanation = engine
+            .explain_match(
+                &results[0],
+                "usrsvc",
+                QueryKind::Symbols,
+                &fuzzy,
+                Some(&info),
+            )
+            .unwrap();
+        let factor = explanation.fuzzy_match.unwrap();
+        assert!(factor > 0.0 && factor < 1.0);
+        assert_eq!(explanation.exact_symbol_name, None);
+        assert!((explanation.score - results[0].score).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_duplicate_files_collapse_into_one_result() {
+        let temp_dir = TempDir::new().unwrap();
+        let project_dir = temp_dir.path().join("project");
+        let src_dir = project_dir.join("src");
+        let vendor_dir = project_dir.join("vendor").join("dep");
+        fs::create_dir_all(&src_dir).unwrap();
+        fs::create_dir_all(&vendor_dir).unwrap();
+
+        let shared = "pub fn shared_helper() -> u32 { 42 }\n";
+        fs::write(src_dir.join("helper.rs"), shared).unwrap();
+        fs::write(vendor_dir.join("helper.rs"), shared).unwrap();
+        fs::write(project_dir.join("helper_copy.rs"), shared).unwrap();
+        fs::write(src_dir.join("other.rs"), "fn shared_helper_user() {}\n").unwrap();
+
+        let mut engine = SearchEngine::new();
+        engine.add_root_path(&project_dir);
+        engine.index_file(src_dir.join("helper.rs")).unwrap();
+        engine.index_file(vendor_dir.join("helper.rs")).unwrap();
+        engine
+            .index_file(project_dir.join("helper_copy.rs"))
+            .unwrap();
+        engine.index_file(src_dir.join("other.rs")).unwrap();
+        engine.finalize();
+
+        let stats = engine.duplicate_stats();
+        assert_eq!(stats.clusters, 1);
+        assert_eq!(stats.duplicate_files, 2);
+        assert_eq!(stats.wasted_bytes, 2 * shared.len() as u64);
+
+        // Without dedup every copy is returned
+        let all = engine.search_regex("shared_helper", "", "", 10).unwrap();
+        assert_eq!(all.len(), 4);
+
+        let scope = SearchScope {
+            collapse_duplicates: true,
+            ..SearchScope::default()
+        };
+        let (collapsed, _) = engine
+            .search_regex_ranked("shared_helper", "", "", 10, &scope)
+            .unwrap();
+        assert_eq!(collapsed.len(), 2);
+        // The src/ copy outranks the vendored and top-level copies
+        let kept = collapsed
+            .iter()
+            .find(|m| m.file_path.ends_with("helper.rs"))
+            .unwrap();
+        assert_eq!(kept.file_path, "project/src/helper.rs");
+        let mut others = engine.duplicate_paths(kept.file_id);
+        others.sort();
+        assert_eq!(
+            others,
+            vec!["project/helper_copy.rs", "project/vendor/dep/helper.rs"]
+        );
+
+        // A path filter that excludes the preferred copy keeps another one
+        let (filtered, _) = engine
+            .search_with_filter_ranked("shared_helper", "", "**/src/**", 10, RankMode::Full, &scope)
+            .unwrap();
+        assert_eq!(filtered.len(), 1);
+
+        let counts = engine
+            .count_matches("shared_helper", "", "", QueryKind::Text, &scope)
+            .unwrap();
+        assert_eq!(counts.matched_files, 2);
+    }
+
+    #[test]
+    fn test_find_references_ranked_by_import_proximity() {
+        use crate::config::IndexerConfig;
+
+        let temp_dir = TempDir::new().unwrap();
+        let project_dir = temp_dir.path().join("project");
+        let other_dir = project_dir.join("other");
+        fs::create_dir_all(&other_dir).unwrap();
+
+        fs::write(
+            project_dir.join("billing.py"),
+            "def compute_total(items):\n    return sum(items)\n\nGRAND = compute_total([1])\n",
+        )
+        .unwrap();
+        fs::write(
+            project_dir.join("app.py"),
+            "import billing\n\nprint(billing.compute_total([2, 3]))\n",
+        )
+        .unwrap();
+        fs::write(
+            other_dir.join("unrelated.py"),
+            "# compute_total in a comment is not a reference\nx = compute_total\n",
+        )
+        .unwrap();
+
+        let mut engine = SearchEngine::new();
+        engine.add_root_path(&project_dir);
+        engine.index_file(project_dir.join("billing.py")).unwrap();
+        engine.index_file(project_dir.join("app.py")).unwrap();
+        engine.index_file(other_dir.join("unrelated.py")).unwrap();
+        engine.resolve_imports();
+        engine.finalize();
+
+        let refs = engine.find_references("compute_total", None, 10).unwrap();
+        let locations: Vec<(&str, usize)> = refs
+            .iter()
+            .map(|m| (m.file_path.as_str(), m.line_number))
+            .collect();
+        // The definition line is not a reference; comments are not identifiers
+        assert_eq!(
+            locations,
+            vec![
+                ("project/billing.py", 4),
+                ("project/app.py", 3),
+                ("project/other/unrelated.py", 2),
+            ]
+        );
+        assert!(refs.iter().all(|m| m.is_reference && !m.is_symbol));
+        let m = &refs[1];
+        assert_eq!(&m.content[m.match_start..m.match_end], "compute_total");
+
+        assert!(engine
+            .find_references("compute_total", Some("missing.py"), 10)
+            .is_err());
+
+        // References survive a save/load round trip
+        let index_path = temp_dir.path().join("index.bin");
+        let config = IndexerConfig {
+            paths: vec![project_dir.to_string_lossy().to_string()],
+            ..Default::default()
+        };
+        engine.save_index(&index_path, &config).unwrap();
+        let mut loaded = SearchEngine::new();
+        loaded.load_index(&index_path).unwrap();
+        let reloaded = loaded
+            .find_references("compute_total", Some("billing.py"), 10)
+            .unwrap();
+        assert_eq!(reloaded.len(), 3);
+    }
+
+    #[test]
+    fn test_identifier_at() {
+        let line = "let total = compute_total(items) + 42;";
+        assert_eq!(identifier_at(line, 12), Some((12, 25)));
+        assert_eq!(identifier_at(line, 20), Some((12, 25)));
+        // Caret just after the identifier
+        assert_eq!(identifier_at(line, 25), Some((12, 25)));
+        assert_eq!(identifier_at(line, 35), None); // numeric literal
+        assert_eq!(identifier_at(line, 33), None); // between operators
+        assert_eq!(identifier_at("", 0), None);
+    }
+
+    #[test]
+    fn test_find_definition_prefers_imported_then_same_directory() {
+        let temp_dir = TempDir::new().unwrap();
+        let project_dir = temp_dir.path().join("project");
+        fs::create_dir_all(project_dir.join("lib")).unwrap();
+        fs::create_dir_all(project_dir.join("other")).unwrap();
+
+        fs::write(
+            project_dir.join("app.py"),
+            "import helpers\n\nhelpers.render()\n",
+        )
+        .unwrap();
+        fs::write(
+            project_dir.join("lib").join("helpers.py"),
+            "def render():\n    return 1\n",
+        )
+        .unwrap();
+        fs::write(project_dir.join("local.py"), "def render():\n    pass\n").unwrap();
+        fs::write(
+            project_dir.join("other").join("views.py"),
+            "def render():\n    pass\n",
+        )
+        .unwrap();
+
+        let mut engine = SearchEngine::new();
+        engine.add_root_path(&project_dir);
+        for file in ["app.py", "lib/helpers.py", "local.py", "other/views.py"] {
+            engine.index_file(project_dir.join(file)).unwrap();
+        }
+        engine.resolve_imports();
+        engine.finalize();
+
+        // Cursor on `render` in `helpers.render()`
+        let lookup = engine.find_definition("app.py", 3, 10, 10).unwrap();
+        assert_eq!(lookup.identifier, "render");
+        let found: Vec<(&str, DefinitionScope)> = lookup
+            .candidates
+            .iter()
+            .map(|c| (c.result.file_path.as_str(), c.scope))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("project/lib/helpers.py", DefinitionScope::Imported),
+                ("project/local.py", DefinitionScope::SameDirectory),
+                ("project/other/views.py", DefinitionScope::Global),
+            ]
+        );
+        let m = &lookup.candidates[0].result;
+        assert_eq!(m.line_number, 1);
+        assert_eq!(&m.content[m.match_start..m.match_end], "render");
+        assert_eq!(lookup.candidates[0].column, 5);
+
+        // A definition in the file itself wins
+        let lookup = engine.find_definition("local.py", 1, 6, 10).unwrap();
+        assert_eq!(lookup.candidates[0].scope, DefinitionScope::SameFile);
+
+        assert!(engine.find_definition("app.py", 2, 1, 10).is_err());
+        assert!(engine.find_definition("missing.py", 1, 1, 10).is_err());
+    }
+
+    /// Columns count UTF-16 code units, so non-ASCII text before the cursor
+    /// or the definition does not shift them.
+    #[test]
+    fn test_find_definition_utf16_columns() {
+        let temp_dir = TempDir::new().unwrap();
+        let file = temp_dir.path().join("app.js");
+        fs::write(
+            &file,
+            "/* é😀 */ function render() {}\nconst label = \"é😀\"; render();\n",
+        )
+        .unwrap();
+
+        let mut engine = SearchEngine::new();
+        engine.index_file(&file).unwrap();
+        engine.finalize();
+
+        // `render` on line 2 starts at UTF-16 column 22 (byte 24)
+        let lookup = engine.find_definition("app.js", 2, 22, 10).unwrap();
+        assert_eq!(lookup.identifier, "render");
+        assert_eq!(lookup.candidates.len(), 1);
+        assert_eq!(lookup.candidates[0].result.line_number, 1);
+        assert_eq!(lookup.candidates[0].column, 20);
+    }
+
+    #[test]
+    fn test_utf16_columns() {
+        let line = "a é😀 b";
+        assert_eq!(utf16_column_to_byte(line, 0), 0);
+        assert_eq!(utf16_column_to_byte(line, 2), 2);
+        assert_eq!(utf16_column_to_byte(line, 3), 4);
+        assert_eq!(utf16_column_to_byte(line, 6), 9);
+        assert_eq!(utf16_column_to_byte(line, 99), line.len());
+        assert_eq!(byte_to_utf16_column(line, 9), 6);
+        assert_eq!(byte_to_utf16_column(line, 4), 3);
+    }
+
+    #[test]
+    fn test_qualified_symbol_search() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("parsers.rs");
+        fs::write(
+            &file_path,
+            "struct Parser;\nstruct Lexer;\n\nimpl Parser {\n    fn parse(&self) {}\n}\n\nimpl Lexer {\n    fn parse(&self) {}\n}\n",
+        )
+        .unwrap();
+
+        let mut engine = SearchEngine::new();
+        engine.index_file(&file_path).unwrap();
+        engine.finalize();
+
+        let results = engine.search_symbols("Parser::parse", "", "", 10).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].line_number, 5);
+        assert_eq!(results[0].container.as_deref(), Some("impl Parser"));
+        let m = &results[0];
+        assert_eq!(&m.content[m.match_start..m.match_end], "parse");
+
+        // Dotted qualifiers work too, and plain names still match every definition
+        let results = engine.search_symbols("lexer.parse", "", "", 10).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].line_number, 9);
+        let containers: Vec<Option<String>> = engine
+            .search_symbols("parse", "", "", 10)
+            .unwrap()
+            .into_iter()
+            .filter(|m| m.content.contains("fn parse"))
+            .map(|m| m.container)
+            .collect();
+        assert_eq!(containers.len(), 2);
+        assert!(containers.contains(&Some("impl Lexer".to_string())));
+    }
+
+    /// TypeScript class methods and arrow-function exports are definitions:
+    /// qualified search, find-definition and the outline all see them.
+    #[test]
+    fn test_typescript_method_navigation() {
+        let temp_dir = TempDir::new().unwrap();
+        let project_dir = temp_dir.path().join("project");
+        fs::create_dir_all(&project_dir).unwrap();
+        fs::write(
+            project_dir.join("service.ts"),
+            "export class UserService {\n    save(user: User): void {}\n    load = (id: string) => fetchUser(id);\n}\n\nexport const helper = () => 1;\n",
+        )
+        .unwrap();
+        fs::write(
+            project_dir.join("app.ts"),
+            "import { UserService, helper } from './service';\n\nnew UserService().save(user);\nhelper();\n",
+        )
+        .unwrap();
+
+        let mut engine = SearchEngine::new();
+        engine.add_root_path(&project_dir);
+        for file in ["service.ts", "app.ts"] {
+            engine.index_file(project_dir.join(file)).unwrap();
+        }
+        engine.resolve_imports();
+        engine.finalize();
+
+        let results = engine
+            .search_symbols("UserService.save", "", "", 10)
+            .unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].line_number, 2);
+        assert_eq!(results[0].container.as_deref(), Some("class UserService"));
+        let results = engine
+            .search_symbols("UserService.load", "", "", 10)
+            .unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].line_number, 3);
+
+        // Cursor on `save` in `new UserService().save(user)`
+        let lookup = engine.find_definition("app.ts", 3, 19, 10).unwrap();
+        assert_eq!(lookup.identifier, "save");
+        assert_eq!(lookup.candidates.len(), 1);
+        assert_eq!(lookup.candidates[0].result.file_path, "project/service.ts");
+        assert_eq!(lookup.candidates[0].result.line_number, 2);
+        let lookup = engine.find_definition("app.ts", 4, 1, 10).unwrap();
+        assert_eq!(lookup.identifier, "helper");
+        assert_eq!(lookup.candidates[0].result.line_number, 6);
+        assert_eq!(lookup.candidates[0].scope, DefinitionScope::Imported);
+
+        let service = engine.find_file_id("service.ts").unwrap();
+        let outline = engine.file_outline(service);
+        let children: Vec<&str> = outline[0]
+            .children
+            .iter()
+            .map(|c| c.name.as_str())
+            .collect();
+        assert_eq!(children, vec!["save", "load"]);
+        assert_eq!(outline[1].name, "helper");
+    }
+
+    #[test]
+    fn test_signature_and_doc_search() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("config.rs");
+        fs::write(
+            &file_path,
+            "/// Read settings from disk.\npub fn load(path: &str) -> Result<Config, Error> {\n    todo!()\n}\n\npub fn name() -> String {\n    String::new()\n}\n",
+        )
+        .unwrap();
+
+        let mut engine = SearchEngine::new();
+        engine.index_file(&file_path).unwrap();
+        engine.finalize();
+
+        let results = engine
+            .search_symbols("sig:\"-> Result<Config\"", "", "", 10)
+            .unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].line_number, 2);
+        assert_eq!(
+            results[0].signature.as_deref(),
+            Some("pub fn load(path: &str) -> Result<Config, Error>")
+        );
+        assert_eq!(results[0].doc.as_deref(), Some("Read settings from disk."));
+
+        let results = engine.search_symbols("doc:settings", "", "", 10).unwrap();
+        assert_eq!(results.len(), 1);
+        assert!(engine
+            .search_symbols("sig:-> Option", "", "", 10)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_symbol_kind_and_definition_filters() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("config.rs");
+        fs::write(
+            &file_path,
+            "pub struct Config {\n    path: String,\n}\n\npub fn load_config() -> Config {\n    todo!()\n}\n",
+        )
+        .unwrap();
+
+        let mut engine = SearchEngine::new();
+        engine.index_file(&file_path).unwrap();
+        engine.finalize();
+
+        let search = |scope: &SearchScope| {
+            engine
+                .search_symbols_ranked("config", "", "", 10, scope)
+                .unwrap()
+                .0
+        };
+
+        // Unfiltered: the struct, the function and the file name
+        assert_eq!(search(&SearchScope::default()).len(), 3);
+
+        let structs = search(&SearchScope {
+            symbol_kinds: vec![SymbolType::Struct],
+            ..SearchScope::default()
+        });
+        assert_eq!(structs.len(), 1);
+        assert_eq!(structs[0].line_number, 1);
+        assert_eq!(structs[0].symbol_type, Some(SymbolType::Struct));
+
+        let definitions = search(&SearchScope {
+            definition_only: true,
+            ..SearchScope::default()
+        });
+        assert_eq!(definitions.len(), 2);
+        assert!(definitions
+            .iter()
+            .all(|m| m.symbol_type != Some(SymbolType::FileName)));
+    }
+
+    /// Functions declared in an impl or class body are methods, so a
+    /// `method` kind filter finds them and a `function` filter does not.
+    #[test]
+    fn test_method_kind_filter() {
+        let temp_dir = TempDir::new().unwrap();
+        let rust_path = temp_dir.path().join("parser.rs");
+        fs::write(
+            &rust_path,
+            "pub struct Parser;\n\nimpl Parser {\n    pub fn parse(&self) {\n        fn parse_inner() {}\n    }\n}\n\npub fn parse_all() {}\n",
+        )
+        .unwrap();
+        let python_path = temp_dir.path().join("service.py");
+        fs::write(
+            &python_path,
+            "class UserService:\n    def save(self):\n        pass\n\ndef save_all():\n    pass\n",
+        )
+        .unwrap();
+
+        let mut engine = SearchEngine::new();
+        engine.index_file(&rust_path).unwrap();
+        engine.index_file(&python_path).unwrap();
+        engine.finalize();
+
+        let search = |query: &str, kind: SymbolType| -> Vec<(usize, Option<SymbolType>)> {
+            let scope = SearchScope {
+                symbol_kinds: vec![kind],
+                ..SearchScope::default()
+            };
+            let mut found: Vec<_> = engine
+                .search_symbols_ranked(query, "", "", 10, &scope)
+                .unwrap()
+                .0
+                .into_iter()
+                .map(|m| (m.line_number, m.symbol_type))
+                .collect();
+            found.sort_by_key(|(line, _)| *line);
+            found
+        };
+
+        let method = Some(SymbolType::Method);
+        assert_eq!(
+            search("Parser::parse", SymbolType::Method),
+            vec![(4, method.clone())]
+        );
+        assert_eq!(
+            search("UserService.save", SymbolType::Method),
+            vec![(2, method)]
+        );
+        // Nested functions and free functions stay functions
+        let function = Some(SymbolType::Function);
+        assert_eq!(
+            search("parse", SymbolType::Function),
+            vec![(5, function.clone()), (9, function.clone())]
+        );
+        assert_eq!(search("save", SymbolType::Function), vec![(5, function)]);
+    }
+
+    #[test]
+    fn test_fuzzy_symbol_search() {
+        let temp_dir = TempDir::new().unwrap();
+        let service_path = temp_dir.path().join("service.rs");
+        fs::write(
+            &service_path,
+            "pub struct UserService;\n\nimpl UserService {\n    pub fn save(&self) {}\n}\n\npub fn parse_config(text: &str) {}\n",
+        )
+        .unwrap();
+        let other_path = temp_dir.path().join("other.rs");
+        fs::write(&other_path, "pub fn render() {}\n").unwrap();
+
+        let mut engine = SearchEngine::new();
+        engine.index_file(&service_path).unwrap();
+        engine.index_file(&other_path).unwrap();
+        engine.finalize();
+
+        let fuzzy = SearchScope {
+            fuzzy: true,
+            ..SearchScope::default()
+        };
+        let search = |query: &str, scope: &SearchScope| {
+            engine
+                .search_symbols_ranked(query, "", "", 10, scope)
+                .unwrap()
+                .0
+        };
+
+        // Plain symbol search needs the exact substring
+        assert!(search("usrsvc", &SearchScope::default()).is_empty());
+
+        for query in ["usrsvc", "UserSvc"] {
+            let results = search(query, &fuzzy);
+            // The struct and its impl block
+            let mut lines: Vec<usize> = results.iter().map(|m| m.line_number).collect();
+            lines.sort_unstable();
+            assert_eq!(lines, vec![1, 3], "query {query}");
+        }
+
+        let results = search("parseCfg", &fuzzy);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].line_number, 7);
+
+        // Qualifiers still have to match the containers exactly
+        assert_eq!(search("UserService::sv", &fuzzy).len(), 1);
+        assert!(search("Other::sv", &fuzzy).is_empty());
+
+        // Exact names outrank fuzzy matches
+        let results = search("save", &fuzzy);
+        assert_eq!(results[0].line_number, 4);
+
+        let counts = engine
+            .count_matches("usrsvc", "", "", QueryKind::Symbols, &fuzzy)
+            .unwrap();
+        assert_eq!(counts.total_matches, 2);
+    }
+
+    #[test]
+    fn test_detected_languages_drive_symbols_and_lang_filter() {
+        let temp_dir = TempDir::new().unwrap();
+        let bin_dir = temp_dir.path().join("bin");
+        fs::create_dir(&bin_dir).unwrap();
+        let script_path = bin_dir.join("deploy");
+        fs::write(
+            &script_path,
+            "#!/usr/bin/env python3\n\ndef deploy_release():\n    pass\n",
+        )
+        .unwrap();
+        let header_path = temp_dir.path().join("release.h");
+        fs::write(
+            &header_path,
+            "namespace ops {\nclass ReleaseTrain {\npublic:\n  void deploy_release();\n};\n}\n",
+        )
+        .unwrap();
+        let makefile_path = temp_dir.path().join("Makefile");
+        fs::write(&makefile_path, "deploy_release:\n\t./bin/deploy\n").unwrap();
+
+        let mut engine = SearchEngine::new();
+        engine.index_file(&script_path).unwrap();
+        engine.index_file(&header_path).unwrap();
+        engine.index_file(&makefile_path).unwrap();
+        engine.finalize();
+
+        let language_of = |path: &Path| {
+            let file_id = engine.find_file_id(&path.to_string_lossy()).unwrap();
+            engine.file_store.get_language(file_id)
+        };
+        assert_eq!(language_of(&script_path), Some(Language::Python));
+        assert_eq!(language_of(&header_path), Some(Language::Cpp));
+        assert_eq!(language_of(&makefile_path), Some(Language::Makefile));
+
+        // The extensionless script is parsed with the Python grammar
+        let definitions = engine
+            .search_symbols_ranked(
+                "deploy_release",
+                "",
+                "",
+                10,
+                &SearchScope {
+                    definition_only: true,
+                    ..SearchScope::default()
+                },
+            )
+            .unwrap()
+            .0;
+        assert!(definitions
+            .iter()
+            .any(|m| m.file_path.ends_with("deploy") && m.line_number == 3));
+
+        let search = |languages: Vec<Language>| {
+            let scope = SearchScope {
+                languages,
+                ..SearchScope::default()
+            };
+            let mut paths: Vec<String> = engine
+                .search_with_filter_ranked("deploy_release", "", "", 10, RankMode::Auto, &scope)
+                .unwrap()
+                .0
+                .into_iter()
+                .map(|m| m.file_path)
+                .collect();
+            paths.dedup();
+            paths
+        };
+        assert_eq!(search(Vec::new()).len(), 3);
+        let python = search(vec![Language::Python]);
+        assert_eq!(python.len(), 1);
+        assert!(python[0].ends_with("deploy"));
+        assert_eq!(search(vec![Language::Cpp, Language::Makefile]).len(), 2);
+
+        let counts = engine
+            .count_matches(
+                "deploy_release",
+                "",
+                "",
+                QueryKind::Text,
+                &SearchScope::default(),
+            )
+            .unwrap();
+        let languages: Vec<&str> = counts
+            .facets
+            .languages
+            .iter()
+            .map(|f| f.value.as_str())
+            .collect();
+        assert!(languages.contains(&"C++") && languages.contains(&"Makefile"));
+    }
+
+    #[test]
+    fn test_resolve_imports_for_go_java_c_csharp_ruby_php() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().join("project");
+        let files = [
+            ("go.mod", "module example.com/app\n\ngo 1.21\n"),
+            (
+                "cmd/main.go",
+                "package main\n\nimport (\n\t\"fmt\"\n\t\"example.com/app/store\"\n)\n",
+            ),
+            ("store/store.go", "package store\n"),
+            ("store/store_test.go", "package store\n"),
+            (
+                "src/com/acme/App.java",
+                "package com.acme;\n\nimport com.acme.model.User;\n",
+            ),
+            ("src/com/acme/model/User.java", "package com.acme.model;\n"),
+            ("src/main.c", "#include \"util.h\"\n#include <geo.h>\n"),
+            ("src/util.h", "int util(void);\n"),
+            ("include/geo.h", "int geo(void);\n"),
+            ("Program.cs", "using Acme.Models;\n"),
+            (
+                "Acme/Models/User.cs",
+                "namespace Acme.Models { class User {} }\n",
+            ),
+            ("app.rb", "require 'json'\nrequire_relative 'lib/helper'\n"),
+            ("lib/helper.rb", "module Helper; end\n"),
+            ("index.php", "<?php\nuse App\\Models\\Post;\n"),
+            ("app/Models/Post.php", "<?php\nclass Post {}\n"),
+        ];
+        for (path, content) in files {
+            let path = root.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+
+        let mut engine = SearchEngine::new();
+        engine.add_root_path(&root);
+        engine.set_include_dirs(&["include".to_string()]);
+        for (path, _) in files {
+            engine.index_file(root.join(path)).unwrap();
+        }
+        engine.resolve_imports();
+
+        let dependencies = |path: &str| -> Vec<String> {
+            let id = engine.find_file_id(path).unwrap();
+            let mut deps: Vec<String> = engine
+                .get_dependencies(id)
+                .into_iter()
+                .map(|dep| engine.make_display_path(engine.file_store.get_path(dep).unwrap()))
+                .collect();
+            deps.sort();
+            deps
+        };
+        assert_eq!(dependencies("cmd/main.go"), vec!["project/store/store.go"]);
+        assert_eq!(
+            dependencies("src/com/acme/App.java"),
+            vec!["project/src/com/acme/model/User.java"]
+        );
+        assert_eq!(
+            dependencies("src/main.c"),
+            vec!["project/include/geo.h", "project/src/util.h"]
+        );
+        assert_eq!(
+            dependencies("Program.cs"),
+            vec!["project/Acme/Models/User.cs"]
+        );
+        assert_eq!(dependencies("app.rb"), vec!["project/lib/helper.rb"]);
+        assert_eq!(
+            dependencies("index.php"),
+            vec!["project/app/Models/Post.php"]
+        );
+    }
+
+    #[test]
+    fn test_resolve_imports_with_project_manifests() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().join("ws");
+        let files = [
+            // JS/TS: tsconfig paths (with comments) and an npm workspace package
+            (
+                "web/tsconfig.json",
+                "{\n  // aliases\n  \"compilerOptions\": {\n    \"baseUrl\": \".\",\n    \"paths\": { \"@app/*\": [\"src/*\"] },\n  },\n}\n",
+            ),
+            (
+                "web/src/main.ts",
+                "import { x } from \"@app/utils\";\nimport { b } from \"@acme/ui/button\";\nimport { c } from \"@acme/ui\";\n",
+            ),
+            ("web/src/utils.ts", "export const x = 1;\n"),
+            ("lib/utils.ts", "export const y = 2;\n"),
+            ("package.json", "{ \"workspaces\": [\"packages/*\"] }\n"),
+            (
+                "packages/ui/package.json",
+                "{ \"name\": \"@acme/ui\", \"main\": \"dist/index.js\" }\n",
+            ),
+            ("packages/ui/src/index.ts", "export * from './button';\n"),
+            ("packages/ui/src/button.ts", "export const b = 1;\n"),
+            // Rust: Cargo workspace with two crates
+            ("Cargo.toml", "[workspace]\nmembers = [\"crates/*\"]\n"),
+            (
+                "crates/core/Cargo.toml",
+                "[package]\nname = \"acme-core\"\nversion = \"0.1.0\"\n",
+            ),
+            ("crates/core/src/lib.rs", "pub mod config;\nmod inline { }\n"),
+            ("crates/core/src/config.rs", "pub struct Config;\n"),
+            (
+                "crates/cli/Cargo.toml",
+                "[package]\nname = \"cli\"\nversion = \"0.1.0\"\n",
+            ),
+            (
+                "crates/cli/src/main.rs",
+                "mod args;\nuse acme_core::config::Config;\nuse crate::args::{parse, Args};\nuse serde::Deserialize;\n",
+            ),
+            ("crates/cli/src/args.rs", "use super::run;\npub struct Args;\n"),
+            ("crates/cli/src/config.rs", "pub struct Local;\n"),
+            // Python: src layout package and an ambiguous bare module name
+            ("py/pyproject.toml", "[project]\nname = \"acme\"\n"),
+            ("py/src/acme/__init__.py", ""),
+            ("py/src/acme/models.py", "from .db import connect\n"),
+            ("py/src/acme/db.py", "def connect(): pass\n"),
+            ("py/tests/test_models.py", "import acme.models\nimport helpers\n"),
+            ("py/a/helpers.py", ""),
+            ("py/b/helpers.py", ""),
+        ];
+        for (path, content) in files {
+            let path = root.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+
+        let mut engine = SearchEngine::new();
+        engine.add_root_path(&root);
+        for (path, _) in files {
+            engine.index_file(root.join(path)).unwrap();
+        }
+        engine.resolve_imports();
+
+        let paths = |ids: Vec<u32>| -> Vec<String> {
+            let mut paths: Vec<String> = ids
+                .into_iter()
+                .map(|id| engine.make_display_path(engine.file_store.get_path(id).unwrap()))
+                .collect();
+            paths.sort();
+            paths
+        };
+        let dependencies =
+            |path: &str| paths(engine.get_dependencies(engine.find_file_id(path).unwrap()));
+
+        assert_eq!(
+            dependencies("web/src/main.ts"),
+            vec![
+                "ws/packages/ui/src/button.ts",
+                "ws/packages/ui/src/index.ts",
+                "ws/web/src/utils.ts"
+            ]
+        );
+        assert_eq!(
+            dependencies("crates/cli/src/main.rs"),
+            vec!["ws/crates/cli/src/args.rs", "ws/crates/core/src/config.rs"]
+        );
+        assert_eq!(
+            dependencies("crates/cli/src/args.rs"),
+            vec!["ws/crates/cli/src/main.rs"]
+        );
+        assert_eq!(
+            dependencies("crates/core/src/lib.rs"),
+            vec!["ws/crates/core/src/config.rs"]
+        );
+        assert_eq!(
+            dependencies("py/src/acme/models.py"),
+            vec!["ws/py/src/acme/db.py"]
+        );
+        assert_eq!(
+            dependencies("py/tests/test_models.py"),
+            vec!["ws/py/src/acme/models.py"]
+        );
+
+        // `import helpers` matches two modules: recorded, not linked
+        let test_id = engine.find_file_id("py/tests/test_models.py").unwrap();
+        let ambiguous = engine.get_ambiguous_imports(test_id);
+        assert_eq!(ambiguous.len(), 1);
+        assert_eq!(ambiguous[0].path, "helpers");
+        assert_eq!(ambiguous[0].line, 1);
+        assert_eq!(
+            paths(ambiguous[0].candidates.clone()),
+            vec!["ws/py/a/helpers.py", "ws/py/b/helpers.py"]
+        );
+    }
+
+    #[test]
+    fn test_dependency_graph_prefix_and_levels() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().join("repo");
+        let files = [
+            ("app/package.json", "{ \"name\": \"app\" }\n"),
+            (
+                "app/src/main.js",
+                "import './views/list';\nimport '../../lib/src/util';\n",
+            ),
+            ("app/src/views/list.js", "import '../../../lib/src/util';\n"),
+            ("lib/package.json", "{ \"name\": \"lib\" }\n"),
+            ("lib/src/util.js", "export const util = 1;\n"),
+        ];
+        for (path, content) in files {
+            let path = root.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+
+        let mut engine = SearchEngine::new();
+        engine.add_root_path(&root);
+        for (path, _) in files.iter().filter(|(p, _)| p.ends_with(".js")) {
+            engine.index_file(root.join(path)).unwrap();
+        }
+        engine.resolve_imports();
+
+        let edges = |graph: DependencyGraph| -> Vec<(String, String, usize)> {
+            graph
+                .edges
+                .into_iter()
+                .map(|e| (e.source, e.target, e.weight))
+                .collect()
+        };
+
+        let files = engine.dependency_graph(None, GraphLevel::File);
+        assert_eq!(files.nodes.len(), 3);
+        assert_eq!(files.edges.len(), 3);
+
+        // Only edges with both ends under the prefix
+        let app = engine.dependency_graph(Some("repo/app/"), GraphLevel::File);
+        assert_eq!(
+            edges(app),
+            vec![(
+                "repo/app/src/main.js".to_string(),
+                "repo/app/src/views/list.js".to_string(),
+                1
+            )]
+        );
+
+        let packages = engine.dependency_graph(None, GraphLevel::Package);
+        assert_eq!(
+            edges(packages),
+            vec![("app".to_string(), "lib".to_string(), 2)]
+        );
+
+        let dirs = engine.dependency_graph(None, GraphLevel::Directory);
+        assert_eq!(
+            edges(dirs),
+            vec![
+                (
+                    "repo/app/src".to_string(),
+                    "repo/app/src/views".to_string(),
+                    1
+                ),
+                ("repo/app/src".to_string(), "repo/lib/src".to_string(), 1),
+                (
+                    "repo/app/src/views".to_string(),
+                    "repo/lib/src".to_string(),
+                    1
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_cycle_stats_report_import_lines() {
+        use crate::config::IndexerConfig;
+
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().join("repo");
+        let files = [
+            ("web/a.js", "import './b';\n"),
+            (
+                "web/b.js",
+                "// b\nimport './c';\nimport { a } from './a';\n",
+            ),
+            ("web/c.js", "import './a';\n"),
+            ("web/main.js", "import './a';\n"),
+            ("lib/x.js", "import './y';\n"),
+            ("lib/y.js", "\nimport './x';\n"),
+        ];
+        for (path, content) in files {
+            let path = root.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+
+        let mut engine = SearchEngine::new();
+        engine.add_root_path(&root);
+        for (path, _) in files {
+            engine.index_file(root.join(path)).unwrap();
+        }
+        engine.resolve_imports();
+
+        let stats = engine.cycle_stats(None, 10);
+        assert_eq!(stats.cycles, 2);
+        assert_eq!(stats.files_in_cycles, 5);
+        assert_eq!(stats.largest_cycle, 3);
+        let web = &stats.largest_cycles[0];
+        assert_eq!(
+            web.files,
+            vec!["repo/web/a.js", "repo/web/b.js", "repo/web/c.js"]
+        );
+        let edges: Vec<(&str, &str, &[usize])> = web
+            .edges
+            .iter()
+            .map(|e| (e.from.as_str(), e.to.as_str(), e.lines.as_slice()))
+            .collect();
+        assert_eq!(
+            edges,
+            vec![
+                ("repo/web/a.js", "repo/web/b.js", &[1][..]),
+                ("repo/web/b.js", "repo/web/a.js", &[3][..]),
+                ("repo/web/b.js", "repo/web/c.js", &[2][..]),
+                ("repo/web/c.js", "repo/web/a.js", &[1][..]),
+            ]
+        );
+
+        let lib = engine.cycle_stats(Some("repo/lib/"), 10);
+        assert_eq!(lib.cycles, 1);
+        assert_eq!(
+            lib.largest_cycles[0].files,
+            vec!["repo/lib/x.js", "repo/lib/y.js"]
+        );
+        assert_eq!(engine.cycle_stats(None, 1).largest_cycles.len(), 1);
+
+        // Import lines survive a save/load round trip
+        let index_path = temp_dir.path().join("index.bin");
+        let config = IndexerConfig {
+            paths: vec![root.to_string_lossy().to_string()],
+            ..Default::default()
+        };
+        engine.save_index(&index_path, &config).unwrap();
+        let mut loaded = SearchEngine::new();
+        loaded.add_root_path(&root);
+        loaded.load_index(&index_path).unwrap();
+        assert_eq!(loaded.cycle_stats(None, 10), stats);
+    }
+
+    #[test]
+    fn test_search_within_dependency_neighborhood() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().join("repo");
+        let files = [
+            (
+                "http/client.js",
+                "import './retry';\nfunction retry_client() {}\n",
+            ),
+            (
+                "http/retry.js",
+                "import '../util/backoff';\nfunction retry_once() {}\n",
+            ),
+            ("util/backoff.js", "function retry_later() {}\n"),
+            (
+                "cli/main.js",
+                "import '../http/client';\nfunction retry_cli() {}\n",
+            ),
+            ("other.js", "function retry_other() {}\n"),
+        ];
+        for (path, content) in files {
+            let path = root.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+
+        let mut engine = SearchEngine::new();
+        engine.add_root_path(&root);
+        for (path, _) in files {
+            engine.index_file(root.join(path)).unwrap();
+        }
+        engine.resolve_imports();
+        engine.finalize();
+
+        let client = engine.find_file_id("repo/http/client.js").unwrap();
+        let scoped = |direction, hops| SearchScope {
+            within: Some(engine.dependency_neighborhood(client, direction, hops)),
+            ..SearchScope::default()
+        };
+        let paths = |matches: Vec<SearchMatch>| {
+            let mut paths: Vec<String> = matches.into_iter().map(|m| m.file_path).collect();
+            paths.sort();
+            paths.dedup();
+            paths
+        };
+
+        let deps = scoped(DependencyDirection::Dependencies, 2);
+        let (text, _) = engine
+            .search_with_filter_ranked("retry_", "", "", 10, RankMode::Full, &deps)
+            .unwrap();
+        assert_eq!(
+            paths(text),
+            vec![
+                "repo/http/client.js",
+                "repo/http/retry.js",
+                "repo/util/backoff.js"
+            ]
+        );
+
+        let one_hop = scoped(DependencyDirection::Dependencies, 1);
+        let (regex, _) = engine
+            .search_regex_ranked("retry_[a-z]+", "", "", 10, &one_hop)
+            .unwrap();
+        assert_eq!(
+            paths(regex),
+            vec!["repo/http/client.js", "repo/http/retry.js"]
+        );
+
+        let dependents = scoped(DependencyDirection::Dependents, 1);
+        let (symbols, _) = engine
+            .search_symbols_ranked("retry", "", "", 10, &dependents)
+            .unwrap();
+        assert_eq!(
+            paths(symbols),
+            vec!["repo/cli/main.js", "repo/http/client.js"]
+        );
+        let counts = engine
+            .count_matches("retry_", "", "", QueryKind::Text, &dependents)
+            .unwrap();
+        assert_eq!(counts.matched_files, 2);
+    }
+
+    #[test]
+    fn test_unresolved_imports_are_kept_and_persisted() {
+        use crate::config::IndexerConfig;
+
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().join("repo");
+        let files = [
+            (
+                "web/app.ts",
+                "import React from 'react';\nimport { x } from './gone';\nimport './util';\n",
+            ),
+            (
+                "web/util.ts",
+                "import { map } from 'lodash/fp';\nimport 'react';\n",
+            ),
+            ("py/main.py", "import numpy.linalg\n"),
+        ];
+        for (path, content) in files {
+            let path = root.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+
+        let mut engine = SearchEngine::new();
+        engine.add_root_path(&root);
+        for (path, _) in files {
+            engine.index_file(root.join(path)).unwrap();
+        }
+        engine.resolve_imports();
+
+        let app = engine.find_file_id("repo/web/app.ts").unwrap();
+        let unresolved = engine.get_unresolved_imports(app);
+        assert_eq!(
+            unresolved
+                .iter()
+                .map(|i| (i.path.as_str(), i.line, i.module.as_deref()))
+                .collect::<Vec<_>>(),
+            vec![("react", 0, Some("react")), ("./gone", 1, None)]
+        );
+
+        let web: Vec<u32> = engine
+            .files_with_unresolved_imports(Some("repo/web/"))
+            .into_iter()
+            .map(|(id, _)| id)
+            .collect();
+        assert_eq!(web.len(), 2);
+        let stats = engine.unresolved_import_stats(web, 10);
+        assert_eq!((stats.total, stats.files, stats.local), (4, 2, 1));
+        assert_eq!(stats.by_language.len(), 1);
+        assert_eq!(stats.external_modules[0].module, "react");
+        assert_eq!(stats.external_modules[0].files, 2);
+
+        let all = |engine: &SearchEngine| {
+            let ids = engine
+                .files_with_unresolved_imports(None)
+                .into_iter()
+                .map(|(id, _)| id);
+            engine.unresolved_import_stats(ids, 10)
+        };
+        let before = all(&engine);
+        assert_eq!(before.total, 5);
+
+        let index_path = temp_dir.path().join("index.bin");
+        let config = IndexerConfig {
+            paths: vec![root.to_string_lossy().to_string()],
+            ..Default::default()
+        };
+        engine.save_index(&index_path, &config).unwrap();
+        let mut loaded = SearchEngine::new();
+        loaded.add_root_path(&root);
+        loaded.load_index(&index_path).unwrap();
+        assert_eq!(all(&loaded), before);
+    }
+
+    #[test]
+    fn test_packages_from_manifests() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().join("repo");
+        let files = [
+            (
+                "web/package.json",
+                r#"{ "name": "@acme/web", "version": "1.4.0", "dependencies": { "@acme/core": "*", "react": "^18" } }"#,
+            ),
+            (
+                "web/src/app.js",
+                "import '../../core/src/index';\nfunction render_page() {}\n",
+            ),
+            (
+                "core/package.json",
+                r#"{ "name": "@acme/core", "devDependencies": { "vitest": "1" } }"#,
+            ),
+            ("core/src/index.js", "function render_core() {}\n"),
+            ("scripts/build.js", "function render_script() {}\n"),
+        ];
+        for (path, content) in files {
+            let path = root.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+
+        let mut engine = SearchEngine::new();
+        engine.add_root_path(&root);
+        for (path, _) in files.iter().filter(|(p, _)| p.ends_with(".js")) {
+            engine.index_file(root.join(path)).unwrap();
+        }
+        engine.resolve_imports();
+        engine.finalize();
+
+        let package = |path: &str| {
+            let file_id = engine.find_file_id(path).unwrap();
+            engine.file_package(file_id).map(|p| p.name.clone())
+        };
+        assert_eq!(package("repo/web/src/app.js").as_deref(), Some("@acme/web"));
+        assert_eq!(
+            package("repo/core/src/index.js").as_deref(),
+            Some("@acme/core")
+        );
+        assert_eq!(package("repo/scripts/build.js"), None);
+
+        let scope = SearchScope {
+            packages: vec!["@acme/web".to_string()],
+            ..SearchScope::default()
+        };
+        let (matches, _) = engine
+            .search_with_filter_ranked("render_", "", "", 10, RankMode::Full, &scope)
+            .unwrap();
+        let paths: Vec<String> = matches.into_iter().map(|m| m.file_path).collect();
+        assert_eq!(paths, vec!["repo/web/src/app.js"]);
+
+        let packages = engine.packages(None);
+        assert_eq!(packages.len(), 2);
+        assert_eq!(packages[0].name, "@acme/core");
+        assert_eq!(packages[0].external_dependencies, vec!["vitest"]);
+        let web = &packages[1];
+        assert_eq!(web.version.as_deref(), Some("1.4.0"));
+        assert_eq!(web.manifest, "repo/web/package.json");
+        assert_eq!(web.files, 1);
+        assert_eq!(web.workspace_dependencies, vec!["@acme/core"]);
+        assert_eq!(web.external_dependencies, vec!["react"]);
+        assert_eq!(engine.packages(Some("repo/core/")).len(), 1);
+
+        let graph = engine.dependency_graph(None, GraphLevel::Package);
+        let edges: Vec<(String, String)> = graph
+            .edges
+            .into_iter()
+            .map(|e| (e.source, e.target))
+            .collect();
+        assert_eq!(
+            edges,
+            vec![("@acme/web".to_string(), "@acme/core".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_dead_code_candidates() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().join("repo");
+        let files = [
+            (
+                "web/index.js",
+                "import { used_helper } from './used';\nused_helper();\n",
+            ),
+            (
+                "web/used.js",
+                "export function used_helper() {}\nexport function never_called_anywhere() {}\nfunction private_helper() {}\nexport function UsedHelper() {}\nexport function UnusedHelper() {}\n",
+            ),
+            // Mixed-case names are found in files of any kind, with their case
+            (
+                "docs/notes.md",
+                "Call `UsedHelper` first; `unusedhelper` is not the same name.\n",
+            ),
+            ("web/orphan.js", "export function orphan_fn() {}\n"),
+            (
+                "web/used.test.js",
+                "import { used_helper } from './used';\nused_helper();\n",
+            ),
+            ("svc/handler.go", "package svc\n\nfunc Handle() {}\n"),
+        ];
+        for (path, content) in files {
+            let path = root.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+
+        let mut engine = SearchEngine::new();
+        engine.add_root_path(&root);
+        for (path, _) in files {
+            engine.index_file(root.join(path)).unwrap();
+        }
+        engine.resolve_imports();
+        engine.finalize();
+
+        let report = engine.dead_code(None, true, 10);
+        // index.js and used.test.js are entry points; Go files are linked by package
+        assert_eq!(report.files_checked, 2);
+        assert_eq!(report.unused_files, 1);
+        assert_eq!(report.unused_symbols, 3);
+        assert_eq!(report.directories.len(), 2);
+        let web = &report.directories[0];
+        assert_eq!(web.directory, "repo/web");
+        assert_eq!(web.files, vec!["repo/web/orphan.js"]);
+        let symbols: Vec<(&str, usize)> = web
+            .symbols
+            .iter()
+            .map(|s| (s.name.as_str(), s.line))
+            .collect();
+        assert_eq!(
+            symbols,
+            vec![("never_called_anywhere", 2), ("UnusedHelper", 5)]
+        );
+
+        let without_symbols = engine.dead_code(Some("repo/web/"), false, 10);
+        assert_eq!(without_symbols.unused_symbols, 0);
+        // Go files are not checked for importers, but their exported symbols are
+        let svc = engine.dead_code(Some("repo/svc/"), true, 10);
+        assert!(svc.directories[0].files.is_empty());
+        assert_eq!(svc.directories[0].symbols[0].name, "Handle");
+
+        engine
+            .set_entrypoints(&["index.js".to_string(), "orphan.js".to_string()])
+            .unwrap();
+        // Configured patterns replace the defaults, so the test file is checked too
+        let report = engine.dead_code(None, false, 10);
+        assert_eq!(report.files_checked, 2);
+        assert_eq!(report.directories[0].files, vec!["repo/web/used.test.js"]);
+    }
+
+    #[test]
+    fn test_package_roots_resolve_across_roots() {
+        let temp_dir = TempDir::new().unwrap();
+        let service = temp_dir.path().join("service");
+        let shared = temp_dir.path().join("shared-lib");
+        let files = [
+            (
+                &service,
+                "app.js",
+                "import '@acme/shared';\nimport { retry } from '@acme/shared/retry';\n",
+            ),
+            (&service, "app.py", "import shared_lib.util\n"),
+            (
+                &service,
+                "main.go",
+                "package main\n\nimport \"github.com/acme/shared/errs\"\n",
+            ),
+            (
+                &service,
+                "src/main.rs",
+                "use acme_core::backoff::Backoff;\n",
+            ),
+            (
+                &shared,
+                "js/package.json",
+                r#"{ "name": "@acme/shared", "main": "lib/entry.js" }"#,
+            ),
+            (&shared, "js/lib/entry.js", "export const entry = 1;\n"),
+            (&shared, "js/src/retry.js", "export function retry() {}\n"),
+            (&shared, "py/shared_lib/__init__.py", ""),
+            (&shared, "py/shared_lib/util.py", "def util():\n    pass\n"),
+            (&shared, "go/errs/errs.go", "package errs\n"),
+            (
+                &shared,
+                "core/Cargo.toml",
+                "[package]\nname = \"acme-core\"\nversion = \"0.1.0\"\n",
+            ),
+            (&shared, "core/src/lib.rs", "pub mod backoff;\n"),
+            (&shared, "core/src/backoff.rs", "pub struct Backoff;\n"),
+        ];
+        for (root, path, content) in &files {
+            let path = root.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+
+        let mut engine = SearchEngine::new();
+        engine.add_root_path(&service);
+        engine.add_root_path(&shared);
+        let roots: std::collections::BTreeMap<String, String> = [
+            ("@acme/shared", "../shared-lib/js"),
+            ("shared_lib", "../shared-lib/py/shared_lib"),
+            ("github.com/acme/shared", "../shared-lib/go"),
+            ("acme-core", "../shared-lib/core"),
+            ("missing", "../nowhere"),
+        ]
+        .into_iter()
+        .map(|(package, dir)| (package.to_string(), dir.to_string()))
+        .collect();
+        engine.set_package_roots(&roots);
+        for (root, path, _) in &files {
+            if !path.ends_with(".toml") && !path.ends_with(".json") {
+                engine.index_file(root.join(path)).unwrap();
+            }
+        }
+        engine.resolve_imports();
+        engine.finalize();
+
+        let id = |path: &str| engine.find_file_id(path).unwrap();
+        let dependencies = |path: &str| {
+            let mut paths: Vec<String> = engine
+                .dependency_index
+                .get_dependencies(id(path))
+                .into_iter()
+                .filter_map(|dep| engine.file_store.get_path(dep))
+                .map(|dep| engine.make_display_path(dep))
+                .collect();
+            paths.sort();
+            paths
+        };
+        assert_eq!(
+            dependencies("service/app.js"),
+            vec!["shared-lib/js/lib/entry.js", "shared-lib/js/src/retry.js"]
+        );
+        assert_eq!(
+            dependencies("service/app.py"),
+            vec!["shared-lib/py/shared_lib/util.py"]
+        );
+        assert_eq!(
+            dependencies("service/main.go"),
+            vec!["shared-lib/go/errs/errs.go"]
+        );
+        assert_eq!(
+            dependencies("service/src/main.rs"),
+            vec!["shared-lib/core/src/backoff.rs"]
+        );
+
+        // Dependents and the ranking signal span both roots
+        let retry = id("shared-lib/js/src/retry.js");
+        assert_eq!(
+            engine.dependency_index.get_dependents(retry),
+            vec![id("service/app.js")]
+        );
+        assert_eq!(engine.dependency_index.get_import_count(retry), 1);
+    }
 }
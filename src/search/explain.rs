@@ -0,0 +1,274 @@
+//! Score breakdowns for `explain=true` searches.
+//!
+//! The ranking formulas live here as small factor structs whose product is
+//! the score, so the hot scoring path and the explanation can never drift
+//! apart: `calculate_score_inline` and friends in the engine are thin
+//! wrappers around these types.
+
+use crate::symbols::Language;
+use regex::Regex;
+use serde::Serialize;
+use std::path::Path;
+
+/// Components of `FileMetadata::base_score`, computed once per file at index time.
+///
+/// `total = (base + src_lib + language + symbols + dependencies) × test_penalty`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct BaseScoreBreakdown {
+    /// Starting score every file receives
+    pub base: f32,
+    /// Added when the file lives under `src/` or `lib/`
+    pub src_lib: f32,
+    /// Added for high-value (code) or medium-value (docs/config) languages
+    pub language: f32,
+    /// `log2(symbol_count)`, capped at 4
+    pub symbols: f32,
+    /// `log2(dependent_count)`, capped at 5
+    pub dependencies: f32,
+    /// Multiplier for test/example paths (0.7) or 1.0
+    pub test_penalty: f32,
+    pub total: f32,
+}
+
+impl BaseScoreBreakdown {
+    pub fn compute(
+        path: &Path,
+        language: Option<Language>,
+        symbol_count: usize,
+        dependency_count: u32,
+    ) -> Self {
+        let path_lower = path.to_string_lossy().to_lowercase();
+
+        let src_lib = if is_src_lib_path(&path_lower) {
+            2.0
+        } else {
+            0.0
+        };
+
+        let language = language.map_or(0.0, Language::ranking_weight);
+
+        // Files with symbols are more likely to be important code
+        let symbols = if symbol_count > 0 {
+            (symbol_count as f32).log2().min(4.0) // Up to +4 for 16+ symbols
+        } else {
+            0.0
+        };
+
+        // Files imported by others are important
+        let dependencies = if dependency_count > 0 {
+            (dependency_count as f32).log2().min(5.0)
+        } else {
+            0.0
+        };
+
+        let test_penalty = if path_lower.contains("/test")
+            || path_lower.contains("\\test")
+            || path_lower.contains("/example")
+            || path_lower.contains("\\example")
+        {
+            0.7
+        } else {
+            1.0
+        };
+
+        let base = 1.0;
+        let total = (base + src_lib + language + symbols + dependencies) * test_penalty;
+
+        Self {
+            base,
+            src_lib,
+            language,
+            symbols,
+            dependencies,
+            test_penalty,
+            total,
+        }
+    }
+
+    pub fn is_src_lib(&self) -> bool {
+        self.src_lib > 0.0
+    }
+}
+
+/// Whether a (lowercased) path is inside a primary source directory
+pub(crate) fn is_src_lib_path(path_lower: &str) -> bool {
+    path_lower.contains("/src/")
+        || path_lower.contains("\\src\\")
+        || path_lower.contains("/lib/")
+        || path_lower.contains("\\lib\\")
+}
+
+/// Multiplicative line-level factors; the line score is their product
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct LineScoreFactors {
+    /// 2.0 when the line contains the query with exact case (text search only)
+    pub exact_case: f64,
+    /// 3.0 when the line holds a symbol definition
+    pub symbol_definition: f64,
+    /// 1.5 for files under `src/` or `lib/`
+    pub src_lib: f64,
+    /// Shorter lines score higher: `1 / (1 + ln(1 + len/100))`, floored at 0.3
+    pub line_length: f64,
+    /// 1.5 when the match starts the (trimmed) line
+    pub line_start: f64,
+}
+
+impl LineScoreFactors {
+    /// Factors for a case-insensitive text match
+    #[inline]
+    pub fn text(
+        line: &str,
+        original_query: &str,
+        query_lower: &str,
+        is_symbol_def: bool,
+        is_src_lib: bool,
+    ) -> Self {
+        let trimmed = line.trim_start();
+        let starts_line = trimmed.len() >= query_lower.len()
+            && trimmed.as_bytes()[..query_lower.len()].eq_ignore_ascii_case(query_lower.as_bytes());
+        Self {
+            exact_case: if line.contains(original_query) {
+                2.0
+            } else {
+                1.0
+            },
+            symbol_definition: if is_symbol_def { 3.0 } else { 1.0 },
+            src_lib: if is_src_lib { 1.5 } else { 1.0 },
+            line_length: line_length_factor(line),
+            line_start: if starts_line { 1.5 } else { 1.0 },
+        }
+    }
+
+    /// Factors for a regex match (no exact-case factor)
+    #[inline]
+    pub fn regex(line: &str, regex: &Regex, is_symbol_def: bool, is_src_lib: bool) -> Self {
+        let starts_line = regex
+            .find(line.trim_start())
+            .map(|m| m.start() == 0)
+            .unwrap_or(false);
+        Self {
+            exact_case: 1.0,
+            symbol_definition: if is_symbol_def { 3.0 } else { 1.0 },
+            src_lib: if is_src_lib { 1.5 } else { 1.0 },
+            line_length: line_length_factor(line),
+            line_start: if starts_line { 1.5 } else { 1.0 },
+        }
+    }
+
+    #[inline]
+    pub fn product(&self) -> f64 {
+        self.exact_case * self.symbol_definition * self.src_lib * self.line_length * self.line_start
+    }
+}
+
+/// Boost for shorter lines (more relevant) — gentle logarithmic curve, floors at 0.3
+#[inline]
+fn line_length_factor(line: &str) -> f64 {
+    (1.0 / (1.0 + (line.len() as f64 / 100.0).ln_1p())).max(0.3)
+}
+
+/// Per-file multipliers applied to every match in a file
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct FileBoostBreakdown {
+    /// `1 + log10(dependent_count) × 0.5`
+    pub dependency: f64,
+    /// `1 + recency_weight × freshness` (1.0 when recency ranking is off)
+    pub recency: f64,
+    /// Learned click-feedback boost (1.0 without feedback)
+    pub feedback: f64,
+}
+
+impl FileBoostBreakdown {
+    #[inline]
+    pub fn product(&self) -> f64 {
+        self.dependency * self.recency * self.feedback
+    }
+}
+
+/// How the document was selected for line-level scoring
+#[derive(Debug, Clone, Serialize)]
+pub struct RankingExplanation {
+    /// "fast" (only the top files by `file_score` were read) or "full" (all candidates read)
+    pub mode: &'static str,
+    /// File-level score used by fast ranking: base score × filename boost ×
+    /// recency boost × feedback boost, so recently changed and clicked files
+    /// are among the files read
+    pub file_score: f32,
+    /// `×5` when the lowercase filename stem contains the query, else 1.0
+    pub filename_stem_boost: f32,
+    /// Candidate documents after trigram and path filtering
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_candidates: Option<usize>,
+    /// Documents actually read
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub candidates_searched: Option<usize>,
+}
+
+/// Full breakdown of a single result's score
+#[derive(Debug, Clone, Serialize)]
+pub struct ScoreExplanation {
+    pub ranking: RankingExplanation,
+    pub base_score: BaseScoreBreakdown,
+    /// Line factors (absent for filename-only matches)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<LineScoreFactors>,
+    /// 3.0 for synthetic filename matches, which skip line scoring
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filename_match: Option<f64>,
+    /// 2.0 when a symbol search matches the symbol name exactly
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exact_symbol_name: Option<f64>,
+    /// Fuzzy score of the matched symbol name (0.0-1.0) for fuzzy symbol
+    /// searches, applied in place of the exact-name factor
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fuzzy_match: Option<f64>,
+    pub file_boost: FileBoostBreakdown,
+    /// Product of all applied factors (equals the result score)
+    pub score: f64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base_score_breakdown_parts() {
+        let b = BaseScoreBreakdown::compute(
+            Path::new("/repo/src/main.rs"),
+            Some(Language::Rust),
+            16,
+            4,
+        );
+        assert_eq!(b.src_lib, 2.0);
+        assert_eq!(b.language, 1.5);
+        assert_eq!(b.symbols, 4.0);
+        assert_eq!(b.dependencies, 2.0);
+        assert_eq!(b.test_penalty, 1.0);
+        assert_eq!(b.total, 10.5);
+
+        let t = BaseScoreBreakdown::compute(
+            Path::new("/repo/tests/notes.md"),
+            Some(Language::Markdown),
+            0,
+            0,
+        );
+        assert_eq!(t.language, 0.5);
+        assert_eq!(t.test_penalty, 0.7);
+        assert!((t.total - 1.05).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_line_factors_product() {
+        let f = LineScoreFactors::text("fn Parse() {}", "Parse", "parse", true, true);
+        assert_eq!(f.exact_case, 2.0);
+        assert_eq!(f.symbol_definition, 3.0);
+        assert_eq!(f.src_lib, 1.5);
+        assert_eq!(f.line_start, 1.0);
+        assert!((f.product() - 9.0 * f.line_length).abs() < 1e-9);
+
+        let re = Regex::new("fn").unwrap();
+        let r = LineScoreFactors::regex("  fn x()", &re, false, false);
+        assert_eq!(r.line_start, 1.5);
+        assert_eq!(r.exact_case, 1.0);
+    }
+}
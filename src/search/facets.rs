@@ -0,0 +1,241 @@
+//! Exact match counting and result facets.
+//!
+//! Ranked search stops reading files once it has enough results, so the
+//! number of returned results says little about how many matches exist.
+//! The types here back the opt-in `count=exact` mode: every candidate
+//! document is verified and matches are tallied per extension, language,
+//! root path and top-level directory so that clients can render filter chips.
+
+use crate::symbols::Language;
+use rustc_hash::FxHashMap;
+use serde::Serialize;
+use std::path::Path;
+
+/// How the total number of matches should be reported
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CountMode {
+    /// Report only the number of returned results (default, cheapest)
+    #[default]
+    Returned,
+    /// Verify every candidate document and report the true match count plus facets
+    Exact,
+}
+
+impl CountMode {
+    /// Parse from string (for API parameter)
+    pub fn parse(s: &str) -> Self {
+        match s.to_lowercase().as_str() {
+            "exact" => CountMode::Exact,
+            _ => CountMode::Returned,
+        }
+    }
+}
+
+/// Kind of query being counted, mirroring the three search flavours
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueryKind {
+    /// Case-insensitive substring search
+    Text,
+    /// Regular expression search
+    Regex,
+    /// Symbol name search
+    Symbols,
+}
+
+/// A single facet bucket
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct FacetCount {
+    /// Bucket value (e.g. `rs`, `Rust`, `project`, `project/src`)
+    pub value: String,
+    /// Number of files with at least one match in this bucket
+    pub files: usize,
+    /// Number of matches in this bucket
+    pub matches: usize,
+}
+
+/// Match counts broken down by facet, sorted by descending match count
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SearchFacets {
+    pub extensions: Vec<FacetCount>,
+    pub languages: Vec<FacetCount>,
+    pub roots: Vec<FacetCount>,
+    pub directories: Vec<FacetCount>,
+}
+
+/// Result of an exact count over all candidate documents
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MatchCounts {
+    /// Total number of matches across all files (not capped per document)
+    pub total_matches: usize,
+    /// Number of files containing at least one match
+    pub matched_files: usize,
+    pub facets: SearchFacets,
+}
+
+/// Accumulates per-file match counts into facet buckets
+#[derive(Debug, Default)]
+pub(crate) struct FacetAccumulator {
+    total_matches: usize,
+    matched_files: usize,
+    extensions: FxHashMap<String, (usize, usize)>,
+    languages: FxHashMap<String, (usize, usize)>,
+    roots: FxHashMap<String, (usize, usize)>,
+    directories: FxHashMap<String, (usize, usize)>,
+}
+
+impl FacetAccumulator {
+    /// Record `matches` matches in a file.
+    ///
+    /// `display_path` is the workspace-relative display path, `root_name`
+    /// the name of the root folder the file lives under (if any) and
+    /// `language` the language detected at index time.
+    pub(crate) fn add(
+        &mut self,
+        path: &Path,
+        display_path: &str,
+        root_name: Option<&str>,
+        language: Option<Language>,
+        matches: usize,
+    ) {
+        if matches == 0 {
+            return;
+        }
+        self.total_matches += matches;
+        self.matched_files += 1;
+
+        let extension = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_lowercase())
+            .unwrap_or_else(|| "(none)".to_string());
+        let language = language.map_or("Other", Language::name);
+        bump(&mut self.extensions, extension, matches);
+        bump(&mut self.languages, language.to_string(), matches);
+
+        match root_name {
+            Some(root) => {
+                bump(&mut self.roots, root.to_string(), matches);
+                // Display paths start with the root folder name; the top-level
+                // directory is the component right below it.
+                let below_root = display_path
+                    .strip_prefix(root)
+                    .and_then(|rest| rest.strip_prefix('/'))
+                    .unwrap_or("");
+                let directory = match below_root.split_once('/') {
+                    Some((dir, _)) => format!("{}/{}", root, dir),
+                    None => root.to_string(),
+                };
+                bump(&mut self.directories, directory, matches);
+            }
+            None => {
+                bump(&mut self.roots, "(other)".to_string(), matches);
+                bump(&mut self.directories, "(other)".to_string(), matches);
+            }
+        }
+    }
+
+    /// Finish accumulation, producing sorted facet lists
+    pub(crate) fn finish(self) -> MatchCounts {
+        MatchCounts {
+            total_matches: self.total_matches,
+            matched_files: self.matched_files,
+            facets: SearchFacets {
+                extensions: into_sorted(self.extensions),
+                languages: into_sorted(self.languages),
+                roots: into_sorted(self.roots),
+                directories: into_sorted(self.directories),
+            },
+        }
+    }
+}
+
+fn bump(map: &mut FxHashMap<String, (usize, usize)>, key: String, matches: usize) {
+    let entry = map.entry(key).or_default();
+    entry.0 += 1;
+    entry.1 += matches;
+}
+
+fn into_sorted(map: FxHashMap<String, (usize, usize)>) -> Vec<FacetCount> {
+    let mut buckets: Vec<FacetCount> = map
+        .into_iter()
+        .map(|(value, (files, matches))| FacetCount {
+            value,
+            files,
+            matches,
+        })
+        .collect();
+    buckets.sort_by(|a, b| {
+        b.matches
+            .cmp(&a.matches)
+            .then_with(|| a.value.cmp(&b.value))
+    });
+    buckets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_accumulator_buckets_by_root_and_directory() {
+        let mut acc = FacetAccumulator::default();
+        acc.add(
+            Path::new("/ws/project/src/main.rs"),
+            "project/src/main.rs",
+            Some("project"),
+            Some(Language::Rust),
+            3,
+        );
+        acc.add(
+            Path::new("/ws/project/README.md"),
+            "project/README.md",
+            Some("project"),
+            Some(Language::Markdown),
+            1,
+        );
+        acc.add(
+            Path::new("/elsewhere/x.py"),
+            "/elsewhere/x.py",
+            None,
+            Some(Language::Python),
+            2,
+        );
+        // Zero-match files are ignored entirely
+        acc.add(
+            Path::new("/ws/project/a.rs"),
+            "project/a.rs",
+            Some("project"),
+            Some(Language::Rust),
+            0,
+        );
+
+        let counts = acc.finish();
+        assert_eq!(counts.total_matches, 6);
+        assert_eq!(counts.matched_files, 3);
+        assert_eq!(
+            counts.facets.extensions[0],
+            FacetCount {
+                value: "rs".to_string(),
+                files: 1,
+                matches: 3
+            }
+        );
+        let dirs: Vec<&str> = counts
+            .facets
+            .directories
+            .iter()
+            .map(|f| f.value.as_str())
+            .collect();
+        assert_eq!(dirs, vec!["project/src", "(other)", "project"]);
+        assert_eq!(counts.facets.roots[0].value, "project");
+        assert_eq!(counts.facets.roots[0].matches, 4);
+        assert!(counts.facets.languages.iter().any(|f| f.value == "Python"));
+    }
+
+    #[test]
+    fn test_count_mode_parse() {
+        assert_eq!(CountMode::parse("exact"), CountMode::Exact);
+        assert_eq!(CountMode::parse("EXACT"), CountMode::Exact);
+        assert_eq!(CountMode::parse(""), CountMode::Returned);
+    }
+}
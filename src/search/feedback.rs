@@ -0,0 +1,379 @@
+//! Click feedback for learning-to-rank.
+//!
+//! Clients report which result a user opened for a query (and optionally
+//! which ones they skipped). Events are appended to a local JSON-lines file
+//! and aggregated into exponentially decaying per-file and per-directory
+//! scores. The scores translate into a bounded ranking boost (never a
+//! penalty), so a handful of clicks nudges ranking without overriding the
+//! structural signals.
+
+use anyhow::{Context, Result};
+use rustc_hash::FxHashMap;
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock};
+
+/// Maximum extra multiplier contributed by clicks on the file itself
+const MAX_FILE_BOOST: f32 = 1.0;
+/// Maximum extra multiplier contributed by clicks within a directory
+const MAX_PREFIX_BOOST: f32 = 0.5;
+/// Score removed from a file when it was shown but not clicked
+const SKIP_PENALTY: f32 = 0.25;
+
+/// A single feedback event reported by a client
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeedbackEvent {
+    /// The query the results were produced for
+    pub query: String,
+    /// Display path of the result (as returned by the search API)
+    pub file_path: String,
+    /// 0-based position of the result in the list
+    #[serde(default)]
+    pub rank: usize,
+    /// Whether the user opened the result (false = shown but skipped)
+    #[serde(default = "default_clicked")]
+    pub clicked: bool,
+    /// Seconds since UNIX epoch; filled in by the server when absent
+    #[serde(default)]
+    pub timestamp: u64,
+}
+
+fn default_clicked() -> bool {
+    true
+}
+
+impl FeedbackEvent {
+    /// Evidence carried by this event. Clicking a result further down the
+    /// list is a stronger signal than clicking the first one.
+    fn weight(&self) -> f32 {
+        if self.clicked {
+            1.0 + (self.rank as f32).ln_1p() * 0.5
+        } else {
+            -SKIP_PENALTY
+        }
+    }
+}
+
+/// A score that halves every `half_life_secs`
+#[derive(Debug, Clone, Copy, Default)]
+struct DecayingScore {
+    value: f32,
+    updated_at: u64,
+}
+
+impl DecayingScore {
+    fn value_at(&self, now: u64, half_life_secs: f32) -> f32 {
+        let elapsed = now.saturating_sub(self.updated_at) as f32;
+        self.value * 0.5f32.powf(elapsed / half_life_secs)
+    }
+
+    fn add(&mut self, amount: f32, at: u64, half_life_secs: f32) {
+        // Events may be replayed out of order; decay towards the newer timestamp
+        let now = at.max(self.updated_at);
+        let current = self.value_at(now, half_life_secs);
+        let amount = amount * 0.5f32.powf(now.saturating_sub(at) as f32 / half_life_secs);
+        self.value = (current + amount).max(0.0);
+        self.updated_at = now;
+    }
+}
+
+#[derive(Debug, Default)]
+struct FeedbackScores {
+    files: FxHashMap<String, DecayingScore>,
+    prefixes: FxHashMap<String, DecayingScore>,
+    events: usize,
+}
+
+/// Aggregated click feedback with an optional on-disk event log
+#[derive(Debug)]
+pub struct FeedbackStore {
+    path: Option<PathBuf>,
+    half_life_secs: f32,
+    scores: Mutex<FeedbackScores>,
+    /// Display paths of the indexed documents, sorted, so that an event can
+    /// be mapped to its document without scanning the index
+    documents: RwLock<Arc<Vec<(String, u32)>>>,
+    /// Boost per document ID, rebuilt when documents change and updated in
+    /// place for the document an event names
+    doc_boosts: RwLock<Arc<FxHashMap<u32, f32>>>,
+}
+
+impl FeedbackStore {
+    /// Create an in-memory store (nothing is persisted)
+    pub fn in_memory(half_life_days: f32) -> Self {
+        Self {
+            path: None,
+            half_life_secs: half_life_days.max(0.01) * 86_400.0,
+            scores: Mutex::new(FeedbackScores::default()),
+            documents: RwLock::new(Arc::new(Vec::new())),
+            doc_boosts: RwLock::new(Arc::new(FxHashMap::default())),
+        }
+    }
+
+    /// Open (or create) a store backed by a JSON-lines event log.
+    /// Existing events are replayed so learned boosts survive restarts.
+    pub fn open(path: impl AsRef<Path>, half_life_days: f32) -> Result<Self> {
+        let path = path.as_ref();
+        let mut store = Self::in_memory(half_life_days);
+        store.path = Some(path.to_path_buf());
+
+        if path.exists() {
+            let file = std::fs::File::open(path)
+                .with_context(|| format!("Failed to open feedback log: {}", path.display()))?;
+            let mut skipped = 0usize;
+            for line in std::io::BufReader::new(file).lines() {
+                let line = line?;
+                if line.trim().is_empty() {
+                    continue;
+                }
+                match serde_json::from_str::<FeedbackEvent>(&line) {
+                    Ok(event) => store.apply(&event),
+                    Err(_) => skipped += 1,
+                }
+            }
+            if skipped > 0 {
+                tracing::warn!(
+                    path = %path.display(),
+                    skipped,
+                    "Skipped malformed feedback log entries"
+                );
+            }
+        }
+
+        Ok(store)
+    }
+
+    /// Record an event: append it to the log (if any) and update scores
+    pub fn record(&self, mut event: FeedbackEvent) -> Result<()> {
+        if event.timestamp == 0 {
+            event.timestamp = super::recency::now_secs();
+        }
+        if let Some(ref path) = self.path {
+            let mut file = std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .with_context(|| format!("Failed to open feedback log: {}", path.display()))?;
+            writeln!(file, "{}", serde_json::to_string(&event)?)?;
+        }
+        self.apply(&event);
+        Ok(())
+    }
+
+    fn apply(&self, event: &FeedbackEvent) {
+        let weight = event.weight();
+        let mut scores = self.scores.lock().unwrap_or_else(|e| e.into_inner());
+        scores.events += 1;
+        scores
+            .files
+            .entry(event.file_path.clone())
+            .or_default()
+            .add(weight, event.timestamp, self.half_life_secs);
+        // Skips only demote the file itself, not its whole directory
+        if event.clicked {
+            for prefix in directory_prefixes(&event.file_path) {
+                scores.prefixes.entry(prefix.to_string()).or_default().add(
+                    weight,
+                    event.timestamp,
+                    self.half_life_secs,
+                );
+            }
+        }
+    }
+
+    /// Number of events applied since startup (including replayed ones)
+    pub fn event_count(&self) -> usize {
+        self.scores.lock().map(|s| s.events).unwrap_or(0)
+    }
+
+    /// Ranking multiplier (>= 1.0) for a display path at time `now`
+    pub fn boost_for_path(&self, display_path: &str, now: u64) -> f32 {
+        let scores = self.scores.lock().unwrap_or_else(|e| e.into_inner());
+        self.boost(&scores, display_path, now)
+    }
+
+    /// Boost from the file's own score and its best directory score
+    fn boost(&self, scores: &FeedbackScores, display_path: &str, now: u64) -> f32 {
+        let file_score = scores
+            .files
+            .get(display_path)
+            .map(|s| s.value_at(now, self.half_life_secs))
+            .unwrap_or(0.0);
+        let prefix_score = directory_prefixes(display_path)
+            .filter_map(|p| scores.prefixes.get(p))
+            .map(|s| s.value_at(now, self.half_life_secs))
+            .fold(0.0f32, f32::max);
+
+        let file_boost = (file_score.ln_1p() * 0.5).min(MAX_FILE_BOOST);
+        let prefix_boost = (prefix_score.ln_1p() * 0.25).min(MAX_PREFIX_BOOST);
+        (1.0 + file_boost) * (1.0 + prefix_boost)
+    }
+
+    /// Replace the indexed documents (display path, document ID) and rebuild
+    /// every document's boost
+    pub fn set_documents(&self, mut documents: Vec<(String, u32)>, now: u64) {
+        documents.sort_unstable();
+        let boosts = {
+            // One lock for the whole rebuild rather than one per document
+            let scores = self.scores.lock().unwrap_or_else(|e| e.into_inner());
+            if scores.files.is_empty() && scores.prefixes.is_empty() {
+                FxHashMap::default()
+            } else {
+                documents
+                    .iter()
+                    .filter_map(|(path, doc_id)| {
+                        let boost = self.boost(&scores, path, now);
+                        (boost > 1.0).then_some((*doc_id, boost))
+                    })
+                    .collect()
+            }
+        };
+        *self.documents.write().unwrap_or_else(|e| e.into_inner()) = Arc::new(documents);
+        *self.doc_boosts.write().unwrap_or_else(|e| e.into_inner()) = Arc::new(boosts);
+    }
+
+    /// Document ID of an indexed display path (as of the last
+    /// [`Self::set_documents`])
+    pub fn document_id(&self, display_path: &str) -> Option<u32> {
+        let documents = self.documents.read().unwrap_or_else(|e| e.into_inner());
+        documents
+            .binary_search_by(|(path, _)| path.as_str().cmp(display_path))
+            .ok()
+            .map(|idx| documents[idx].1)
+    }
+
+    /// Recompute the boost of a single document, in place
+    pub fn refresh_document(&self, display_path: &str, doc_id: u32, now: u64) {
+        let boost = {
+            let scores = self.scores.lock().unwrap_or_else(|e| e.into_inner());
+            self.boost(&scores, display_path, now)
+        };
+        let mut guard = self.doc_boosts.write().unwrap_or_else(|e| e.into_inner());
+        // Copies the map only while a search still holds the previous snapshot
+        let boosts = Arc::make_mut(&mut guard);
+        if boost > 1.0 {
+            boosts.insert(doc_id, boost);
+        } else {
+            boosts.remove(&doc_id);
+        }
+    }
+
+    /// Current per-document boost snapshot (documents without feedback are absent)
+    pub fn doc_boosts(&self) -> Arc<FxHashMap<u32, f32>> {
+        self.doc_boosts
+            .read()
+            .map(|g| Arc::clone(&g))
+            .unwrap_or_default()
+    }
+}
+
+/// Directory prefixes of a display path, from the root down
+/// (e.g. `a/b/c.rs` → `a`, `a/b`)
+fn directory_prefixes(path: &str) -> impl Iterator<Item = &str> {
+    path.match_indices('/').map(move |(i, _)| &path[..i])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn click(path: &str, rank: usize, timestamp: u64) -> FeedbackEvent {
+        FeedbackEvent {
+            query: "config".to_string(),
+            file_path: path.to_string(),
+            rank,
+            clicked: true,
+            timestamp,
+        }
+    }
+
+    #[test]
+    fn test_clicks_boost_file_and_directory() {
+        let store = FeedbackStore::in_memory(14.0);
+        let now = 1_000_000;
+        assert_eq!(store.boost_for_path("proj/src/config.rs", now), 1.0);
+
+        store.record(click("proj/src/config.rs", 3, now)).unwrap();
+        let file_boost = store.boost_for_path("proj/src/config.rs", now);
+        let sibling_boost = store.boost_for_path("proj/src/other.rs", now);
+        let unrelated = store.boost_for_path("elsewhere/x.rs", now);
+
+        assert!(file_boost > sibling_boost);
+        assert!(sibling_boost > 1.0);
+        assert_eq!(unrelated, 1.0);
+        assert!(file_boost <= (1.0 + MAX_FILE_BOOST) * (1.0 + MAX_PREFIX_BOOST));
+    }
+
+    #[test]
+    fn test_boost_decays_and_skips_never_penalize() {
+        let store = FeedbackStore::in_memory(1.0);
+        let t0 = 1_000_000;
+        store.record(click("a/b.rs", 0, t0)).unwrap();
+        let fresh = store.boost_for_path("a/b.rs", t0);
+        let later = store.boost_for_path("a/b.rs", t0 + 10 * 86_400);
+        assert!(later < fresh);
+        assert!(later >= 1.0);
+
+        let mut skip = click("a/c.rs", 0, t0);
+        skip.clicked = false;
+        store.record(skip).unwrap();
+        assert!(store.boost_for_path("a/c.rs", t0) >= 1.0);
+    }
+
+    #[test]
+    fn test_events_persist_across_reopen() {
+        let temp_dir = TempDir::new().unwrap();
+        let log = temp_dir.path().join("feedback.jsonl");
+        let now = super::super::recency::now_secs();
+
+        let store = FeedbackStore::open(&log, 14.0).unwrap();
+        store.record(click("proj/lib.rs", 1, now)).unwrap();
+        let boost = store.boost_for_path("proj/lib.rs", now);
+        drop(store);
+
+        let reopened = FeedbackStore::open(&log, 14.0).unwrap();
+        assert_eq!(reopened.event_count(), 1);
+        assert!((reopened.boost_for_path("proj/lib.rs", now) - boost).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_event_refreshes_clicked_document() {
+        let store = FeedbackStore::in_memory(14.0);
+        let now = 1_000_000;
+        let documents = vec![
+            ("proj/src/config.rs".to_string(), 0),
+            ("proj/src/other.rs".to_string(), 1),
+            ("elsewhere/x.rs".to_string(), 2),
+        ];
+        store.set_documents(documents.clone(), now);
+        assert!(store.doc_boosts().is_empty());
+        assert_eq!(store.document_id("proj/src/other.rs"), Some(1));
+        // Documents indexed after the last refresh are unknown
+        assert_eq!(store.document_id("proj/new.rs"), None);
+
+        store.record(click("proj/src/config.rs", 3, now)).unwrap();
+        let snapshot = store.doc_boosts();
+        store.refresh_document("proj/src/config.rs", 0, now);
+        let boosts = store.doc_boosts();
+        assert_eq!(
+            boosts.get(&0).copied(),
+            Some(store.boost_for_path("proj/src/config.rs", now))
+        );
+        // Siblings pick up the directory boost at the next full refresh
+        assert!(!boosts.contains_key(&1));
+        assert!(snapshot.is_empty());
+
+        store.set_documents(documents, now);
+        assert!(store.doc_boosts().contains_key(&1));
+        assert!(!store.doc_boosts().contains_key(&2));
+    }
+
+    #[test]
+    fn test_directory_prefixes() {
+        let prefixes: Vec<&str> = directory_prefixes("a/b/c.rs").collect();
+        assert_eq!(prefixes, vec!["a", "a/b"]);
+        assert_eq!(directory_prefixes("c.rs").count(), 0);
+    }
+}
@@ -0,0 +1,214 @@
+//! Fuzzy symbol-name matching.
+//!
+//! Scores a query as a subsequence of a symbol name, the way IDE "go to
+//! symbol" pickers do: characters at word starts (after `_`, `-`, `.` or at a
+//! camelCase boundary) and consecutive runs score higher, gaps cost a little.
+//! `usrsvc` and `UserSvc` both find `UserService`, `parse_cfg` finds
+//! `parseConfig`. Separators in the query are ignored.
+
+/// Minimum normalized score for a name to count as a match
+pub const MIN_FUZZY_SCORE: f32 = 0.35;
+
+const MATCH: f32 = 1.0;
+const WORD_START_BONUS: f32 = 2.0;
+const CONSECUTIVE_BONUS: f32 = 1.0;
+const GAP_PENALTY: f32 = 0.2;
+const MAX_GAP_PENALTY: f32 = 1.5;
+
+/// A lowercased query prepared for scoring many names
+#[derive(Debug, Clone)]
+pub struct FuzzyPattern {
+    chars: Vec<char>,
+    mask: u64,
+}
+
+impl FuzzyPattern {
+    /// Prepare a query; `None` when it has no alphanumeric characters
+    pub fn new(query: &str) -> Option<Self> {
+        let chars: Vec<char> = query
+            .chars()
+            .filter(|c| c.is_alphanumeric())
+            .flat_map(char::to_lowercase)
+            .collect();
+        if chars.is_empty() {
+            return None;
+        }
+        let mask = char_mask(chars.iter().copied());
+        Some(Self { chars, mask })
+    }
+
+    /// Character-set bitmask; a name can only match if its mask is a superset
+    pub fn mask(&self) -> u64 {
+        self.mask
+    }
+
+    /// Score `name` in `[MIN_FUZZY_SCORE, 1]`, or `None` if it does not match.
+    ///
+    /// The query must be a subsequence of the name and its first character
+    /// must land on a word start. An exact (case-insensitive) match scores 1.
+    pub fn score(&self, name: &str) -> Option<f32> {
+        let name_chars: Vec<char> = name.chars().collect();
+        let m = self.chars.len();
+        let n = name_chars.len();
+        if m > n {
+            return None;
+        }
+        let lower: Vec<char> = name_chars
+            .iter()
+            .map(|c| c.to_lowercase().next().unwrap_or(*c))
+            .collect();
+        let word_start: Vec<bool> = (0..n).map(|j| is_word_start(&name_chars, j)).collect();
+
+        // best[j]: best score with the current query char matched at name[j]
+        let mut best = vec![f32::NEG_INFINITY; n];
+        for (i, &qc) in self.chars.iter().enumerate() {
+            let mut next = vec![f32::NEG_INFINITY; n];
+            // Best previous match at least one character before j - 1, as
+            // (score, position); its gap penalty is applied when used
+            let mut carry: Option<(f32, usize)> = None;
+            for j in 0..n {
+                if i > 0 && j >= 2 && best[j - 2] > f32::NEG_INFINITY {
+                    let candidate = best[j - 2];
+                    let replace = match carry {
+                        Some((score, at)) => candidate - gap_cost(1) > score - gap_cost(j - 1 - at),
+                        None => true,
+                    };
+                    if replace {
+                        carry = Some((candidate, j - 2));
+                    }
+                }
+                if lower[j] != qc {
+                    continue;
+                }
+                let bonus = if word_start[j] { WORD_START_BONUS } else { 0.0 };
+                if i == 0 {
+                    if word_start[j] {
+                        next[j] = MATCH + bonus;
+                    }
+                    continue;
+                }
+                let consecutive = if j > 0 && best[j - 1] > f32::NEG_INFINITY {
+                    best[j - 1] + CONSECUTIVE_BONUS
+                } else {
+                    f32::NEG_INFINITY
+                };
+                let gapped = match carry {
+                    Some((score, at)) => score - gap_cost(j - 1 - at),
+                    None => f32::NEG_INFINITY,
+                };
+                let prev = consecutive.max(gapped);
+                if prev > f32::NEG_INFINITY {
+                    next[j] = prev + MATCH + bonus;
+                }
+            }
+            best = next;
+        }
+
+        let raw = best.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+        if raw == f32::NEG_INFINITY {
+            return None;
+        }
+        // A query equal to a single word of the name scores the maximum
+        let perfect = m as f32 * (MATCH + CONSECUTIVE_BONUS) + WORD_START_BONUS - CONSECUTIVE_BONUS;
+        let coverage = m as f32 / n as f32;
+        let score = (raw / perfect).min(1.0) * (0.8 + 0.2 * coverage);
+        (score >= MIN_FUZZY_SCORE).then_some(score)
+    }
+}
+
+fn gap_cost(gap: usize) -> f32 {
+    (gap as f32 * GAP_PENALTY).min(MAX_GAP_PENALTY)
+}
+
+/// Whether `chars[j]` begins a word: the first character, one following a
+/// separator, a lower-to-upper camelCase step, or the last capital of an
+/// acronym followed by lowercase (`S` in `HTTPServer`)
+fn is_word_start(chars: &[char], j: usize) -> bool {
+    let c = chars[j];
+    if !c.is_alphanumeric() {
+        return false;
+    }
+    let Some(&prev) = j.checked_sub(1).and_then(|p| chars.get(p)) else {
+        return true;
+    };
+    if !prev.is_alphanumeric() {
+        return true;
+    }
+    if c.is_uppercase() {
+        if prev.is_lowercase() || prev.is_ascii_digit() {
+            return true;
+        }
+        if prev.is_uppercase() {
+            return chars.get(j + 1).is_some_and(|next| next.is_lowercase());
+        }
+    }
+    c.is_ascii_digit() && !prev.is_ascii_digit()
+}
+
+/// Bitmask of the characters in a name: one bit per ASCII letter and digit,
+/// with everything else sharing the top bit
+pub fn char_mask(chars: impl Iterator<Item = char>) -> u64 {
+    chars.fold(0u64, |mask, c| {
+        let c = c.to_ascii_lowercase();
+        let bit = match c {
+            'a'..='z' => c as u32 - 'a' as u32,
+            '0'..='9' => 26 + c as u32 - '0' as u32,
+            _ if c.is_alphanumeric() => 63,
+            _ => return mask,
+        };
+        mask | (1 << bit)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn score(query: &str, name: &str) -> Option<f32> {
+        FuzzyPattern::new(query).unwrap().score(name)
+    }
+
+    #[test]
+    fn test_abbreviations_across_word_boundaries() {
+        assert!(score("usrsvc", "UserService").is_some());
+        assert!(score("UserSvc", "UserService").is_some());
+        assert!(score("parse_cfg", "parseConfig").is_some());
+        assert!(score("parseCfg", "parse_config").is_some());
+        assert!(score("hs", "HTTPServer").is_some());
+        assert!(score("cfg", "Config").is_some());
+    }
+
+    #[test]
+    fn test_rejects_non_matches() {
+        assert_eq!(score("usrsvcx", "UserService"), None);
+        // The first character must start a word
+        assert!(score("ser", "UserService").is_some());
+        assert_eq!(score("rvice", "UserService"), None);
+        assert_eq!(score("longer_than_name", "short"), None);
+        assert!(FuzzyPattern::new("::").is_none());
+    }
+
+    #[test]
+    fn test_ranking() {
+        let exact = score("config", "config").unwrap();
+        let prefix = score("config", "config_loader").unwrap();
+        let word = score("config", "load_config").unwrap();
+        let scattered = score("config", "CountOfNewFigures").unwrap_or(0.0);
+        assert!((exact - 1.0).abs() < 1e-6);
+        assert!(exact > prefix);
+        assert!(prefix > scattered);
+        assert!(word > scattered);
+
+        let abbrev = score("usrsvc", "UserService").unwrap();
+        let longer = score("usrsvc", "UserServiceImpl").unwrap();
+        assert!(abbrev > longer);
+    }
+
+    #[test]
+    fn test_char_mask() {
+        let pattern = FuzzyPattern::new("ab1").unwrap();
+        let name = char_mask("a_b_c1".chars());
+        assert_eq!(pattern.mask() & name, pattern.mask());
+        assert_ne!(pattern.mask() & char_mask("ac".chars()), pattern.mask());
+    }
+}
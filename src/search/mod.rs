@@ -1,19 +1,39 @@
 pub mod background_indexer;
+pub mod dead_code;
+pub mod duplicates;
 pub mod engine;
+pub mod explain;
+pub mod facets;
+pub mod feedback;
 pub mod file_discovery;
+pub mod fuzzy;
 pub mod path_filter;
+pub mod recency;
+pub mod references;
 pub mod regex_search;
+pub mod symbol_names;
+pub mod symbol_query;
 pub mod watcher;
 
 pub use background_indexer::{
     run as run_background_indexer, save_on_watcher_update, BackgroundIndexerConfig,
 };
+pub use dead_code::{DeadCodeDirectory, DeadCodeReport, UnusedSymbol};
+pub use duplicates::{DuplicateCluster, DuplicateStats};
 pub use engine::{
-    create_progress_broadcaster, IndexingProgress, IndexingStatus, LoadIndexResult, LoadingPhase,
-    PartialIndexedFile, PreIndexedFile, ProgressBroadcaster, RankMode, SearchEngine, SearchMatch,
-    SearchRankingInfo, SearchStats, SharedIndexingProgress,
+    create_progress_broadcaster, DefinitionCandidate, DefinitionLookup, DefinitionScope,
+    IndexingProgress, IndexingStatus, LoadIndexResult, LoadingPhase, PartialIndexedFile,
+    PreIndexedFile, ProgressBroadcaster, RankMode, SearchEngine, SearchMatch, SearchRankingInfo,
+    SearchScope, SearchStats, SharedIndexingProgress,
 };
+pub use explain::ScoreExplanation;
+pub use facets::{CountMode, FacetCount, MatchCounts, QueryKind, SearchFacets};
+pub use feedback::{FeedbackEvent, FeedbackStore};
 pub use file_discovery::{discover_files, FileDiscoveryConfig, FileDiscoveryIterator};
+pub use fuzzy::FuzzyPattern;
 pub use path_filter::PathFilter;
+pub use references::{PackedReference, ReferenceIndex};
 pub use regex_search::RegexAnalysis;
+pub use symbol_names::{FuzzyNameMatches, SymbolNameIndex};
+pub use symbol_query::{SymbolField, SymbolQuery};
 pub use watcher::{FileChange, FileWatcher, WatcherConfig};
@@ -0,0 +1,190 @@
+//! Recency signal for ranking.
+//!
+//! Files that changed recently are often the ones a developer is looking for.
+//! The signal is a "freshness" value in `(0, 1]` that halves every
+//! `half_life_days`; the ranking boost derived from it is
+//! `1 + weight × freshness`, so a weight of 0 leaves scores unchanged.
+
+use rustc_hash::{FxHashMap, FxHashSet};
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+const SECS_PER_DAY: f32 = 86_400.0;
+
+/// Freshness of a file last changed at `changed_secs`, as seen at `now_secs`.
+///
+/// Returns 1.0 for files changed now (or in the future, e.g. clock skew) and
+/// decays exponentially with the given half-life. A zero timestamp means
+/// "unknown" and yields 0.0 so that such files receive no boost.
+pub fn freshness(changed_secs: u64, now_secs: u64, half_life_days: f32) -> f32 {
+    if changed_secs == 0 || half_life_days <= 0.0 {
+        return 0.0;
+    }
+    let age_days = now_secs.saturating_sub(changed_secs) as f32 / SECS_PER_DAY;
+    0.5f32.powf(age_days / half_life_days)
+}
+
+/// Current time in seconds since the UNIX epoch
+pub fn now_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Upper bound on the commits read per repository, so that very long
+/// histories cannot make a refresh unbounded. Files last changed before the
+/// oldest commit read fall back to their mtime.
+const MAX_COMMITS: usize = 10_000;
+
+/// Normalize a path the same way on both sides of a git lookup: symlinks and
+/// relative components resolved, falling back to the path as given.
+pub fn normalize_path(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// Indexed files whose recency comes from git history.
+///
+/// Collected under the engine lock by `SearchEngine::git_recency` and resolved
+/// with [`GitRecency::resolve`] outside it, since reading history can take a
+/// while on large repositories.
+#[derive(Debug, Clone, Default)]
+pub struct GitRecency {
+    pub roots: Vec<PathBuf>,
+    /// Document ID and path as indexed
+    pub files: Vec<(u32, PathBuf)>,
+}
+
+impl GitRecency {
+    /// Last commit time of every file git knows, as
+    /// `(document ID, path as indexed, commit time)`
+    pub fn resolve(&self) -> Vec<(u32, PathBuf, u64)> {
+        let normalized: FxHashMap<PathBuf, usize> = self
+            .files
+            .iter()
+            .enumerate()
+            .map(|(idx, (_, path))| (normalize_path(path), idx))
+            .collect();
+        let wanted: FxHashSet<PathBuf> = normalized.keys().cloned().collect();
+
+        let mut times: FxHashMap<PathBuf, u64> = FxHashMap::default();
+        for root in &self.roots {
+            times.extend(git_commit_times(root, &wanted));
+        }
+        times
+            .into_iter()
+            .filter_map(|(path, time)| {
+                let (file_id, indexed) = &self.files[*normalized.get(&path)?];
+                Some((*file_id, indexed.clone(), time))
+            })
+            .collect()
+    }
+}
+
+/// Last commit time of the `wanted` files inside the git repository containing `root`.
+///
+/// Streams `git log` (newest first, at most [`MAX_COMMITS`] commits) and
+/// records the first timestamp seen for each wanted path, stopping as soon as
+/// every wanted path in the repository has one. Keys are normalized with
+/// [`normalize_path`], so `wanted` must be too. Returns an empty map when
+/// `root` is not inside a git repository or git is unavailable.
+pub fn git_commit_times(root: &Path, wanted: &FxHashSet<PathBuf>) -> FxHashMap<PathBuf, u64> {
+    let mut times = FxHashMap::default();
+
+    let Some(toplevel) = git_output(root, &["rev-parse", "--show-toplevel"]) else {
+        return times;
+    };
+    let toplevel = normalize_path(Path::new(toplevel.trim()));
+    let target = wanted.iter().filter(|p| p.starts_with(&toplevel)).count();
+    if target == 0 {
+        return times;
+    }
+
+    let max_count = format!("--max-count={MAX_COMMITS}");
+    let Ok(mut child) = Command::new("git")
+        .arg("-C")
+        .arg(root)
+        .args(["-c", "core.quotepath=off", "log", &max_count])
+        .args(["--format=%x00%ct", "--name-only", "--no-renames", "--", "."])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+    else {
+        return times;
+    };
+
+    if let Some(stdout) = child.stdout.take() {
+        let mut current: u64 = 0;
+        for line in BufReader::new(stdout).split(b'\n') {
+            let Ok(line) = line else { break };
+            if let Some(ts) = line.strip_prefix(b"\0") {
+                current = String::from_utf8_lossy(ts).trim().parse().unwrap_or(0);
+            } else if !line.is_empty() && current > 0 {
+                let path = toplevel.join(String::from_utf8_lossy(&line).as_ref());
+                if wanted.contains(&path) {
+                    times.entry(path).or_insert(current);
+                    if times.len() == target {
+                        break;
+                    }
+                }
+            }
+        }
+    }
+    // Stop git early when every wanted file was found
+    let _ = child.kill();
+    let _ = child.wait();
+
+    tracing::debug!(
+        root = %root.display(),
+        files = times.len(),
+        wanted = target,
+        "Read last commit times from git history"
+    );
+    times
+}
+
+fn git_output(dir: &Path, args: &[&str]) -> Option<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    String::from_utf8(output.stdout).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_freshness_halves_every_half_life() {
+        let now = 1_000 * SECS_PER_DAY as u64;
+        assert_eq!(freshness(now, now, 30.0), 1.0);
+        let month_ago = now - 30 * SECS_PER_DAY as u64;
+        assert!((freshness(month_ago, now, 30.0) - 0.5).abs() < 1e-4);
+        let two_months_ago = now - 60 * SECS_PER_DAY as u64;
+        assert!((freshness(two_months_ago, now, 30.0) - 0.25).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_freshness_unknown_or_future() {
+        assert_eq!(freshness(0, 1_000, 30.0), 0.0);
+        assert_eq!(freshness(2_000, 1_000, 30.0), 1.0);
+        assert_eq!(freshness(500, 1_000, 0.0), 0.0);
+    }
+
+    #[test]
+    fn test_git_commit_times_outside_repo_is_empty() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        // A fresh temp dir is not a git repository (unless TMPDIR lives in one,
+        // in which case the file below is untracked and still absent).
+        let wanted = FxHashSet::from_iter([temp_dir.path().join("nothing.rs")]);
+        let times = git_commit_times(temp_dir.path(), &wanted);
+        assert!(!times.contains_key(&temp_dir.path().join("nothing.rs")));
+    }
+}
@@ -0,0 +1,208 @@
+//! Identifier reference index (find-usages).
+//!
+//! Symbol extraction records where names are *defined*; this index records
+//! where they are *used*. Reference names are interned once, each file keeps
+//! a compact list of `(name ID, line, column)` triples, and a posting bitmap
+//! per name gives the files that mention it without scanning every file.
+
+use crate::symbols::SymbolReference;
+use roaring::RoaringBitmap;
+use rustc_hash::FxHashMap;
+use serde::{Deserialize, Serialize};
+
+/// A reference with its name interned into the index's name table
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PackedReference {
+    /// Index into the name table
+    pub name: u32,
+    /// Line number (0-based)
+    pub line: u32,
+    /// Column (0-based, in bytes)
+    pub column: u32,
+}
+
+#[derive(Debug, Default)]
+pub struct ReferenceIndex {
+    name_ids: FxHashMap<Box<str>, u32>,
+    names: Vec<Box<str>>,
+    /// Files referencing each name (indexed by name ID)
+    postings: Vec<RoaringBitmap>,
+    /// References per file ID, sorted by (name, line)
+    files: Vec<Vec<PackedReference>>,
+}
+
+impl ReferenceIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn intern(&mut self, name: &str) -> u32 {
+        if let Some(&id) = self.name_ids.get(name) {
+            return id;
+        }
+        let id = self.names.len() as u32;
+        self.names.push(name.into());
+        self.name_ids.insert(name.into(), id);
+        self.postings.push(RoaringBitmap::new());
+        id
+    }
+
+    /// Replace the references recorded for a file
+    pub fn set_file(&mut self, file_id: u32, references: &[SymbolReference]) {
+        let packed = references
+            .iter()
+            .map(|r| PackedReference {
+                name: self.intern(&r.name),
+                line: r.line as u32,
+                column: r.column as u32,
+            })
+            .collect();
+        self.set_file_packed(file_id, packed);
+    }
+
+    fn set_file_packed(&mut self, file_id: u32, mut packed: Vec<PackedReference>) {
+        let idx = file_id as usize;
+        if self.files.len() <= idx {
+            self.files.resize_with(idx + 1, Vec::new);
+        }
+        for old in std::mem::take(&mut self.files[idx]) {
+            self.postings[old.name as usize].remove(file_id);
+        }
+        for r in &packed {
+            self.postings[r.name as usize].insert(file_id);
+        }
+        packed.sort_unstable_by_key(|r| (r.name, r.line));
+        self.files[idx] = packed;
+    }
+
+    /// Files containing at least one reference to `name` (exact, case-sensitive)
+    pub fn files_referencing(&self, name: &str) -> RoaringBitmap {
+        self.name_ids
+            .get(name)
+            .map(|&id| self.postings[id as usize].clone())
+            .unwrap_or_default()
+    }
+
+    /// `(line, column)` of each reference to `name` in a file, in line order
+    pub fn references_in(&self, file_id: u32, name: &str) -> Vec<(u32, u32)> {
+        let (Some(&id), Some(refs)) = (self.name_ids.get(name), self.files.get(file_id as usize))
+        else {
+            return Vec::new();
+        };
+        let start = refs.partition_point(|r| r.name < id);
+        refs[start..]
+            .iter()
+            .take_while(|r| r.name == id)
+            .map(|r| (r.line, r.column))
+            .collect()
+    }
+
+    /// Total number of references stored
+    pub fn len(&self) -> usize {
+        self.files.iter().map(Vec::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.files.iter().all(Vec::is_empty)
+    }
+
+    /// Interned name table (for persistence)
+    pub fn names(&self) -> Vec<String> {
+        self.names.iter().map(|n| n.to_string()).collect()
+    }
+
+    /// Packed references of a file (for persistence)
+    pub fn file_references(&self, file_id: u32) -> &[PackedReference] {
+        self.files
+            .get(file_id as usize)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Restore a file's references from persisted data, re-interning names
+    /// through `persisted_names` (the name table saved alongside them)
+    pub fn restore_file(
+        &mut self,
+        file_id: u32,
+        references: &[PackedReference],
+        persisted_names: &[String],
+        name_map: &mut FxHashMap<u32, u32>,
+    ) {
+        let packed = references
+            .iter()
+            .filter_map(|r| {
+                let name = match name_map.get(&r.name) {
+                    Some(&id) => id,
+                    None => {
+                        let id = self.intern(persisted_names.get(r.name as usize)?);
+                        name_map.insert(r.name, id);
+                        id
+                    }
+                };
+                Some(PackedReference { name, ..*r })
+            })
+            .collect();
+        self.set_file_packed(file_id, packed);
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    pub fn shrink_to_fit(&mut self) {
+        for refs in &mut self.files {
+            refs.shrink_to_fit();
+        }
+        self.files.shrink_to_fit();
+        self.names.shrink_to_fit();
+        self.postings.shrink_to_fit();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn r(name: &str, line: usize, column: usize) -> SymbolReference {
+        SymbolReference {
+            name: name.to_string(),
+            line,
+            column,
+        }
+    }
+
+    #[test]
+    fn test_set_and_query_references() {
+        let mut index = ReferenceIndex::new();
+        index.set_file(0, &[r("parse", 3, 4), r("Config", 1, 0), r("parse", 1, 9)]);
+        index.set_file(2, &[r("parse", 7, 0)]);
+
+        let files: Vec<u32> = index.files_referencing("parse").iter().collect();
+        assert_eq!(files, vec![0, 2]);
+        assert_eq!(index.references_in(0, "parse"), vec![(1, 9), (3, 4)]);
+        assert!(index.files_referencing("Parse").is_empty());
+        assert_eq!(index.len(), 4);
+
+        // Replacing a file's references updates the postings
+        index.set_file(0, &[r("Config", 2, 0)]);
+        let files: Vec<u32> = index.files_referencing("parse").iter().collect();
+        assert_eq!(files, vec![2]);
+    }
+
+    #[test]
+    fn test_restore_remaps_names() {
+        let mut original = ReferenceIndex::new();
+        original.set_file(0, &[r("alpha", 0, 0), r("beta", 1, 0)]);
+        let names = original.names();
+        let saved = original.file_references(0).to_vec();
+
+        let mut restored = ReferenceIndex::new();
+        restored.set_file(5, &[r("beta", 9, 9)]);
+        let mut name_map = FxHashMap::default();
+        restored.restore_file(0, &saved, &names, &mut name_map);
+
+        assert_eq!(restored.references_in(0, "alpha"), vec![(0, 0)]);
+        let beta: Vec<u32> = restored.files_referencing("beta").iter().collect();
+        assert_eq!(beta, vec![0, 5]);
+    }
+}
@@ -0,0 +1,180 @@
+//! Symbol name index for fuzzy symbol search.
+//!
+//! Distinct symbol names are interned once with a character-set bitmask and a
+//! posting bitmap of the files that define them. A fuzzy query scores the
+//! (much smaller) name table instead of every file's symbol list, and the
+//! postings of the matching names give the candidate files.
+
+use crate::search::fuzzy::{char_mask, FuzzyPattern};
+use crate::symbols::Symbol;
+use rayon::prelude::*;
+use roaring::RoaringBitmap;
+use rustc_hash::FxHashMap;
+
+/// Upper bound on the number of distinct names a fuzzy query keeps
+pub const MAX_FUZZY_NAMES: usize = 5000;
+
+#[derive(Debug, Default)]
+pub struct SymbolNameIndex {
+    name_ids: FxHashMap<Box<str>, u32>,
+    names: Vec<Box<str>>,
+    /// Character-set bitmask per name (indexed by name ID)
+    masks: Vec<u64>,
+    /// Files defining each name (indexed by name ID)
+    postings: Vec<RoaringBitmap>,
+    /// Name ID of each symbol per file ID, parallel to the file's symbol list
+    files: Vec<Vec<u32>>,
+}
+
+/// Names matched by a fuzzy query
+#[derive(Debug, Default)]
+pub struct FuzzyNameMatches {
+    /// Score in `(0, 1]` per matching name ID
+    scores: FxHashMap<u32, f32>,
+    /// Files defining at least one matching name
+    pub files: RoaringBitmap,
+}
+
+impl FuzzyNameMatches {
+    pub fn is_empty(&self) -> bool {
+        self.scores.is_empty()
+    }
+}
+
+impl SymbolNameIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn intern(&mut self, name: &str) -> u32 {
+        if let Some(&id) = self.name_ids.get(name) {
+            return id;
+        }
+        let id = self.names.len() as u32;
+        self.names.push(name.into());
+        self.name_ids.insert(name.into(), id);
+        self.masks.push(char_mask(name.chars()));
+        self.postings.push(RoaringBitmap::new());
+        id
+    }
+
+    /// Replace the symbol names recorded for a file
+    pub fn set_file(&mut self, file_id: u32, symbols: &[Symbol]) {
+        let ids: Vec<u32> = symbols.iter().map(|s| self.intern(&s.name)).collect();
+        let idx = file_id as usize;
+        if self.files.len() <= idx {
+            self.files.resize_with(idx + 1, Vec::new);
+        }
+        for old in std::mem::take(&mut self.files[idx]) {
+            self.postings[old as usize].remove(file_id);
+        }
+        for &id in &ids {
+            self.postings[id as usize].insert(file_id);
+        }
+        self.files[idx] = ids;
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Number of distinct symbol names
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// Files with a symbol named exactly `name`
+    pub fn files_with_name(&self, name: &str) -> Option<&RoaringBitmap> {
+        let &id = self.name_ids.get(name)?;
+        self.postings.get(id as usize)
+    }
+
+    /// Score every name against the pattern, keeping the best
+    /// [`MAX_FUZZY_NAMES`] matches and the files that define them
+    pub fn fuzzy_search(&self, pattern: &FuzzyPattern) -> FuzzyNameMatches {
+        let mask = pattern.mask();
+        let mut scored: Vec<(u32, f32)> = self
+            .names
+            .par_iter()
+            .enumerate()
+            .filter(|(id, _)| self.masks[*id] & mask == mask && !self.postings[*id].is_empty())
+            .filter_map(|(id, name)| Some((id as u32, pattern.score(name)?)))
+            .collect();
+        if scored.len() > MAX_FUZZY_NAMES {
+            scored.select_nth_unstable_by(MAX_FUZZY_NAMES, |a, b| b.1.total_cmp(&a.1));
+            scored.truncate(MAX_FUZZY_NAMES);
+        }
+
+        let mut files = RoaringBitmap::new();
+        for &(id, _) in &scored {
+            files |= &self.postings[id as usize];
+        }
+        FuzzyNameMatches {
+            scores: scored.into_iter().collect(),
+            files,
+        }
+    }
+
+    /// Fuzzy score of the symbol at `symbol_idx` in a file's symbol list
+    pub fn symbol_score(
+        &self,
+        matches: &FuzzyNameMatches,
+        file_id: u32,
+        symbol_idx: usize,
+    ) -> Option<f32> {
+        let id = self.files.get(file_id as usize)?.get(symbol_idx)?;
+        matches.scores.get(id).copied()
+    }
+
+    pub fn shrink_to_fit(&mut self) {
+        self.names.shrink_to_fit();
+        self.masks.shrink_to_fit();
+        self.postings.shrink_to_fit();
+        self.files.shrink_to_fit();
+        for ids in &mut self.files {
+            ids.shrink_to_fit();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn symbols(names: &[&str]) -> Vec<Symbol> {
+        names.iter().map(|name| Symbol::file_name(name)).collect()
+    }
+
+    #[test]
+    fn test_fuzzy_search_postings() {
+        let mut index = SymbolNameIndex::new();
+        index.set_file(0, &symbols(&["UserService", "main"]));
+        index.set_file(1, &symbols(&["parseConfig", "UserService"]));
+        index.set_file(2, &symbols(&["render"]));
+        assert_eq!(index.len(), 4);
+
+        let matches = index.fuzzy_search(&FuzzyPattern::new("usrsvc").unwrap());
+        assert_eq!(matches.files.iter().collect::<Vec<_>>(), vec![0, 1]);
+        assert!(index.symbol_score(&matches, 0, 0).is_some());
+        assert!(index.symbol_score(&matches, 0, 1).is_none());
+        assert!(index.symbol_score(&matches, 1, 1).is_some());
+
+        let matches = index.fuzzy_search(&FuzzyPattern::new("parse_cfg").unwrap());
+        assert_eq!(matches.files.iter().collect::<Vec<_>>(), vec![1]);
+    }
+
+    #[test]
+    fn test_set_file_replaces_postings() {
+        let mut index = SymbolNameIndex::new();
+        index.set_file(0, &symbols(&["UserService"]));
+        index.set_file(0, &symbols(&["render"]));
+
+        let matches = index.fuzzy_search(&FuzzyPattern::new("usrsvc").unwrap());
+        assert!(matches.is_empty());
+        assert!(matches.files.is_empty());
+    }
+}
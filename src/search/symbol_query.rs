@@ -0,0 +1,175 @@
+//! Symbol search query syntax.
+//!
+//! A symbol query matches symbol names by case-insensitive substring, and may
+//! be qualified with container names (`Parser::parse`, `UserService.save`).
+//! The `sig:` and `doc:` prefixes match the signature or doc comment instead:
+//! `sig:"-> Result<Config"`, `doc:deprecated`.
+//!
+//! Matching takes the file's symbol list (for containers) and its source
+//! (signatures and docs are read from it).
+
+use crate::search::engine::contains_case_insensitive;
+use crate::symbols::{split_qualified, Symbol, SymbolType};
+
+/// Which part of a symbol a query is matched against
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolField {
+    Name,
+    Signature,
+    Doc,
+}
+
+#[derive(Debug, Clone)]
+pub struct SymbolQuery {
+    pub field: SymbolField,
+    /// Text compared with symbol lines for highlighting and scoring: the name
+    /// segment of a qualified query, otherwise the query without its prefix
+    pub text: String,
+    pub text_lower: String,
+    /// Whole lowercased name query (a plain substring match is always tried)
+    full_lower: String,
+    /// Lowercased containers of a qualified name query
+    qualifiers: Vec<String>,
+}
+
+impl SymbolQuery {
+    pub fn parse(query: &str) -> Self {
+        let query = query.trim();
+        let prefixed = |prefix: &str| {
+            query
+                .get(..prefix.len())
+                .filter(|p| p.eq_ignore_ascii_case(prefix))
+                .map(|_| &query[prefix.len()..])
+        };
+
+        let (field, rest) = if let Some(rest) = prefixed("sig:") {
+            (SymbolField::Signature, rest)
+        } else if let Some(rest) = prefixed("doc:") {
+            (SymbolField::Doc, rest)
+        } else {
+            (SymbolField::Name, query)
+        };
+
+        if field != SymbolField::Name {
+            let unquoted = rest
+                .trim()
+                .strip_prefix('"')
+                .and_then(|r| r.strip_suffix('"'))
+                .unwrap_or(rest.trim());
+            // Signatures and docs are compared with whitespace collapsed
+            let text = unquoted.split_whitespace().collect::<Vec<_>>().join(" ");
+            return Self {
+                field,
+                text_lower: text.to_lowercase(),
+                full_lower: String::new(),
+                qualifiers: Vec::new(),
+                text,
+            };
+        }
+
+        let full_lower = query.to_lowercase();
+        let (qualifiers, text) = match split_qualified(query) {
+            Some((qualifiers, name)) => (
+                qualifiers.iter().map(|q| q.to_lowercase()).collect(),
+                name.to_string(),
+            ),
+            None => (Vec::new(), query.to_string()),
+        };
+        Self {
+            field,
+            text_lower: text.to_lowercase(),
+            full_lower,
+            qualifiers,
+            text,
+        }
+    }
+
+    pub fn matches(&self, symbol: &Symbol, symbols: &[Symbol], source: &str) -> bool {
+        match self.field {
+            SymbolField::Name => {
+                contains_case_insensitive(&symbol.name, &self.full_lower)
+                    || (!self.qualifiers.is_empty()
+                        && symbol.matches_qualified(symbols, &self.qualifiers, &self.text_lower))
+            }
+            SymbolField::Signature => symbol
+                .signature(source)
+                .is_some_and(|sig| contains_case_insensitive(&sig, &self.text_lower)),
+            SymbolField::Doc => symbol
+                .doc(source)
+                .is_some_and(|doc| contains_case_insensitive(&doc, &self.text_lower)),
+        }
+    }
+
+    /// Whether the symbol's containers match the qualifiers of a qualified
+    /// name query (always true for unqualified queries)
+    pub fn matches_qualifiers(&self, symbol: &Symbol, symbols: &[Symbol]) -> bool {
+        self.qualifiers.is_empty() || symbol.matches_qualified(symbols, &self.qualifiers, "")
+    }
+
+    /// Whether the symbol's name equals the (name segment of the) query
+    pub fn is_exact_name(&self, symbol: &Symbol) -> bool {
+        self.field == SymbolField::Name
+            && symbol.symbol_type != SymbolType::FileName
+            && symbol.name.to_lowercase() == self.text_lower
+    }
+
+    /// Lowercase literal that every matching file contains, used to narrow
+    /// candidates with the trigram index (`None` when too short)
+    pub fn trigram_literal(&self) -> Option<&str> {
+        let literal = match self.field {
+            SymbolField::Name => self.text_lower.as_str(),
+            // Whitespace was collapsed and doc lines were joined, so only a
+            // single token is guaranteed to appear verbatim in the source
+            SymbolField::Signature | SymbolField::Doc => self
+                .text_lower
+                .split(' ')
+                .max_by_key(|token| token.len())
+                .unwrap_or(""),
+        };
+        (literal.len() >= 3).then_some(literal)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::symbols::SymbolExtractor;
+    use std::path::Path;
+
+    #[test]
+    fn test_parse_prefixes() {
+        let q = SymbolQuery::parse("sig:\"->  Result<Config\"");
+        assert_eq!(q.field, SymbolField::Signature);
+        assert_eq!(q.text, "-> Result<Config");
+        assert_eq!(q.trigram_literal(), Some("result<config"));
+
+        let q = SymbolQuery::parse("DOC:deprecated");
+        assert_eq!(q.field, SymbolField::Doc);
+        assert_eq!(q.text, "deprecated");
+
+        let q = SymbolQuery::parse("Parser::parse");
+        assert_eq!(q.field, SymbolField::Name);
+        assert_eq!(q.text, "parse");
+        assert_eq!(q.trigram_literal(), Some("parse"));
+    }
+
+    #[test]
+    fn test_matches_fields() {
+        let source = "/// Load the configuration file.\n/// Deprecated: use `Config::load`.\npub fn load_config(path: &Path) -> Result<Config> {\n    todo!()\n}\n\nfn x() {}\n";
+        let symbols = SymbolExtractor::new(Path::new("config.rs"))
+            .extract(source)
+            .unwrap();
+        let (load, x) = (&symbols[0], &symbols[1]);
+        let matches = |query: &str, symbol: &Symbol| {
+            SymbolQuery::parse(query).matches(symbol, &symbols, source)
+        };
+        assert!(matches("config", load));
+        assert!(matches("sig:\"-> result<config\"", load));
+        assert!(!matches("sig:-> Option", load));
+        assert!(matches("doc:deprecated", load));
+        assert!(!matches("doc:deprecated", x));
+
+        assert!(SymbolQuery::parse("LOAD_CONFIG").is_exact_name(load));
+        assert!(!SymbolQuery::parse("sig:load_config").is_exact_name(load));
+    }
+}
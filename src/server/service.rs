@@ -1,5 +1,7 @@
 use crate::config::IndexerConfig;
-use crate::search::SearchEngine;
+use crate::dependencies::packages::split_package_filters;
+use crate::search::{RankMode, SearchEngine, SearchMatch, SearchScope};
+use crate::symbols::{split_language_filters, SymbolType};
 use anyhow::Result;
 use std::path::Path;
 use std::sync::{Arc, RwLock};
@@ -16,7 +18,8 @@ pub mod search_proto {
 
 use search_proto::{
     code_search_server::{CodeSearch, CodeSearchServer},
-    IndexRequest, IndexResponse, MatchType, SearchRequest, SearchResult,
+    FindDefinitionRequest, FindReferencesRequest, IndexRequest, IndexResponse, MatchType,
+    SearchRequest, SearchResult,
 };
 
 pub struct CodeSearchService {
@@ -216,12 +219,23 @@ impl CodeSearch for CodeSearchService {
         request: Request<SearchRequest>,
     ) -> Result<Response<Self::SearchStream>, Status> {
         let req = request.into_inner();
-        let query = req.query.trim().to_string();
+        let (query, languages) = split_language_filters(req.query.trim())
+            .map_err(|e| Status::invalid_argument(e.to_string()))?;
+        let (query, packages) = split_package_filters(&query);
         let max_results = req.max_results.clamp(1, 1000) as usize;
         let include_patterns = req.include_paths.join(";");
         let exclude_patterns = req.exclude_paths.join(";");
         let is_regex = req.is_regex;
         let symbols_only = req.symbols_only;
+        let scope = SearchScope {
+            symbol_kinds: SymbolType::parse_kind_list(&req.kinds.join(","))
+                .map_err(|e| Status::invalid_argument(e.to_string()))?,
+            definition_only: req.definition_only,
+            fuzzy: req.fuzzy,
+            languages,
+            packages,
+            ..SearchScope::default()
+        };
 
         // Return empty stream immediately for empty queries, consistent with REST API.
         if query.is_empty() {
@@ -248,27 +262,51 @@ impl CodeSearch for CodeSearchService {
             let matches = if symbols_only {
                 // Search only in discovered symbols
                 engine
-                    .search_symbols(&query, &include_patterns, &exclude_patterns, max_results)
+                    .search_symbols_ranked(
+                        &query,
+                        &include_patterns,
+                        &exclude_patterns,
+                        max_results,
+                        &scope,
+                    )
                     .map_err(|e| {
                         Status::invalid_argument(format!("Invalid filter pattern: {}", e))
                     })?
+                    .0
             } else if is_regex {
                 // Use regex search with optional path filtering
                 engine
-                    .search_regex(&query, &include_patterns, &exclude_patterns, max_results)
-                    .map_err(|e| {
-                        Status::invalid_argument(format!("Invalid regex pattern: {}", e))
-                    })?
-            } else if include_patterns.is_empty() && exclude_patterns.is_empty() {
+                    .search_regex_ranked(
+                        &query,
+                        &include_patterns,
+                        &exclude_patterns,
+                        max_results,
+                        &scope,
+                    )
+                    .map_err(|e| Status::invalid_argument(format!("Invalid regex pattern: {}", e)))?
+                    .0
+            } else if include_patterns.is_empty()
+                && exclude_patterns.is_empty()
+                && scope.languages.is_empty()
+                && scope.packages.is_empty()
+            {
                 // Plain text search without filtering
                 engine.search(&query, max_results)
             } else {
-                // Plain text search with path filtering
+                // Plain text search with path, language and package filtering
                 engine
-                    .search_with_filter(&query, &include_patterns, &exclude_patterns, max_results)
+                    .search_with_filter_ranked(
+                        &query,
+                        &include_patterns,
+                        &exclude_patterns,
+                        max_results,
+                        RankMode::Auto,
+                        &scope,
+                    )
                     .map_err(|e| {
                         Status::invalid_argument(format!("Invalid filter pattern: {}", e))
                     })?
+                    .0
             };
 
             // Evict fallback file bytes cached when the OS mmap limit was exceeded.
@@ -278,35 +316,97 @@ impl CodeSearch for CodeSearchService {
         .await
         .map_err(|e| Status::internal(format!("Search task panicked: {}", e)))??;
 
-        let (tx, rx) = tokio::sync::mpsc::channel(128);
-
-        // Spawn a task to stream results
-        tokio::spawn(async move {
-            for m in matches {
-                let match_type = if m.is_symbol {
-                    MatchType::SymbolDefinition
-                } else {
-                    MatchType::Text
-                };
-
-                let result = SearchResult {
-                    file_path: m.file_path,
-                    content: m.content,
-                    line_number: m.line_number as i32,
-                    score: m.score,
-                    match_type: match_type as i32,
-                    match_start: m.match_start as i32,
-                    match_end: m.match_end as i32,
-                    content_truncated: m.content_truncated,
-                };
-
-                if tx.send(Ok(result)).await.is_err() {
-                    break;
+        Ok(Response::new(stream_matches(matches)))
+    }
+
+    type FindReferencesStream = ReceiverStream<Result<SearchResult, Status>>;
+
+    #[tracing::instrument(skip(self, request))]
+    async fn find_references(
+        &self,
+        request: Request<FindReferencesRequest>,
+    ) -> Result<Response<Self::FindReferencesStream>, Status> {
+        let req = request.into_inner();
+        let symbol = req.symbol.trim().to_string();
+        if symbol.is_empty() {
+            return Err(Status::invalid_argument("symbol must not be empty"));
+        }
+        let defining_file = Some(req.defining_file).filter(|f| !f.is_empty());
+        let max_results = req.max_results.clamp(1, 1000) as usize;
+
+        let engine_arc = std::sync::Arc::clone(&self.engine);
+        let matches = tokio::task::spawn_blocking(move || {
+            let engine = engine_arc.try_read().map_err(|e| match e {
+                std::sync::TryLockError::WouldBlock => {
+                    Status::unavailable("Index is currently being updated, please retry shortly")
                 }
-            }
-        });
+                std::sync::TryLockError::Poisoned(e) => {
+                    Status::internal(format!("Lock error: {}", e))
+                }
+            })?;
+            let matches = engine
+                .find_references(&symbol, defining_file.as_deref(), max_results)
+                .map_err(|e| Status::not_found(e.to_string()))?;
+            engine.evict_file_fallbacks();
+            Ok::<_, Status>(matches)
+        })
+        .await
+        .map_err(|e| Status::internal(format!("Search task panicked: {}", e)))??;
+
+        Ok(Response::new(stream_matches(matches)))
+    }
+
+    type FindDefinitionStream = ReceiverStream<Result<SearchResult, Status>>;
+
+    #[tracing::instrument(skip(self, request))]
+    async fn find_definition(
+        &self,
+        request: Request<FindDefinitionRequest>,
+    ) -> Result<Response<Self::FindDefinitionStream>, Status> {
+        let req = request.into_inner();
+        if req.file_path.is_empty() {
+            return Err(Status::invalid_argument("file_path must not be empty"));
+        }
+        if req.line <= 0 || req.column <= 0 {
+            return Err(Status::invalid_argument(
+                "line and column are 1-based and must be positive",
+            ));
+        }
+        let max_results = req.max_results.clamp(1, 1000) as usize;
+
+        let engine_arc = std::sync::Arc::clone(&self.engine);
+        let results = tokio::task::spawn_blocking(move || {
+            let engine = engine_arc.try_read().map_err(|e| match e {
+                std::sync::TryLockError::WouldBlock => {
+                    Status::unavailable("Index is currently being updated, please retry shortly")
+                }
+                std::sync::TryLockError::Poisoned(e) => {
+                    Status::internal(format!("Lock error: {}", e))
+                }
+            })?;
+            let lookup = engine
+                .find_definition(
+                    &req.file_path,
+                    req.line as usize,
+                    req.column as usize,
+                    max_results,
+                )
+                .map_err(|e| Status::not_found(e.to_string()))?;
+            engine.evict_file_fallbacks();
+            let results: Vec<SearchResult> = lookup
+                .candidates
+                .into_iter()
+                .map(|c| SearchResult {
+                    column: c.column as i32,
+                    ..search_result(c.result)
+                })
+                .collect();
+            Ok::<_, Status>(results)
+        })
+        .await
+        .map_err(|e| Status::internal(format!("Search task panicked: {}", e)))??;
 
-        Ok(Response::new(ReceiverStream::new(rx)))
+        Ok(Response::new(stream_results(results)))
     }
 
     #[tracing::instrument(skip(self, request))]
@@ -397,6 +497,54 @@ impl CodeSearch for CodeSearchService {
     }
 }
 
+/// Stream search matches to the client from a spawned task
+fn stream_matches(matches: Vec<SearchMatch>) -> ReceiverStream<Result<SearchResult, Status>> {
+    stream_results(matches.into_iter().map(search_result).collect())
+}
+
+fn stream_results(results: Vec<SearchResult>) -> ReceiverStream<Result<SearchResult, Status>> {
+    let (tx, rx) = tokio::sync::mpsc::channel(128);
+
+    tokio::spawn(async move {
+        for result in results {
+            if tx.send(Ok(result)).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    ReceiverStream::new(rx)
+}
+
+fn search_result(m: SearchMatch) -> SearchResult {
+    let match_type = if m.is_reference {
+        MatchType::SymbolReference
+    } else if m.is_symbol {
+        MatchType::SymbolDefinition
+    } else {
+        MatchType::Text
+    };
+
+    SearchResult {
+        file_path: m.file_path,
+        content: m.content,
+        line_number: m.line_number as i32,
+        score: m.score,
+        match_type: match_type as i32,
+        match_start: m.match_start as i32,
+        match_end: m.match_end as i32,
+        content_truncated: m.content_truncated,
+        container: m.container.unwrap_or_default(),
+        signature: m.signature.unwrap_or_default(),
+        doc: m.doc.unwrap_or_default(),
+        symbol_type: m
+            .symbol_type
+            .map(|t| format!("{:?}", t))
+            .unwrap_or_default(),
+        column: 0,
+    }
+}
+
 pub fn create_server() -> CodeSearchServer<CodeSearchService> {
     CodeSearchServer::new(CodeSearchService::new())
 }
@@ -1,8 +1,11 @@
+use super::queries::{grammar_named, queries_for, Grammar, QueryResults};
+use super::Language;
 use anyhow::Result;
+use rustc_hash::FxHashSet;
 use serde::{Deserialize, Serialize};
+use std::ops::Range;
 use std::path::Path;
 use tree_sitter::Parser;
-use tree_sitter_language::LanguageFn;
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum SymbolType {
@@ -20,6 +23,56 @@ pub enum SymbolType {
     FileName,
 }
 
+impl SymbolType {
+    /// Kind names accepted by symbol-kind filters
+    pub const KIND_NAMES: &'static [&'static str] = &[
+        "function",
+        "method",
+        "class",
+        "struct",
+        "trait",
+        "interface",
+        "enum",
+        "const",
+        "type",
+        "variable",
+    ];
+
+    /// Parse a kind name used in filters (`struct`, `fn`, `const`, ...)
+    pub fn from_kind_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "function" | "fn" | "func" => Some(SymbolType::Function),
+            "method" => Some(SymbolType::Method),
+            "class" => Some(SymbolType::Class),
+            "struct" => Some(SymbolType::Struct),
+            "trait" => Some(SymbolType::Trait),
+            "interface" => Some(SymbolType::Interface),
+            "enum" => Some(SymbolType::Enum),
+            "const" | "constant" => Some(SymbolType::Constant),
+            "type" => Some(SymbolType::Type),
+            "variable" | "var" => Some(SymbolType::Variable),
+            _ => None,
+        }
+    }
+
+    /// Parse a comma-separated list of kind names, rejecting unknown ones
+    pub fn parse_kind_list(list: &str) -> Result<Vec<Self>> {
+        list.split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .map(|name| {
+                Self::from_kind_name(name).ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "Unknown symbol kind '{}' (expected one of: {})",
+                        name,
+                        Self::KIND_NAMES.join(", ")
+                    )
+                })
+            })
+            .collect()
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Symbol {
     pub name: String,
@@ -27,8 +80,371 @@ pub struct Symbol {
     pub line: usize,
     pub column: usize,
     pub is_definition: bool,
+    /// Last line of the definition (0-based, inclusive)
+    #[serde(default)]
+    pub end_line: usize,
+    /// Byte range of the definition node in the source
+    #[serde(default)]
+    pub byte_range: Range<usize>,
+    /// Index of the innermost enclosing definition (impl, class, module,
+    /// ...) in the same file's symbol list
+    #[serde(default)]
+    pub parent: Option<u32>,
+    /// Kind of scope this definition opens for the symbols nested in it
+    #[serde(default)]
+    pub scope: Option<ScopeKind>,
+    /// Type a method is declared on from outside its definition (Go
+    /// receiver, Lua table, SQL schema)
+    #[serde(default)]
+    pub receiver: Option<String>,
+    /// End of the declaration header, the definition up to its body
+    /// (see [`Self::signature`])
+    #[serde(default)]
+    pub header_end: usize,
+    /// Byte range of the doc comment or docstring (see [`Self::doc`])
+    #[serde(default)]
+    pub doc_range: Option<Range<usize>>,
+}
+
+/// Kind of definition that encloses other symbols
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ScopeKind {
+    Impl,
+    Class,
+    Module,
+    Namespace,
+    Interface,
+    Trait,
+    Struct,
+    Enum,
+    Function,
+    /// Receiver type of a method declared outside it
+    Type,
+}
+
+impl ScopeKind {
+    /// Keyword used when displaying the container ("in impl Parser")
+    pub fn keyword(self) -> &'static str {
+        match self {
+            ScopeKind::Impl => "impl",
+            ScopeKind::Class => "class",
+            ScopeKind::Module => "module",
+            ScopeKind::Namespace => "namespace",
+            ScopeKind::Interface => "interface",
+            ScopeKind::Trait => "trait",
+            ScopeKind::Struct => "struct",
+            ScopeKind::Enum => "enum",
+            ScopeKind::Function => "fn",
+            ScopeKind::Type => "type",
+        }
+    }
+}
+
+impl Symbol {
+    /// A top-level definition spanning `node` (containers are set by the caller)
+    pub(super) fn definition(
+        name: &str,
+        symbol_type: SymbolType,
+        node: &tree_sitter::Node,
+        source: &str,
+    ) -> Self {
+        let start = node.start_position();
+        Self {
+            name: name.to_string(),
+            symbol_type,
+            line: start.row,
+            column: start.column,
+            is_definition: true,
+            end_line: node.end_position().row,
+            byte_range: node.byte_range(),
+            parent: None,
+            scope: None,
+            receiver: None,
+            header_end: header_end(node, source),
+            doc_range: doc_range(node),
+        }
+    }
+
+    /// Synthetic symbol for a file name (line 0, no source range)
+    pub fn file_name(stem: &str) -> Self {
+        Self {
+            name: stem.to_string(),
+            symbol_type: SymbolType::FileName,
+            line: 0,
+            column: 0,
+            is_definition: true,
+            end_line: 0,
+            byte_range: 0..0,
+            parent: None,
+            scope: None,
+            receiver: None,
+            header_end: 0,
+            doc_range: None,
+        }
+    }
+
+    /// Whether the symbol has no container (no enclosing definition or receiver)
+    pub fn is_top_level(&self) -> bool {
+        self.parent.is_none() && self.receiver.is_none()
+    }
+
+    /// Containers of the symbol, innermost first: its receiver, then the
+    /// enclosing definitions. `symbols` is the file's symbol list.
+    pub fn containers<'a>(
+        &'a self,
+        symbols: &'a [Symbol],
+    ) -> impl Iterator<Item = (ScopeKind, &'a str)> + 'a {
+        let receiver = self.receiver.as_deref().map(|name| (ScopeKind::Type, name));
+        // Parents come before their children, so the chain always ends
+        let mut next = self.parent;
+        let enclosing = std::iter::from_fn(move || {
+            let idx = next? as usize;
+            let parent = symbols.get(idx)?;
+            next = parent.parent.filter(|&p| (p as usize) < idx);
+            Some((parent.scope?, parent.name.as_str()))
+        });
+        receiver.into_iter().chain(enclosing)
+    }
+
+    /// Innermost container for display, e.g. `impl Parser` or `class UserService`
+    pub fn container(&self, symbols: &[Symbol]) -> Option<String> {
+        self.containers(symbols)
+            .next()
+            .map(|(kind, name)| format!("{} {}", kind.keyword(), name))
+    }
+
+    /// Whether the symbol matches a qualified query such as `Parser::parse`
+    /// or `UserService.save`, split by [`split_qualified`] and lowercased.
+    ///
+    /// `name` must occur in the symbol name; the qualifiers must equal the
+    /// innermost container names, ignoring case and generic arguments
+    /// (`impl<T> Parser<T>` matches `Parser`).
+    pub fn matches_qualified<Q: AsRef<str>>(
+        &self,
+        symbols: &[Symbol],
+        qualifiers: &[Q],
+        name: &str,
+    ) -> bool {
+        if !self.name.to_lowercase().contains(name) {
+            return false;
+        }
+        let containers: Vec<&str> = self
+            .containers(symbols)
+            .take(qualifiers.len())
+            .map(|(_, name)| name)
+            .collect();
+        containers.len() == qualifiers.len()
+            && containers
+                .iter()
+                .rev()
+                .zip(qualifiers)
+                .all(|(container, qualifier)| {
+                    let base = container.split('<').next().unwrap_or("").trim();
+                    base.eq_ignore_ascii_case(qualifier.as_ref())
+                })
+    }
+
+    /// Declaration header: the definition up to its body, whitespace
+    /// collapsed (`pub fn load(path: &Path) -> Result<Config>`). Read from
+    /// `source`, the file the symbol was extracted from.
+    pub fn signature(&self, source: &str) -> Option<String> {
+        let header = source.get(self.byte_range.start..self.header_end)?;
+        let collapsed = header.split_whitespace().collect::<Vec<_>>().join(" ");
+        // Drop the block opener (`{`, Python's `:`, Scala's `=`, Elixir's `do`)
+        // left in front of the body, or the `;` ending a declaration
+        let trimmed = collapsed.trim_end_matches(['{', ':', '=', ';']).trim_end();
+        let trimmed = trimmed.strip_suffix(" do").unwrap_or(trimmed);
+        if trimmed.is_empty() {
+            return None;
+        }
+        Some(truncate_on_char_boundary(
+            trimmed.to_string(),
+            MAX_SIGNATURE_LEN,
+        ))
+    }
+
+    /// Doc comment or docstring attached to the definition, markers
+    /// stripped. Read from `source`, the file the symbol was extracted from.
+    pub fn doc(&self, source: &str) -> Option<String> {
+        let text = source.get(self.doc_range.clone()?)?;
+        let quoted = text.trim_start_matches(|c: char| c.is_ascii_alphabetic());
+        let docstring = ["\"\"\"", "'''", "\"", "'"]
+            .iter()
+            .find_map(|q| quoted.strip_prefix(q)?.strip_suffix(q));
+        let lines: Vec<&str> = match docstring {
+            Some(inner) => inner.lines().map(str::trim).collect(),
+            None => text.lines().map(strip_comment_markers).collect(),
+        };
+        join_doc_lines(&lines)
+    }
+}
+
+/// Truncate to at most `max` bytes on a char boundary
+fn truncate_on_char_boundary(mut text: String, max: usize) -> String {
+    if text.len() > max {
+        let mut end = max;
+        while !text.is_char_boundary(end) {
+            end -= 1;
+        }
+        text.truncate(end);
+    }
+    text
+}
+
+/// End of the declaration header of a definition: the start of its body,
+/// or the end of its first line when it has no body.
+///
+/// The body is the `body` field, or for grammars without one a child named
+/// like `class_body` or `message_body`.
+fn header_end(node: &tree_sitter::Node, source: &str) -> usize {
+    let body = node.child_by_field_name("body").or_else(|| {
+        let mut cursor = node.walk();
+        let body = node
+            .named_children(&mut cursor)
+            .find(|child| child.kind().ends_with("_body"));
+        body
+    });
+    match body {
+        Some(body) => body.start_byte(),
+        None => {
+            let text = &source[node.byte_range()];
+            node.start_byte() + text.find('\n').unwrap_or(text.len())
+        }
+    }
+}
+
+/// Byte range of a definition's doc: a Python docstring, or the comments
+/// directly above it (attributes and decorators in between are skipped)
+fn doc_range(node: &tree_sitter::Node) -> Option<Range<usize>> {
+    if let Some(docstring) = python_docstring(node) {
+        return Some(docstring.byte_range());
+    }
+
+    // Comments attach to the wrapping statement (`export function`, decorated
+    // Python definitions, Go `type` blocks, C++ templates)
+    let mut anchor = match node.parent() {
+        Some(parent)
+            if matches!(
+                parent.kind(),
+                "export_statement"
+                    | "decorated_definition"
+                    | "type_declaration"
+                    | "template_declaration"
+            ) =>
+        {
+            parent
+        }
+        _ => *node,
+    };
+    // The first node of a wrapper (a SQL `statement`, Haskell `declarations`)
+    // takes the comments above the wrapper
+    while anchor.prev_sibling().is_none() {
+        match anchor.parent() {
+            Some(parent) if parent.start_byte() == anchor.start_byte() => anchor = parent,
+            _ => break,
+        }
+    }
+
+    let mut range: Option<Range<usize>> = None;
+    let mut next_row = anchor.start_position().row;
+    let mut sibling = anchor.prev_sibling();
+    while let Some(prev) = sibling {
+        match prev.kind() {
+            "comment" | "line_comment" | "block_comment" | "haddock" => {
+                // Only comments directly above (no blank line in between)
+                if prev.end_position().row + 1 < next_row {
+                    break;
+                }
+                let end = range.as_ref().map_or(prev.end_byte(), |r| r.end);
+                range = Some(prev.start_byte()..end);
+            }
+            "attribute_item" | "decorator" | "annotation" | "marker_annotation" => {}
+            _ => break,
+        }
+        next_row = prev.start_position().row;
+        sibling = prev.prev_sibling();
+    }
+    range
+}
+
+/// First statement of a Python definition body, when it is a string literal
+fn python_docstring<'tree>(node: &tree_sitter::Node<'tree>) -> Option<tree_sitter::Node<'tree>> {
+    let body = node.child_by_field_name("body")?;
+    if body.kind() != "block" {
+        return None;
+    }
+    let statement = body.named_child(0)?;
+    if statement.kind() != "expression_statement" {
+        return None;
+    }
+    let string = statement.named_child(0)?;
+    (string.kind() == "string").then_some(string)
+}
+
+/// Remove comment syntax from one line of a comment
+fn strip_comment_markers(line: &str) -> &str {
+    let mut text = line.trim();
+    for prefix in ["///", "//!", "//", "/**", "/*!", "/*", "#", "-- |", "--"] {
+        if let Some(rest) = text.strip_prefix(prefix) {
+            text = rest;
+            break;
+        }
+    }
+    text = text.strip_suffix("*/").unwrap_or(text).trim();
+    // Continuation lines of block comments (` * text`)
+    text.strip_prefix('*').unwrap_or(text).trim()
+}
+
+/// Join stripped doc lines, dropping blank lines at either end
+fn join_doc_lines(lines: &[&str]) -> Option<String> {
+    let start = lines.iter().position(|l| !l.is_empty())?;
+    let end = lines.iter().rposition(|l| !l.is_empty())?;
+    Some(truncate_on_char_boundary(
+        lines[start..=end].join("\n"),
+        MAX_DOC_LEN,
+    ))
 }
 
+/// Split a qualified symbol query into container segments and the symbol
+/// name: `Parser::parse` and `UserService.save` both qualify; a query without
+/// a separator (or with an empty segment) does not.
+pub fn split_qualified(query: &str) -> Option<(Vec<&str>, &str)> {
+    let separator = if query.contains("::") {
+        "::"
+    } else if query.contains('.') {
+        "."
+    } else {
+        return None;
+    };
+    let mut segments: Vec<&str> = query.split(separator).map(str::trim).collect();
+    if segments.iter().any(|s| s.is_empty()) {
+        return None;
+    }
+    let name = segments.pop()?;
+    Some((segments, name))
+}
+
+/// An identifier occurrence that is not the name of a definition
+/// (call site, type use, field access, ...)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SymbolReference {
+    pub name: String,
+    /// Line number (0-based)
+    pub line: usize,
+    /// Column of the identifier (0-based, in bytes)
+    pub column: usize,
+}
+
+/// Longest signature kept per symbol (bytes)
+const MAX_SIGNATURE_LEN: usize = 300;
+
+/// Longest doc comment kept per symbol (bytes)
+const MAX_DOC_LEN: usize = 1000;
+
+/// Maximum references kept per file; generated files can contain millions of
+/// identifiers and would otherwise dominate the reference index
+pub const MAX_REFERENCES_PER_FILE: usize = 20_000;
+
 /// Represents an import statement found in source code
 #[derive(Debug, Clone)]
 pub struct ImportStatement {
@@ -49,460 +465,49 @@ pub enum ImportType {
     Python,
     /// JavaScript/TypeScript: `import`, `require()`
     JavaScript,
+    /// Go: `import "module/path/pkg"`, resolved through `go.mod`
+    Go,
+    /// Java: `import a.b.C`, `import a.b.*`, `import static a.b.C.m`
+    Java,
+    /// C/C++: `#include "foo.h"`, `#include <foo.h>`
+    Include,
+    /// C#: `using A.B`, `using static A.B.C`, `using X = A.B`
+    CSharp,
+    /// Ruby: `require_relative "foo"`
+    Ruby,
+    /// PHP: `use App\Models\User`, `require __DIR__ . '/foo.php'`
+    Php,
 }
 
 pub struct SymbolExtractor {
-    language: Option<LanguageFn>,
-    extension: String,
+    grammar: Option<&'static Grammar>,
+    language: Option<Language>,
 }
 
 impl SymbolExtractor {
+    /// Extractor for a file whose language is known only from its path
+    /// (see [`Language::detect`] for content-based detection)
     pub fn new(file_path: &Path) -> Self {
-        let language = Self::language_for_file(file_path);
-        let extension = file_path
-            .extension()
-            .and_then(|s| s.to_str())
-            .unwrap_or("")
-            .to_string();
-        Self {
-            language,
-            extension,
-        }
+        Self::for_language(Language::detect(file_path, None))
     }
 
-    fn language_for_file(path: &Path) -> Option<LanguageFn> {
-        let extension = path.extension()?.to_str()?;
-
-        match extension {
-            // Core programming languages
-            "rs" => Some(tree_sitter_rust::LANGUAGE),
-            "py" | "pyi" | "pyw" => Some(tree_sitter_python::LANGUAGE),
-            "js" | "jsx" | "mjs" | "cjs" => Some(tree_sitter_javascript::LANGUAGE),
-            "ts" | "tsx" | "mts" | "cts" => Some(tree_sitter_typescript::LANGUAGE_TYPESCRIPT),
-            "go" => Some(tree_sitter_go::LANGUAGE),
-            "c" | "h" => Some(tree_sitter_c::LANGUAGE),
-            "cpp" | "cc" | "cxx" | "hpp" | "hxx" | "hh" => Some(tree_sitter_cpp::LANGUAGE),
-            "java" => Some(tree_sitter_java::LANGUAGE),
-            "cs" => Some(tree_sitter_c_sharp::LANGUAGE),
-            "rb" | "rake" | "gemspec" => Some(tree_sitter_ruby::LANGUAGE),
-            "php" => Some(tree_sitter_php::LANGUAGE_PHP),
-            "sh" | "bash" | "zsh" => Some(tree_sitter_bash::LANGUAGE),
-            // Config and markup languages
-            "json" | "jsonc" => Some(tree_sitter_json::LANGUAGE),
-            "toml" => Some(tree_sitter_toml_ng::LANGUAGE),
-            "yaml" | "yml" => Some(tree_sitter_yaml::LANGUAGE),
-            "html" | "htm" => Some(tree_sitter_html::LANGUAGE),
-            "css" | "scss" => Some(tree_sitter_css::LANGUAGE),
-            "md" | "markdown" => Some(tree_sitter_md::LANGUAGE),
-            _ => None,
+    pub fn for_language(language: Option<Language>) -> Self {
+        Self {
+            grammar: language
+                .and_then(Language::grammar_name)
+                .and_then(grammar_named),
+            language,
         }
     }
 
     pub fn extract(&self, source: &str) -> Result<Vec<Symbol>> {
-        let language = match self.language {
-            Some(lang) => lang,
-            None => return Ok(Vec::new()), // No symbols for unknown languages
-        };
-
-        let mut parser = Parser::new();
-        parser.set_language(&language.into())?;
-
-        let tree = match parser.parse(source, None) {
-            Some(tree) => tree,
-            None => return Ok(Vec::new()),
-        };
-
-        let mut symbols = Vec::new();
-        let root_node = tree.root_node();
-
-        // Extract function definitions
-        Self::extract_functions(&root_node, source, &mut symbols);
-
-        symbols.sort_by_key(|s| s.line);
+        let (symbols, _, _) = self.extract_parsed(source, false)?;
         Ok(symbols)
     }
 
-    fn extract_functions(node: &tree_sitter::Node, source: &str, symbols: &mut Vec<Symbol>) {
-        // Use iterative traversal with explicit stack to avoid stack overflow
-        // on deeply nested code
-        let mut stack = vec![*node];
-
-        while let Some(current) = stack.pop() {
-            let mut cursor = current.walk();
-
-            for child in current.children(&mut cursor) {
-                match child.kind() {
-                    // Functions: Rust, Python, JS/TS, PHP, Bash
-                    // Note: C/C++ function_definition has "declarator" not "name"
-                    "function_item" | "function_declaration" | "function_definition" => {
-                        // Try "name" first (most languages), then "declarator" (C/C++)
-                        let name_opt = child.child_by_field_name("name").or_else(|| {
-                            // C/C++: name is inside declarator -> function_declarator -> identifier
-                            child
-                                .child_by_field_name("declarator")
-                                .and_then(|d| d.child_by_field_name("declarator"))
-                                .or_else(|| child.child_by_field_name("declarator"))
-                        });
-                        if let Some(name_node) = name_opt {
-                            // For C/C++, the declarator might be a function_declarator
-                            // We need to find the actual identifier
-                            let name_text = if name_node.kind() == "function_declarator" {
-                                name_node
-                                    .child_by_field_name("declarator")
-                                    .map(|n| &source[n.byte_range()])
-                            } else {
-                                Some(&source[name_node.byte_range()])
-                            };
-                            if let Some(name) = name_text {
-                                let start = child.start_position();
-                                symbols.push(Symbol {
-                                    name: name.to_string(),
-                                    symbol_type: SymbolType::Function,
-                                    line: start.row,
-                                    column: start.column,
-                                    is_definition: true,
-                                });
-                            }
-                        }
-                    }
-                    // Methods: Go, Java, C#, Ruby, PHP
-                    "method_declaration" | "method" | "singleton_method" => {
-                        if let Some(name_node) = child.child_by_field_name("name") {
-                            let name = &source[name_node.byte_range()];
-                            let start = child.start_position();
-                            symbols.push(Symbol {
-                                name: name.to_string(),
-                                symbol_type: SymbolType::Method,
-                                line: start.row,
-                                column: start.column,
-                                is_definition: true,
-                            });
-                        }
-                    }
-                    // Constructors: Java, C#
-                    "constructor_declaration" => {
-                        if let Some(name_node) = child.child_by_field_name("name") {
-                            let name = &source[name_node.byte_range()];
-                            let start = child.start_position();
-                            symbols.push(Symbol {
-                                name: name.to_string(),
-                                symbol_type: SymbolType::Method,
-                                line: start.row,
-                                column: start.column,
-                                is_definition: true,
-                            });
-                        }
-                    }
-                    // C# property declarations
-                    "property_declaration" => {
-                        if let Some(name_node) = child.child_by_field_name("name") {
-                            let name = &source[name_node.byte_range()];
-                            let start = child.start_position();
-                            symbols.push(Symbol {
-                                name: name.to_string(),
-                                symbol_type: SymbolType::Method,
-                                line: start.row,
-                                column: start.column,
-                                is_definition: true,
-                            });
-                        }
-                    }
-                    // Classes: JS/TS, Python, Java, C#, PHP, Ruby
-                    "class_declaration" | "class_definition" | "class" => {
-                        if let Some(name_node) = child.child_by_field_name("name") {
-                            let name = &source[name_node.byte_range()];
-                            let start = child.start_position();
-                            symbols.push(Symbol {
-                                name: name.to_string(),
-                                symbol_type: SymbolType::Class,
-                                line: start.row,
-                                column: start.column,
-                                is_definition: true,
-                            });
-                        }
-                    }
-                    // Ruby modules (similar to classes)
-                    "module" => {
-                        if let Some(name_node) = child.child_by_field_name("name") {
-                            let name = &source[name_node.byte_range()];
-                            let start = child.start_position();
-                            symbols.push(Symbol {
-                                name: name.to_string(),
-                                symbol_type: SymbolType::Class,
-                                line: start.row,
-                                column: start.column,
-                                is_definition: true,
-                            });
-                        }
-                    }
-                    // Rust impl blocks use "type" field, not "name"
-                    "impl_item" => {
-                        if let Some(type_node) = child.child_by_field_name("type") {
-                            let name = &source[type_node.byte_range()];
-                            let start = child.start_position();
-                            symbols.push(Symbol {
-                                name: name.to_string(),
-                                symbol_type: SymbolType::Class,
-                                line: start.row,
-                                column: start.column,
-                                is_definition: true,
-                            });
-                        }
-                    }
-                    // Interfaces: TS, Java, C#, PHP
-                    "interface_declaration" => {
-                        if let Some(name_node) = child.child_by_field_name("name") {
-                            let name = &source[name_node.byte_range()];
-                            let start = child.start_position();
-                            symbols.push(Symbol {
-                                name: name.to_string(),
-                                symbol_type: SymbolType::Interface,
-                                line: start.row,
-                                column: start.column,
-                                is_definition: true,
-                            });
-                        }
-                    }
-                    // Type aliases: TS, Rust
-                    "type_alias_declaration" | "type_item" => {
-                        if let Some(name_node) = child.child_by_field_name("name") {
-                            let name = &source[name_node.byte_range()];
-                            let start = child.start_position();
-                            symbols.push(Symbol {
-                                name: name.to_string(),
-                                symbol_type: SymbolType::Type,
-                                line: start.row,
-                                column: start.column,
-                                is_definition: true,
-                            });
-                        }
-                    }
-                    // Enums: TS, Rust, Java, C#
-                    "enum_declaration" | "enum_item" => {
-                        if let Some(name_node) = child.child_by_field_name("name") {
-                            let name = &source[name_node.byte_range()];
-                            let start = child.start_position();
-                            symbols.push(Symbol {
-                                name: name.to_string(),
-                                symbol_type: SymbolType::Enum,
-                                line: start.row,
-                                column: start.column,
-                                is_definition: true,
-                            });
-                        }
-                    }
-                    // Records: Java, C#
-                    "record_declaration" | "record_struct_declaration" => {
-                        if let Some(name_node) = child.child_by_field_name("name") {
-                            let name = &source[name_node.byte_range()];
-                            let start = child.start_position();
-                            symbols.push(Symbol {
-                                name: name.to_string(),
-                                symbol_type: SymbolType::Class,
-                                line: start.row,
-                                column: start.column,
-                                is_definition: true,
-                            });
-                        }
-                    }
-                    // Rust traits (similar to interfaces)
-                    "trait_item" => {
-                        if let Some(name_node) = child.child_by_field_name("name") {
-                            let name = &source[name_node.byte_range()];
-                            let start = child.start_position();
-                            symbols.push(Symbol {
-                                name: name.to_string(),
-                                symbol_type: SymbolType::Trait,
-                                line: start.row,
-                                column: start.column,
-                                is_definition: true,
-                            });
-                        }
-                    }
-                    // PHP traits
-                    "trait_declaration" => {
-                        if let Some(name_node) = child.child_by_field_name("name") {
-                            let name = &source[name_node.byte_range()];
-                            let start = child.start_position();
-                            symbols.push(Symbol {
-                                name: name.to_string(),
-                                symbol_type: SymbolType::Trait,
-                                line: start.row,
-                                column: start.column,
-                                is_definition: true,
-                            });
-                        }
-                    }
-                    // Structs: Rust, C#
-                    "struct_item" | "struct_declaration" => {
-                        if let Some(name_node) = child.child_by_field_name("name") {
-                            let name = &source[name_node.byte_range()];
-                            let start = child.start_position();
-                            symbols.push(Symbol {
-                                name: name.to_string(),
-                                symbol_type: SymbolType::Struct,
-                                line: start.row,
-                                column: start.column,
-                                is_definition: true,
-                            });
-                        }
-                    }
-                    // Rust constants and statics
-                    "const_item" | "static_item" => {
-                        if let Some(name_node) = child.child_by_field_name("name") {
-                            let name = &source[name_node.byte_range()];
-                            let start = child.start_position();
-                            symbols.push(Symbol {
-                                name: name.to_string(),
-                                symbol_type: SymbolType::Constant,
-                                line: start.row,
-                                column: start.column,
-                                is_definition: true,
-                            });
-                        }
-                    }
-                    // Go: type declarations (struct, interface, type alias)
-                    "type_declaration" => {
-                        let mut type_cursor = child.walk();
-                        for type_child in child.children(&mut type_cursor) {
-                            if type_child.kind() == "type_spec" {
-                                if let Some(name_node) = type_child.child_by_field_name("name") {
-                                    let name = &source[name_node.byte_range()];
-                                    let start = type_child.start_position();
-                                    let symbol_type = if let Some(type_node) =
-                                        type_child.child_by_field_name("type")
-                                    {
-                                        match type_node.kind() {
-                                            "struct_type" => SymbolType::Struct,
-                                            "interface_type" => SymbolType::Interface,
-                                            _ => SymbolType::Type,
-                                        }
-                                    } else {
-                                        SymbolType::Type
-                                    };
-                                    symbols.push(Symbol {
-                                        name: name.to_string(),
-                                        symbol_type,
-                                        line: start.row,
-                                        column: start.column,
-                                        is_definition: true,
-                                    });
-                                }
-                            }
-                        }
-                    }
-                    // Go: const and var declarations
-                    "const_declaration" | "var_declaration" => {
-                        let mut const_cursor = child.walk();
-                        for spec in child.children(&mut const_cursor) {
-                            if spec.kind() == "const_spec" || spec.kind() == "var_spec" {
-                                if let Some(name_node) = spec.child_by_field_name("name") {
-                                    let name = &source[name_node.byte_range()];
-                                    let start = spec.start_position();
-                                    let symbol_type = if child.kind() == "const_declaration" {
-                                        SymbolType::Constant
-                                    } else {
-                                        SymbolType::Variable
-                                    };
-                                    symbols.push(Symbol {
-                                        name: name.to_string(),
-                                        symbol_type,
-                                        line: start.row,
-                                        column: start.column,
-                                        is_definition: true,
-                                    });
-                                }
-                            }
-                        }
-                    }
-                    // C/C++: struct, union declarations
-                    "struct_specifier" | "union_specifier" => {
-                        if let Some(name_node) = child.child_by_field_name("name") {
-                            let name = &source[name_node.byte_range()];
-                            let start = child.start_position();
-                            symbols.push(Symbol {
-                                name: name.to_string(),
-                                symbol_type: SymbolType::Struct,
-                                line: start.row,
-                                column: start.column,
-                                is_definition: true,
-                            });
-                        }
-                    }
-                    // C/C++: enum specifier
-                    "enum_specifier" => {
-                        if let Some(name_node) = child.child_by_field_name("name") {
-                            let name = &source[name_node.byte_range()];
-                            let start = child.start_position();
-                            symbols.push(Symbol {
-                                name: name.to_string(),
-                                symbol_type: SymbolType::Enum,
-                                line: start.row,
-                                column: start.column,
-                                is_definition: true,
-                            });
-                        }
-                    }
-                    // C++: class specifier and namespace
-                    "class_specifier" | "namespace_definition" => {
-                        if let Some(name_node) = child.child_by_field_name("name") {
-                            let name = &source[name_node.byte_range()];
-                            let start = child.start_position();
-                            symbols.push(Symbol {
-                                name: name.to_string(),
-                                symbol_type: SymbolType::Class,
-                                line: start.row,
-                                column: start.column,
-                                is_definition: true,
-                            });
-                        }
-                    }
-                    // C++: template declarations - traverse into them
-                    // C++: template declarations — just let the default stack.push(child)
-                    // below handle traversal. The template_declaration node will be pushed
-                    // to the stack, and when it becomes `current`, its children
-                    // (function_definition, class_specifier, etc.) will be matched naturally.
-                    // No special handling needed.
-                    "template_declaration" => {}
-                    _ => {}
-                }
-
-                // Add child to stack for iterative processing
-                stack.push(child);
-            }
-        }
-    }
-
     /// Extract import statements from source code
     pub fn extract_imports(&self, source: &str) -> Result<Vec<ImportStatement>> {
-        let language = match self.language {
-            Some(lang) => lang,
-            None => return Ok(Vec::new()),
-        };
-
-        let mut parser = Parser::new();
-        parser.set_language(&language.into())?;
-
-        let tree = match parser.parse(source, None) {
-            Some(tree) => tree,
-            None => return Ok(Vec::new()),
-        };
-
-        let mut imports = Vec::new();
-        let root_node = tree.root_node();
-
-        match self.extension.as_str() {
-            "rs" => Self::extract_rust_imports(&root_node, source, &mut imports),
-            // Include stub files (.pyi) and Windows-specific (.pyw) as Python
-            "py" | "pyi" | "pyw" => Self::extract_python_imports(&root_node, source, &mut imports),
-            // Include ESM/CJS variants and TypeScript module variants
-            "js" | "jsx" | "mjs" | "cjs" | "ts" | "tsx" | "mts" | "cts" => {
-                Self::extract_js_imports(&root_node, source, &mut imports)
-            }
-            _ => {}
-        }
-
-        imports.sort_by_key(|i| i.line);
+        let (_, imports, _) = self.extract_parsed(source, false)?;
         Ok(imports)
     }
 
@@ -512,216 +517,128 @@ impl SymbolExtractor {
     /// only parses the source file once, making it roughly 2× faster when both
     /// are needed.
     pub fn extract_all(&self, source: &str) -> Result<(Vec<Symbol>, Vec<ImportStatement>)> {
-        let language = match self.language {
-            Some(lang) => lang,
-            None => return Ok((Vec::new(), Vec::new())),
+        let (symbols, imports, _) = self.extract_parsed(source, false)?;
+        Ok((symbols, imports))
+    }
+
+    /// Like [`Self::extract_all`], additionally collecting identifier references
+    /// from the same parse tree (only for programming languages; config and
+    /// markup files yield no references).
+    pub fn extract_with_references(
+        &self,
+        source: &str,
+    ) -> Result<(Vec<Symbol>, Vec<ImportStatement>, Vec<SymbolReference>)> {
+        self.extract_parsed(source, true)
+    }
+
+    fn extract_parsed(
+        &self,
+        source: &str,
+        with_references: bool,
+    ) -> Result<(Vec<Symbol>, Vec<ImportStatement>, Vec<SymbolReference>)> {
+        let grammar = match self.grammar {
+            Some(grammar) => grammar,
+            None => return Ok((Vec::new(), Vec::new(), Vec::new())),
         };
+        let queries = queries_for(grammar);
+        let with_references = with_references && self.supports_references();
+        if queries.is_empty() && !with_references {
+            return Ok((Vec::new(), Vec::new(), Vec::new()));
+        }
 
         let mut parser = Parser::new();
-        parser.set_language(&language.into())?;
+        parser.set_language(&grammar.language.into())?;
 
         let tree = match parser.parse(source, None) {
             Some(tree) => tree,
-            None => return Ok((Vec::new(), Vec::new())),
+            None => return Ok((Vec::new(), Vec::new(), Vec::new())),
         };
 
         let root_node = tree.root_node();
+        let QueryResults {
+            symbols,
+            mut imports,
+            name_nodes,
+        } = queries.run(root_node, source, grammar.import_type.clone());
+        // Symbols already come out in source order, which their parent
+        // indices rely on
+        imports.sort_by_key(|i| i.line);
 
-        let mut symbols = Vec::new();
-        Self::extract_functions(&root_node, source, &mut symbols);
-        symbols.sort_by_key(|s| s.line);
-
-        let mut imports = Vec::new();
-        match self.extension.as_str() {
-            "rs" => Self::extract_rust_imports(&root_node, source, &mut imports),
-            "py" | "pyi" | "pyw" => Self::extract_python_imports(&root_node, source, &mut imports),
-            "js" | "jsx" | "mjs" | "cjs" | "ts" | "tsx" | "mts" | "cts" => {
-                Self::extract_js_imports(&root_node, source, &mut imports)
-            }
-            _ => {}
+        let mut references = Vec::new();
+        if with_references {
+            Self::extract_references(&root_node, source, &name_nodes, &mut references);
         }
-        imports.sort_by_key(|i| i.line);
 
-        Ok((symbols, imports))
+        Ok((symbols, imports, references))
     }
 
-    /// Extract Rust use statements and mod declarations
-    fn extract_rust_imports(
-        node: &tree_sitter::Node,
-        source: &str,
-        imports: &mut Vec<ImportStatement>,
-    ) {
-        // Use iterative traversal with explicit stack to avoid stack overflow
-        let mut stack = vec![*node];
-
-        while let Some(current) = stack.pop() {
-            let mut cursor = current.walk();
-
-            for child in current.children(&mut cursor) {
-                match child.kind() {
-                    "use_declaration" => {
-                        // Extract the use path
-                        if let Some(path_node) = child.child_by_field_name("argument") {
-                            let path = &source[path_node.byte_range()];
-                            imports.push(ImportStatement {
-                                path: path.to_string(),
-                                line: child.start_position().row,
-                                import_type: ImportType::Rust,
-                            });
-                        } else {
-                            // Fallback: get full text minus 'use' and ';'
-                            let text = &source[child.byte_range()];
-                            let path = text.trim_start_matches("use").trim_end_matches(';').trim();
-                            if !path.is_empty() {
-                                imports.push(ImportStatement {
-                                    path: path.to_string(),
-                                    line: child.start_position().row,
-                                    import_type: ImportType::Rust,
-                                });
-                            }
-                        }
-                    }
-                    "mod_item" => {
-                        // mod foo; declarations
-                        if let Some(name_node) = child.child_by_field_name("name") {
-                            let name = &source[name_node.byte_range()];
-                            imports.push(ImportStatement {
-                                path: name.to_string(),
-                                line: child.start_position().row,
-                                import_type: ImportType::Rust,
-                            });
-                        }
-                    }
-                    _ => {}
-                }
-
-                // Add child to stack for iterative processing
-                stack.push(child);
-            }
-        }
+    /// Whether identifier references are extracted for this file's language
+    fn supports_references(&self) -> bool {
+        self.language.is_some_and(Language::supports_references)
     }
 
-    /// Extract Python import statements
-    fn extract_python_imports(
+    /// Collect identifier leaves that are not the name of a definition.
+    ///
+    /// At most one reference per (name, line) is kept, and at most
+    /// [`MAX_REFERENCES_PER_FILE`] per file. Single-character identifiers
+    /// (loop counters, generics) are skipped.
+    fn extract_references(
         node: &tree_sitter::Node,
         source: &str,
-        imports: &mut Vec<ImportStatement>,
+        definition_names: &FxHashSet<usize>,
+        references: &mut Vec<SymbolReference>,
     ) {
-        // Use iterative traversal with explicit stack to avoid stack overflow
+        let mut seen = std::collections::HashSet::new();
         let mut stack = vec![*node];
 
         while let Some(current) = stack.pop() {
+            if references.len() >= MAX_REFERENCES_PER_FILE {
+                break;
+            }
             let mut cursor = current.walk();
-
             for child in current.children(&mut cursor) {
-                match child.kind() {
-                    "import_statement" | "import_from_statement" => {
-                        // Get the module name from the import
-                        if let Some(module_node) = child.child_by_field_name("module_name") {
-                            let module = &source[module_node.byte_range()];
-                            imports.push(ImportStatement {
-                                path: module.to_string(),
-                                line: child.start_position().row,
-                                import_type: ImportType::Python,
-                            });
-                        } else {
-                            // Fallback: extract from full text
-                            let text = &source[child.byte_range()];
-                            if let Some(path) = Self::parse_python_import_text(text) {
-                                imports.push(ImportStatement {
-                                    path,
-                                    line: child.start_position().row,
-                                    import_type: ImportType::Python,
-                                });
-                            }
-                        }
-                    }
-                    _ => {}
+                if child.child_count() > 0 {
+                    stack.push(child);
+                    continue;
+                }
+                if !Self::is_identifier_kind(child.kind()) || definition_names.contains(&child.id())
+                {
+                    continue;
+                }
+                let name = &source[child.byte_range()];
+                if name.len() < 2 {
+                    continue;
+                }
+                let start = child.start_position();
+                if seen.insert((name, start.row)) {
+                    references.push(SymbolReference {
+                        name: name.to_string(),
+                        line: start.row,
+                        column: start.column,
+                    });
                 }
-
-                // Add child to stack for iterative processing
-                stack.push(child);
             }
         }
-    }
 
-    /// Parse Python import text to extract module path
-    fn parse_python_import_text(text: &str) -> Option<String> {
-        let text = text.trim();
-        if text.starts_with("from ") {
-            // from foo.bar import baz
-            let rest = text.strip_prefix("from ")?.trim();
-            let module = rest.split_whitespace().next()?;
-            Some(module.to_string())
-        } else if text.starts_with("import ") {
-            // import foo.bar
-            let rest = text.strip_prefix("import ")?.trim();
-            let module = rest.split([',', ' ']).next()?;
-            Some(module.to_string())
-        } else {
-            None
-        }
+        references.sort_by_key(|r| (r.line, r.column));
     }
 
-    /// Extract JavaScript/TypeScript import statements
-    fn extract_js_imports(
-        node: &tree_sitter::Node,
-        source: &str,
-        imports: &mut Vec<ImportStatement>,
-    ) {
-        // Use iterative traversal with explicit stack to avoid stack overflow
-        let mut stack = vec![*node];
-
-        while let Some(current) = stack.pop() {
-            let mut cursor = current.walk();
-
-            for child in current.children(&mut cursor) {
-                match child.kind() {
-                    "import_statement" => {
-                        // import { foo } from './bar'
-                        if let Some(source_node) = child.child_by_field_name("source") {
-                            let path = &source[source_node.byte_range()];
-                            // Remove quotes
-                            let path = path.trim_matches(|c| c == '"' || c == '\'');
-                            imports.push(ImportStatement {
-                                path: path.to_string(),
-                                line: child.start_position().row,
-                                import_type: ImportType::JavaScript,
-                            });
-                        }
-                    }
-                    "call_expression" => {
-                        // require('./foo')
-                        if let Some(func_node) = child.child_by_field_name("function") {
-                            let func_name = &source[func_node.byte_range()];
-                            if func_name == "require" {
-                                if let Some(args_node) = child.child_by_field_name("arguments") {
-                                    let args_text = &source[args_node.byte_range()];
-                                    let path = args_text
-                                        .trim_matches(|c| c == '(' || c == ')')
-                                        .trim_matches(|c| c == '"' || c == '\'');
-                                    if !path.is_empty() {
-                                        imports.push(ImportStatement {
-                                            path: path.to_string(),
-                                            line: child.start_position().row,
-                                            import_type: ImportType::JavaScript,
-                                        });
-                                    }
-                                }
-                            }
-                        }
-                    }
-                    _ => {}
-                }
-
-                // Add child to stack for iterative processing
-                stack.push(child);
-            }
-        }
+    fn is_identifier_kind(kind: &str) -> bool {
+        matches!(
+            kind,
+            "identifier"
+                | "type_identifier"
+                | "field_identifier"
+                | "property_identifier"
+                | "shorthand_property_identifier"
+                | "namespace_identifier"
+                | "constant"
+                | "name"
+        )
     }
 
     pub fn is_supported(&self) -> bool {
-        self.language.is_some()
+        self.grammar.is_some()
     }
 }
 
@@ -748,6 +665,206 @@ fn another_function() {
         assert!(symbols.iter().any(|s| s.name == "another_function"));
     }
 
+    #[test]
+    fn test_symbol_parents_and_ranges() {
+        let source = r#"
+mod parsing {
+    pub struct Parser;
+
+    impl<'a> Parser<'a> {
+        pub fn parse(&self) -> bool {
+            true
+        }
+    }
+}
+"#;
+        let extractor = SymbolExtractor::new(Path::new("test.rs"));
+        let symbols = extractor.extract(source).unwrap();
+
+        let parse = symbols.iter().find(|s| s.name == "parse").unwrap();
+        // `mod` is not extracted as a symbol, so the impl is the outermost container
+        let chain: Vec<_> = parse.containers(&symbols).collect();
+        assert_eq!(chain, vec![(ScopeKind::Impl, "Parser<'a>")]);
+        assert_eq!(
+            parse.container(&symbols).as_deref(),
+            Some("impl Parser<'a>")
+        );
+        assert_eq!((parse.line, parse.end_line), (5, 7));
+        assert!(source[parse.byte_range.clone()].starts_with("pub fn parse"));
+        assert!(source[parse.byte_range.clone()].ends_with('}'));
+
+        let (qualifiers, name) = split_qualified("parser::parse").unwrap();
+        assert!(parse.matches_qualified(&symbols, &qualifiers, name));
+        let (qualifiers, name) = split_qualified("lexer::parse").unwrap();
+        assert!(!parse.matches_qualified(&symbols, &qualifiers, name));
+        assert!(split_qualified("parse").is_none());
+        assert!(split_qualified("parser::").is_none());
+
+        let parser = symbols.iter().find(|s| s.name == "Parser").unwrap();
+        assert!(parser.is_top_level());
+    }
+
+    #[test]
+    fn test_nested_class_parents() {
+        let source = r#"
+class UserService:
+    class Cache:
+        def get(self):
+            pass
+
+    def save(self):
+        pass
+"#;
+        let extractor = SymbolExtractor::new(Path::new("test.py"));
+        let symbols = extractor.extract(source).unwrap();
+
+        let get = symbols.iter().find(|s| s.name == "get").unwrap();
+        let chain: Vec<&str> = get.containers(&symbols).map(|(_, name)| name).collect();
+        assert_eq!(chain, vec!["Cache", "UserService"]);
+
+        let save = symbols.iter().find(|s| s.name == "save").unwrap();
+        assert_eq!(
+            save.container(&symbols).as_deref(),
+            Some("class UserService")
+        );
+        let (qualifiers, name) = split_qualified("userservice.save").unwrap();
+        assert!(save.matches_qualified(&symbols, &qualifiers, name));
+        let (qualifiers, name) = split_qualified("userservice.cache.get").unwrap();
+        assert!(get.matches_qualified(&symbols, &qualifiers, name));
+        assert!(!save.matches_qualified(&symbols, &qualifiers, name));
+    }
+
+    #[test]
+    fn test_go_method_receiver_is_container() {
+        let source = r#"
+package parser
+
+type Parser struct{}
+
+func (p *Parser) Parse() error {
+    return nil
+}
+"#;
+        let extractor = SymbolExtractor::new(Path::new("test.go"));
+        let symbols = extractor.extract(source).unwrap();
+        let parse = symbols.iter().find(|s| s.name == "Parse").unwrap();
+        assert_eq!(parse.container(&symbols).as_deref(), Some("type Parser"));
+    }
+
+    #[test]
+    fn test_signatures_and_doc_comments() {
+        let rust = r#"
+/// Load the configuration.
+///
+/// Falls back to defaults.
+#[inline]
+pub fn load_config(
+    path: &Path,
+) -> Result<Config, Error> {
+    todo!()
+}
+
+// Unrelated comment
+
+pub struct Config {
+    name: String,
+}
+"#;
+        let symbols = SymbolExtractor::new(Path::new("lib.rs"))
+            .extract(rust)
+            .unwrap();
+        let load = symbols.iter().find(|s| s.name == "load_config").unwrap();
+        assert_eq!(
+            load.signature(rust).as_deref(),
+            Some("pub fn load_config( path: &Path, ) -> Result<Config, Error>")
+        );
+        assert_eq!(
+            load.doc(rust).as_deref(),
+            Some("Load the configuration.\n\nFalls back to defaults.")
+        );
+        let config = symbols.iter().find(|s| s.name == "Config").unwrap();
+        assert_eq!(config.signature(rust).as_deref(), Some("pub struct Config"));
+        // Separated by a blank line, so not attached
+        assert_eq!(config.doc(rust), None);
+
+        let python = r#"
+class Store:
+    """Key-value store."""
+
+    def get(self, key: str) -> bytes:
+        """
+        Fetch a value.
+        """
+        return b""
+"#;
+        let symbols = SymbolExtractor::new(Path::new("store.py"))
+            .extract(python)
+            .unwrap();
+        let get = symbols.iter().find(|s| s.name == "get").unwrap();
+        assert_eq!(
+            get.signature(python).as_deref(),
+            Some("def get(self, key: str) -> bytes")
+        );
+        assert_eq!(get.doc(python).as_deref(), Some("Fetch a value."));
+        let store = symbols.iter().find(|s| s.name == "Store").unwrap();
+        assert_eq!(store.doc(python).as_deref(), Some("Key-value store."));
+
+        let ts = r#"
+/**
+ * Parse a query string.
+ */
+export function parseQuery(input: string): Query {
+    return {};
+}
+"#;
+        let symbols = SymbolExtractor::new(Path::new("query.ts"))
+            .extract(ts)
+            .unwrap();
+        let parse = symbols.iter().find(|s| s.name == "parseQuery").unwrap();
+        assert_eq!(
+            parse.signature(ts).as_deref(),
+            Some("function parseQuery(input: string): Query")
+        );
+        assert_eq!(parse.doc(ts).as_deref(), Some("Parse a query string."));
+    }
+
+    #[test]
+    fn test_reference_extraction_skips_definitions() {
+        let source = r#"
+struct Config { path: String }
+
+fn load_config() -> Config {
+    Config { path: String::new() }
+}
+
+fn main() {
+    let cfg = load_config();
+    load_config(); load_config();
+}
+"#;
+        let extractor = SymbolExtractor::new(Path::new("test.rs"));
+        let (symbols, _, references) = extractor.extract_with_references(source).unwrap();
+        assert!(symbols.iter().any(|s| s.name == "load_config"));
+
+        let lines: Vec<usize> = references
+            .iter()
+            .filter(|r| r.name == "load_config")
+            .map(|r| r.line)
+            .collect();
+        // Definition on line 3 is excluded; duplicates on one line collapse
+        assert_eq!(lines, vec![8, 9]);
+        let config_lines: Vec<usize> = references
+            .iter()
+            .filter(|r| r.name == "Config")
+            .map(|r| r.line)
+            .collect();
+        assert_eq!(config_lines, vec![3, 4]);
+
+        let json = SymbolExtractor::new(Path::new("data.json"));
+        let (_, _, none) = json.extract_with_references("{\"key\": 1}").unwrap();
+        assert!(none.is_empty());
+    }
+
     #[test]
     fn test_typescript_interface_extraction() {
         let source = r#"
@@ -840,6 +957,82 @@ enum Color {
         );
     }
 
+    #[test]
+    fn test_javascript_methods_and_bound_functions() {
+        let source = r#"
+class UserService {
+    save(user) {}
+    load = () => {};
+    count = 0;
+}
+
+export const helper = () => 1;
+var legacy = function () {};
+const limit = 10;
+"#;
+        let symbols = SymbolExtractor::new(Path::new("service.js"))
+            .extract(source)
+            .unwrap();
+        let found: Vec<(&str, SymbolType, Option<String>)> = symbols
+            .iter()
+            .map(|s| {
+                (
+                    s.name.as_str(),
+                    s.symbol_type.clone(),
+                    s.container(&symbols),
+                )
+            })
+            .collect();
+        let class = Some("class UserService".to_string());
+        assert_eq!(
+            found,
+            vec![
+                ("UserService", SymbolType::Class, None),
+                ("save", SymbolType::Method, class.clone()),
+                ("load", SymbolType::Method, class),
+                ("helper", SymbolType::Function, None),
+                ("legacy", SymbolType::Function, None),
+            ]
+        );
+        let helper = &symbols[3];
+        assert_eq!(
+            helper.signature(source).as_deref(),
+            Some("const helper = () => 1")
+        );
+    }
+
+    #[test]
+    fn test_typescript_class_members() {
+        let source = r#"
+export class UserService {
+    save(user: User): void {}
+    private load = async (id: string): Promise<User> => fetchUser(id);
+    readonly name: string = "users";
+}
+
+export const helper = (): number => 1;
+"#;
+        let symbols = SymbolExtractor::new(Path::new("service.ts"))
+            .extract(source)
+            .unwrap();
+        let names: Vec<&str> = symbols.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["UserService", "save", "load", "helper"]);
+
+        let save = &symbols[1];
+        assert_eq!(save.symbol_type, SymbolType::Method);
+        assert_eq!(
+            save.container(&symbols).as_deref(),
+            Some("class UserService")
+        );
+        let (qualifiers, name) = split_qualified("UserService.save").unwrap();
+        assert!(save.matches_qualified(&symbols, &qualifiers, name));
+        assert_eq!(
+            symbols[2].container(&symbols).as_deref(),
+            Some("class UserService")
+        );
+        assert!(symbols[3].is_top_level());
+    }
+
     #[test]
     fn test_rust_comprehensive_extraction() {
         let source = r#"
@@ -1289,6 +1482,332 @@ helper() {
         );
     }
 
+    #[test]
+    fn test_swift_extraction() {
+        let source = r#"protocol Shape {
+    func area() -> Double
+}
+
+struct Circle: Shape {
+    let radius: Double
+    init(radius: Double) { self.radius = radius }
+    func area() -> Double { return 3.14 * radius * radius }
+}
+
+enum Direction { case north, south }
+
+extension Circle {
+    func describe() -> String { return "circle" }
+}
+
+typealias Shapes = [Shape]
+"#;
+        let extractor = SymbolExtractor::new(Path::new("shapes.swift"));
+        let symbols = extractor.extract(source).unwrap();
+        let find = |name: &str, kind: SymbolType| {
+            symbols
+                .iter()
+                .find(|s| s.name == name && s.symbol_type == kind)
+                .unwrap_or_else(|| panic!("missing {name} ({kind:?}) in {symbols:?}"))
+        };
+
+        find("Shape", SymbolType::Interface);
+        find("area", SymbolType::Method);
+        find("Circle", SymbolType::Struct);
+        find("Direction", SymbolType::Enum);
+        find("Shapes", SymbolType::Type);
+        assert_eq!(
+            find("init", SymbolType::Method)
+                .container(&symbols)
+                .as_deref(),
+            Some("struct Circle")
+        );
+        assert_eq!(
+            find("describe", SymbolType::Method)
+                .container(&symbols)
+                .as_deref(),
+            Some("impl Circle")
+        );
+    }
+
+    #[test]
+    fn test_elixir_extraction() {
+        let source = r#"defmodule MyApp.Accounts do
+  def get_user(id) do
+    Repo.get(User, id)
+  end
+
+  defp normalize(email) when is_binary(email), do: String.downcase(email)
+
+  defmacro admin?(user), do: quote(do: unquote(user).role == :admin)
+end
+"#;
+        let extractor = SymbolExtractor::new(Path::new("accounts.ex"));
+        let symbols = extractor.extract(source).unwrap();
+        let names: Vec<&str> = symbols.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec!["MyApp.Accounts", "get_user", "normalize", "admin?"]
+        );
+
+        let get_user = &symbols[1];
+        assert_eq!(get_user.symbol_type, SymbolType::Function);
+        assert_eq!(
+            get_user.container(&symbols).as_deref(),
+            Some("module MyApp.Accounts")
+        );
+        assert_eq!(
+            get_user.signature(source).as_deref(),
+            Some("def get_user(id)")
+        );
+        assert_eq!((get_user.line, get_user.end_line), (1, 3));
+    }
+
+    #[test]
+    fn test_protobuf_extraction() {
+        let source = r#"syntax = "proto3";
+
+// A search request.
+message SearchRequest {
+  string query = 1;  // not a { brace
+  message Filter {
+    string path = 1;
+  }
+}
+
+enum MatchType { TEXT = 0; SYMBOL = 1; }
+
+service CodeSearch {
+  /* Stream matches */
+  rpc Search(SearchRequest) returns (stream SearchResult);
+  rpc Index(IndexRequest) returns (IndexResponse) {
+    option deprecated = true;
+  }
+}
+"#;
+        let symbols = SymbolExtractor::new(Path::new("search.proto"))
+            .extract(source)
+            .unwrap();
+        let names: Vec<&str> = symbols.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "SearchRequest",
+                "Filter",
+                "MatchType",
+                "CodeSearch",
+                "Search",
+                "Index"
+            ]
+        );
+
+        let request = &symbols[0];
+        assert_eq!(request.symbol_type, SymbolType::Struct);
+        assert_eq!((request.line, request.end_line), (3, 8));
+        assert_eq!(request.doc(source).as_deref(), Some("A search request."));
+        assert_eq!(
+            request.signature(source).as_deref(),
+            Some("message SearchRequest")
+        );
+        assert_eq!(
+            symbols[1].container(&symbols).as_deref(),
+            Some("struct SearchRequest")
+        );
+        assert_eq!(symbols[2].symbol_type, SymbolType::Enum);
+
+        let search = &symbols[4];
+        assert_eq!(search.symbol_type, SymbolType::Method);
+        assert_eq!(
+            search.container(&symbols).as_deref(),
+            Some("interface CodeSearch")
+        );
+        assert_eq!(
+            search.signature(source).as_deref(),
+            Some("rpc Search(SearchRequest) returns (stream SearchResult)")
+        );
+        assert_eq!(search.doc(source).as_deref(), Some("Stream matches"));
+        assert_eq!(symbols[5].end_line, 17);
+        assert_eq!(symbols[3].end_line, 18);
+    }
+
+    #[test]
+    fn test_kotlin_and_scala_extraction() {
+        let kotlin = r#"package app
+
+/** Loads users. */
+@Service
+data class UserService(private val repo: Repo) {
+    suspend fun load(id: Long): User? {
+        val cached = repo.find(id)
+        return cached
+    }
+    val count: Int = 0
+    companion object Factory {}
+}
+
+interface Named { fun name(): String }
+enum class Color { RED }
+fun String.slugify(): String = lowercase()
+const val MAX_USERS = 10
+"#;
+        let symbols = SymbolExtractor::new(Path::new("UserService.kt"))
+            .extract(kotlin)
+            .unwrap();
+        let find = |name: &str| {
+            symbols
+                .iter()
+                .find(|s| s.name == name)
+                .unwrap_or_else(|| panic!("missing {name} in {symbols:?}"))
+        };
+        let service = find("UserService");
+        assert_eq!(service.symbol_type, SymbolType::Class);
+        assert_eq!(service.doc(kotlin).as_deref(), Some("Loads users."));
+        assert_eq!(
+            service.signature(kotlin).as_deref(),
+            Some("@Service data class UserService(private val repo: Repo)")
+        );
+        assert_eq!(service.end_line, 11);
+        let load = find("load");
+        assert_eq!(
+            load.container(&symbols).as_deref(),
+            Some("class UserService")
+        );
+        assert_eq!(
+            load.signature(kotlin).as_deref(),
+            Some("suspend fun load(id: Long): User?")
+        );
+        assert_eq!(find("count").symbol_type, SymbolType::Method);
+        assert!(symbols.iter().all(|s| s.name != "cached"));
+        assert_eq!(
+            find("Factory").container(&symbols).as_deref(),
+            Some("class UserService")
+        );
+        assert_eq!(find("Named").symbol_type, SymbolType::Interface);
+        assert_eq!(find("Color").symbol_type, SymbolType::Enum);
+        assert!(find("slugify").is_top_level());
+        assert_eq!(find("MAX_USERS").symbol_type, SymbolType::Constant);
+
+        let scala = r#"sealed trait Shape {
+  def area: Double
+}
+case class Circle(r: Double) extends Shape {
+  def area: Double = math.Pi * r * r
+}
+object Shapes {
+  type Area = Double
+}
+"#;
+        let symbols = SymbolExtractor::new(Path::new("Shapes.scala"))
+            .extract(scala)
+            .unwrap();
+        let kinds: Vec<(&str, SymbolType)> = symbols
+            .iter()
+            .map(|s| (s.name.as_str(), s.symbol_type.clone()))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                ("Shape", SymbolType::Trait),
+                ("area", SymbolType::Method),
+                ("Circle", SymbolType::Class),
+                ("area", SymbolType::Method),
+                ("Shapes", SymbolType::Class),
+                ("Area", SymbolType::Type),
+            ]
+        );
+        assert_eq!(
+            symbols[3].container(&symbols).as_deref(),
+            Some("class Circle")
+        );
+        assert_eq!(
+            symbols[3].signature(scala).as_deref(),
+            Some("def area: Double")
+        );
+        assert_eq!(
+            symbols[5].container(&symbols).as_deref(),
+            Some("module Shapes")
+        );
+    }
+
+    #[test]
+    fn test_lua_haskell_and_sql_extraction() {
+        let lua = "local M = {}\n\n-- Greets someone\nfunction M.greet(name)\nend\n\nfunction M:reset() end\nlocal helper = function() end\n";
+        let symbols = SymbolExtractor::new(Path::new("init.lua"))
+            .extract(lua)
+            .unwrap();
+        let names: Vec<&str> = symbols.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["greet", "reset", "helper"]);
+        assert_eq!(symbols[0].container(&symbols).as_deref(), Some("type M"));
+        assert_eq!(symbols[0].doc(lua).as_deref(), Some("Greets someone"));
+        assert_eq!(symbols[1].symbol_type, SymbolType::Method);
+        assert!(symbols[2].is_top_level());
+
+        let haskell = "module Data.Parser where\n\n-- | Parse a config\nparseConfig :: String -> Either String Config\nparseConfig s = undefined\n\ndata Config = Config { port :: Int }\nclass Monad m => MonadLog m where\n  logMsg :: String -> m ()\n";
+        let symbols = SymbolExtractor::new(Path::new("Parser.hs"))
+            .extract(haskell)
+            .unwrap();
+        let kinds: Vec<(&str, SymbolType)> = symbols
+            .iter()
+            .map(|s| (s.name.as_str(), s.symbol_type.clone()))
+            .collect();
+        // One symbol per function, named by its signature; record fields
+        // are not definitions
+        assert_eq!(
+            kinds,
+            vec![
+                ("Data.Parser", SymbolType::Class),
+                ("parseConfig", SymbolType::Function),
+                ("Config", SymbolType::Type),
+                ("MonadLog", SymbolType::Trait),
+                ("logMsg", SymbolType::Method),
+            ]
+        );
+        assert_eq!(symbols[1].doc(haskell).as_deref(), Some("Parse a config"));
+        assert_eq!(
+            symbols[4].container(&symbols).as_deref(),
+            Some("trait MonadLog")
+        );
+
+        let sql = "-- Users table\nCREATE TABLE IF NOT EXISTS public.\"users\" (\n  id serial primary key\n);\ncreate or replace function touch_updated_at() returns trigger as $$\nselect 1;\n$$ language sql;\nCREATE UNIQUE INDEX users_email_idx ON users (email);\n";
+        let symbols = SymbolExtractor::new(Path::new("schema.sql"))
+            .extract(sql)
+            .unwrap();
+        let kinds: Vec<(&str, SymbolType)> = symbols
+            .iter()
+            .map(|s| (s.name.as_str(), s.symbol_type.clone()))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                ("users", SymbolType::Struct),
+                ("touch_updated_at", SymbolType::Function),
+                ("users_email_idx", SymbolType::Variable),
+            ]
+        );
+        let users = &symbols[0];
+        assert_eq!(users.container(&symbols).as_deref(), Some("type public"));
+        assert_eq!((users.line, users.end_line), (1, 3));
+        assert_eq!(users.doc(sql).as_deref(), Some("Users table"));
+    }
+
+    #[test]
+    fn test_grammar_languages_are_supported() {
+        for file in [
+            "a.kt", "a.scala", "a.lua", "a.hs", "a.sql", "a.proto", "a.swift", "a.ex",
+        ] {
+            assert!(
+                SymbolExtractor::new(Path::new(file)).is_supported(),
+                "{file}"
+            );
+        }
+        let source = "service Search {\n  rpc Query(Req) returns (Resp);\n}\n";
+        let extractor = SymbolExtractor::new(Path::new("search.proto"));
+        let (symbols, imports, references) = extractor.extract_with_references(source).unwrap();
+        assert_eq!(symbols.len(), 2);
+        assert!(imports.is_empty() && references.is_empty());
+        assert_eq!(extractor.extract(source).unwrap().len(), 2);
+    }
+
     /// Fix #3: C++ template declarations should NOT produce duplicate symbols.
     /// A templated function like `template<class T> T max_val(T a, T b)` should
     /// yield exactly one symbol "max_val", not two.
@@ -1391,4 +1910,119 @@ struct Config {
             assert_eq!(a.line, b.line);
         }
     }
+
+    #[test]
+    fn test_parse_kind_list() {
+        assert_eq!(
+            SymbolType::parse_kind_list("struct, fn,Trait").unwrap(),
+            vec![SymbolType::Struct, SymbolType::Function, SymbolType::Trait]
+        );
+        assert!(SymbolType::parse_kind_list("").unwrap().is_empty());
+        let err = SymbolType::parse_kind_list("struct,widget").unwrap_err();
+        assert!(err.to_string().contains("widget"));
+    }
+
+    #[test]
+    fn test_query_imports_and_receivers() {
+        let python = "import os, sys as system\nfrom .models import User\n";
+        let imports = SymbolExtractor::new(Path::new("app.py"))
+            .extract_imports(python)
+            .unwrap();
+        let paths: Vec<_> = imports.iter().map(|i| i.path.as_str()).collect();
+        assert_eq!(paths, vec!["os", "sys", ".models"]);
+
+        let ts =
+            "import {\n  a,\n  b,\n} from './lib';\nconst x = require(\"fs\");\nrequire(name);\n";
+        let imports = SymbolExtractor::new(Path::new("app.ts"))
+            .extract_imports(ts)
+            .unwrap();
+        let found: Vec<_> = imports.iter().map(|i| (i.path.as_str(), i.line)).collect();
+        assert_eq!(found, vec![("./lib", 0), ("fs", 4)]);
+
+        let go = "package main\n\ntype Stack[T any] struct{}\n\nfunc (s *Stack[T]) Push(v T) {}\n";
+        let symbols = SymbolExtractor::new(Path::new("stack.go"))
+            .extract(go)
+            .unwrap();
+        let push = symbols.iter().find(|s| s.name == "Push").unwrap();
+        assert_eq!(push.container(&symbols).as_deref(), Some("type Stack"));
+
+        let c = "struct point { int x; };\nstruct point origin(void);\nstatic char **names(void) { return 0; }\n";
+        let symbols = SymbolExtractor::new(Path::new("geo.c")).extract(c).unwrap();
+        let names: Vec<_> = symbols.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["point", "names"]);
+    }
+
+    #[test]
+    fn test_imports_for_go_java_c_csharp_ruby_php() {
+        let imports = |file: &str, source: &str| -> Vec<(String, ImportType)> {
+            SymbolExtractor::new(Path::new(file))
+                .extract_imports(source)
+                .unwrap()
+                .into_iter()
+                .map(|i| (i.path, i.import_type))
+                .collect()
+        };
+        let paths = |file: &str, source: &str| -> Vec<String> {
+            imports(file, source).into_iter().map(|(p, _)| p).collect()
+        };
+
+        let go = imports(
+            "main.go",
+            "package main\n\nimport (\n\t\"fmt\"\n\tdb \"example.com/app/internal/store\"\n)\n",
+        );
+        assert_eq!(
+            go,
+            vec![
+                ("fmt".to_string(), ImportType::Go),
+                ("example.com/app/internal/store".to_string(), ImportType::Go),
+            ]
+        );
+
+        assert_eq!(
+            paths(
+                "App.java",
+                "import java.util.List;\nimport com.acme.model.*;\nimport static com.acme.Util.max;\n",
+            ),
+            vec!["java.util.List", "com.acme.model", "com.acme.Util.max"]
+        );
+
+        let c = imports("main.cpp", "#include <vector>\n#include \"geo/point.h\"\n");
+        assert_eq!(
+            c,
+            vec![
+                ("vector".to_string(), ImportType::Include),
+                ("geo/point.h".to_string(), ImportType::Include),
+            ]
+        );
+
+        assert_eq!(
+            paths(
+                "Program.cs",
+                "using System;\nusing static Acme.Util.Math;\nusing Json = Newtonsoft.Json;\n",
+            ),
+            vec!["System", "Acme.Util.Math", "Newtonsoft.Json"]
+        );
+
+        // Only `require_relative` is resolvable within the repository
+        assert_eq!(
+            paths(
+                "app.rb",
+                "require 'json'\nrequire_relative \"lib/helper\"\n"
+            ),
+            vec!["lib/helper"]
+        );
+
+        assert_eq!(
+            paths(
+                "index.php",
+                "<?php\nuse App\\Models\\User;\nuse Carbon\\Carbon as Date;\nrequire_once __DIR__ . '/bootstrap.php';\ninclude 'views/header.php';\n",
+            ),
+            vec![
+                "App\\Models\\User",
+                "Carbon\\Carbon",
+                "/bootstrap.php",
+                "views/header.php"
+            ]
+        );
+    }
 }
@@ -0,0 +1,562 @@
+//! Language detection.
+//!
+//! File extensions alone miss extensionless scripts, build files and C++
+//! headers with a `.h` extension. [`Language::detect`] combines well-known
+//! file names, editor modelines, extensions (with a C++ check for `.h`
+//! headers) and shebang lines; the result drives symbol extraction, ranking,
+//! `lang:` filters, facets and diagnostics.
+
+use anyhow::Result;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::OnceLock;
+
+/// Number of lines at the start and end of a file searched for modelines
+const MODELINE_LINES: usize = 5;
+
+/// Number of lines of a `.h` header inspected for C++ constructs
+const HEADER_SCAN_LINES: usize = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Language {
+    Rust,
+    Python,
+    JavaScript,
+    TypeScript,
+    Go,
+    C,
+    Cpp,
+    Java,
+    CSharp,
+    Ruby,
+    Php,
+    Shell,
+    Swift,
+    Kotlin,
+    Scala,
+    Lua,
+    Elixir,
+    Haskell,
+    Sql,
+    Protobuf,
+    /// Bazel `BUILD`/`WORKSPACE` files and `.bzl` extensions
+    Starlark,
+    /// Groovy sources and `Jenkinsfile`s
+    Groovy,
+    Dockerfile,
+    Makefile,
+    Json,
+    Toml,
+    Yaml,
+    Html,
+    Css,
+    Markdown,
+    Text,
+}
+
+impl Language {
+    /// Every language, in display order
+    pub const ALL: &'static [Language] = &[
+        Language::Rust,
+        Language::Python,
+        Language::JavaScript,
+        Language::TypeScript,
+        Language::Go,
+        Language::C,
+        Language::Cpp,
+        Language::Java,
+        Language::CSharp,
+        Language::Ruby,
+        Language::Php,
+        Language::Shell,
+        Language::Swift,
+        Language::Kotlin,
+        Language::Scala,
+        Language::Lua,
+        Language::Elixir,
+        Language::Haskell,
+        Language::Sql,
+        Language::Protobuf,
+        Language::Starlark,
+        Language::Groovy,
+        Language::Dockerfile,
+        Language::Makefile,
+        Language::Json,
+        Language::Toml,
+        Language::Yaml,
+        Language::Html,
+        Language::Css,
+        Language::Markdown,
+        Language::Text,
+    ];
+
+    /// Human-readable name (used in facets and diagnostics)
+    pub fn name(self) -> &'static str {
+        match self {
+            Language::Rust => "Rust",
+            Language::Python => "Python",
+            Language::JavaScript => "JavaScript",
+            Language::TypeScript => "TypeScript",
+            Language::Go => "Go",
+            Language::C => "C",
+            Language::Cpp => "C++",
+            Language::Java => "Java",
+            Language::CSharp => "C#",
+            Language::Ruby => "Ruby",
+            Language::Php => "PHP",
+            Language::Shell => "Shell",
+            Language::Swift => "Swift",
+            Language::Kotlin => "Kotlin",
+            Language::Scala => "Scala",
+            Language::Lua => "Lua",
+            Language::Elixir => "Elixir",
+            Language::Haskell => "Haskell",
+            Language::Sql => "SQL",
+            Language::Protobuf => "Protobuf",
+            Language::Starlark => "Starlark",
+            Language::Groovy => "Groovy",
+            Language::Dockerfile => "Dockerfile",
+            Language::Makefile => "Makefile",
+            Language::Json => "JSON",
+            Language::Toml => "TOML",
+            Language::Yaml => "YAML",
+            Language::Html => "HTML",
+            Language::Css => "CSS",
+            Language::Markdown => "Markdown",
+            Language::Text => "Text",
+        }
+    }
+
+    /// Parse a language name as written in `lang:` filters and modelines.
+    ///
+    /// Accepts the display name (case-insensitive), common aliases and the
+    /// main file extension (`rs`, `py`, `c++`, `sh`, `bzl`, ...).
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.trim().to_ascii_lowercase();
+        if let Some(language) = Self::ALL
+            .iter()
+            .find(|language| language.name().eq_ignore_ascii_case(&name))
+        {
+            return Some(*language);
+        }
+        let language = match name.as_str() {
+            "rs" => Language::Rust,
+            "py" | "python3" => Language::Python,
+            "js" | "node" => Language::JavaScript,
+            "ts" => Language::TypeScript,
+            "golang" => Language::Go,
+            "cpp" | "cxx" | "cc" => Language::Cpp,
+            "cs" | "csharp" | "c-sharp" => Language::CSharp,
+            "rb" => Language::Ruby,
+            "sh" | "bash" | "zsh" => Language::Shell,
+            "kt" => Language::Kotlin,
+            "hs" => Language::Haskell,
+            "proto" => Language::Protobuf,
+            "bzl" | "bazel" => Language::Starlark,
+            "docker" => Language::Dockerfile,
+            "make" | "mk" => Language::Makefile,
+            "yml" => Language::Yaml,
+            "md" => Language::Markdown,
+            "txt" => Language::Text,
+            _ => return None,
+        };
+        Some(language)
+    }
+
+    /// Parse a comma-separated list of language names, rejecting unknown ones
+    pub fn parse_list(list: &str) -> Result<Vec<Self>> {
+        list.split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .map(|name| {
+                Self::from_name(name).ok_or_else(|| anyhow::anyhow!("Unknown language '{}'", name))
+            })
+            .collect()
+    }
+
+    /// Language implied by a (case-insensitive) file extension
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        let language = match extension.to_ascii_lowercase().as_str() {
+            "rs" => Language::Rust,
+            "py" | "pyi" | "pyw" => Language::Python,
+            "js" | "jsx" | "mjs" | "cjs" => Language::JavaScript,
+            "ts" | "tsx" | "mts" | "cts" => Language::TypeScript,
+            "go" => Language::Go,
+            "c" | "h" => Language::C,
+            "cpp" | "cc" | "cxx" | "hpp" | "hxx" | "hh" => Language::Cpp,
+            "java" => Language::Java,
+            "cs" => Language::CSharp,
+            "rb" | "rake" | "gemspec" => Language::Ruby,
+            "php" => Language::Php,
+            "sh" | "bash" | "zsh" => Language::Shell,
+            "swift" => Language::Swift,
+            "kt" | "kts" => Language::Kotlin,
+            "scala" | "sc" => Language::Scala,
+            "lua" => Language::Lua,
+            "ex" | "exs" => Language::Elixir,
+            "hs" => Language::Haskell,
+            "sql" => Language::Sql,
+            "proto" => Language::Protobuf,
+            "bzl" | "star" => Language::Starlark,
+            "groovy" | "gradle" => Language::Groovy,
+            "dockerfile" => Language::Dockerfile,
+            "mk" => Language::Makefile,
+            "json" | "jsonc" => Language::Json,
+            "toml" => Language::Toml,
+            "yaml" | "yml" => Language::Yaml,
+            "html" | "htm" => Language::Html,
+            "css" | "scss" => Language::Css,
+            "md" | "markdown" => Language::Markdown,
+            "txt" => Language::Text,
+            _ => return None,
+        };
+        Some(language)
+    }
+
+    /// Language implied by a well-known file name (`Dockerfile`, `BUILD`, ...)
+    pub fn from_file_name(file_name: &str) -> Option<Self> {
+        let language = match file_name {
+            "Dockerfile" | "Containerfile" => Language::Dockerfile,
+            "Makefile" | "makefile" | "GNUmakefile" => Language::Makefile,
+            "Jenkinsfile" => Language::Groovy,
+            "BUILD" | "BUILD.bazel" | "WORKSPACE" | "WORKSPACE.bazel" | "MODULE.bazel" => {
+                Language::Starlark
+            }
+            "Rakefile" | "Gemfile" | "Podfile" | "Vagrantfile" | "Brewfile" => Language::Ruby,
+            ".bashrc" | ".bash_profile" | ".zshrc" | ".profile" => Language::Shell,
+            "go.mod" | "go.sum" => Language::Go,
+            _ if file_name.starts_with("Dockerfile.") => Language::Dockerfile,
+            _ => return None,
+        };
+        Some(language)
+    }
+
+    /// Language of the interpreter named by a `#!` line
+    pub fn from_shebang(first_line: &str) -> Option<Self> {
+        let command = first_line.strip_prefix("#!")?;
+        let mut words = command.split_whitespace();
+        let mut interpreter = interpreter_name(words.next()?);
+        if interpreter == "env" {
+            // `#!/usr/bin/env -S python3 -u`, `#!/usr/bin/env PYTHONUTF8=1 python3`
+            interpreter =
+                interpreter_name(words.find(|word| !word.starts_with('-') && !word.contains('='))?);
+        }
+
+        let language = match interpreter.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.')
+        {
+            "python" | "pypy" => Language::Python,
+            "node" | "nodejs" | "bun" => Language::JavaScript,
+            "deno" | "ts-node" | "tsx" => Language::TypeScript,
+            "sh" | "bash" | "zsh" | "dash" | "ksh" | "ash" => Language::Shell,
+            "ruby" => Language::Ruby,
+            "php" => Language::Php,
+            "lua" | "luajit" => Language::Lua,
+            "elixir" => Language::Elixir,
+            "runhaskell" | "runghc" => Language::Haskell,
+            "swift" => Language::Swift,
+            "scala" => Language::Scala,
+            "groovy" => Language::Groovy,
+            "make" => Language::Makefile,
+            _ => return None,
+        };
+        Some(language)
+    }
+
+    /// Language named by a vim (`vim: ft=python`) or emacs (`-*- mode: ruby -*-`)
+    /// modeline in the first or last few lines of a file
+    pub fn from_modeline(content: &str) -> Option<Self> {
+        static VIM: OnceLock<Regex> = OnceLock::new();
+        static EMACS: OnceLock<Regex> = OnceLock::new();
+        let vim = VIM.get_or_init(|| {
+            Regex::new(r"\b(?:vim?|ex):.*\b(?:ft|filetype|syntax)=([A-Za-z0-9_+#-]+)").unwrap()
+        });
+        let emacs = EMACS.get_or_init(|| {
+            Regex::new(r"-\*-\s*(?:[^;]*?\bmode:\s*)?([A-Za-z0-9_+#-]+)\s*(?:;[^*]*)?-\*-").unwrap()
+        });
+
+        // The tail is read backwards from the end of whatever the head did not
+        // cover, so neither scan touches the middle of a large file
+        let head_end = content
+            .match_indices('\n')
+            .nth(MODELINE_LINES - 1)
+            .map_or(content.len(), |(i, _)| i + 1);
+        let rest = &content[head_end..];
+        let rest = rest.strip_suffix('\n').unwrap_or(rest);
+        let head = content.lines().take(MODELINE_LINES);
+        let tail = rest.rsplit('\n').take(MODELINE_LINES);
+        head.chain(tail).find_map(|line| {
+            let captures = vim.captures(line).or_else(|| emacs.captures(line))?;
+            Self::from_name(&captures[1])
+        })
+    }
+
+    /// Detect the language of a file from its path and, when available, its content.
+    ///
+    /// Precedence: well-known file names, then modelines, then the extension
+    /// (with `.h` headers upgraded to C++ when they contain C++ constructs),
+    /// then the shebang line.
+    pub fn detect(path: &Path, content: Option<&str>) -> Option<Self> {
+        if let Some(language) = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(Self::from_file_name)
+        {
+            return Some(language);
+        }
+        if let Some(language) = content.and_then(Self::from_modeline) {
+            return Some(language);
+        }
+
+        let extension = path.extension().and_then(|e| e.to_str());
+        if let Some(language) = extension.and_then(Self::from_extension) {
+            let is_header = extension.is_some_and(|e| e.eq_ignore_ascii_case("h"));
+            if is_header && content.is_some_and(looks_like_cpp) {
+                return Some(Language::Cpp);
+            }
+            return Some(language);
+        }
+
+        content
+            .and_then(|content| content.lines().next())
+            .and_then(Self::from_shebang)
+    }
+
+    /// Tree-sitter grammar used for symbol and import extraction
+    pub(super) fn grammar_name(self) -> Option<&'static str> {
+        let name = match self {
+            Language::Rust => "rust",
+            // Starlark is a Python dialect
+            Language::Python | Language::Starlark => "python",
+            Language::JavaScript => "javascript",
+            Language::TypeScript => "typescript",
+            Language::Go => "go",
+            Language::C => "c",
+            Language::Cpp => "cpp",
+            Language::Java => "java",
+            Language::CSharp => "c_sharp",
+            Language::Ruby => "ruby",
+            Language::Php => "php",
+            Language::Shell => "bash",
+            Language::Swift => "swift",
+            Language::Elixir => "elixir",
+            Language::Kotlin => "kotlin",
+            Language::Scala => "scala",
+            Language::Lua => "lua",
+            Language::Haskell => "haskell",
+            Language::Sql => "sql",
+            Language::Protobuf => "proto",
+            Language::Json => "json",
+            Language::Toml => "toml",
+            Language::Yaml => "yaml",
+            Language::Html => "html",
+            Language::Css => "css",
+            Language::Markdown => "markdown",
+            _ => return None,
+        };
+        Some(name)
+    }
+
+    /// Whether identifier references are extracted for this language
+    pub(super) fn supports_references(self) -> bool {
+        matches!(
+            self,
+            Language::Rust
+                | Language::Python
+                | Language::JavaScript
+                | Language::TypeScript
+                | Language::Go
+                | Language::C
+                | Language::Cpp
+                | Language::Java
+                | Language::CSharp
+                | Language::Ruby
+                | Language::Php
+        )
+    }
+
+    /// Base-score bonus for files in this language: code is worth more than
+    /// docs and config, which are worth more than everything else
+    pub fn ranking_weight(self) -> f32 {
+        match self {
+            Language::Rust
+            | Language::Python
+            | Language::TypeScript
+            | Language::JavaScript
+            | Language::Go
+            | Language::Java
+            | Language::C
+            | Language::Cpp => 1.5,
+            Language::Markdown
+            | Language::Text
+            | Language::Json
+            | Language::Toml
+            | Language::Yaml => 0.5,
+            _ => 0.0,
+        }
+    }
+}
+
+/// Split `lang:` filters off a search query.
+///
+/// Returns the query without the filter tokens and the requested languages
+/// (`lang:rust,go` and repeated `lang:` tokens are both accepted). A query
+/// without filters is returned unchanged.
+pub fn split_language_filters(query: &str) -> Result<(String, Vec<Language>)> {
+    let is_filter = |token: &str| {
+        token
+            .get(..5)
+            .is_some_and(|prefix| prefix.eq_ignore_ascii_case("lang:"))
+    };
+    if !query.split_whitespace().any(is_filter) {
+        return Ok((query.to_string(), Vec::new()));
+    }
+
+    let mut languages = Vec::new();
+    let mut rest = Vec::new();
+    for token in query.split_whitespace() {
+        if is_filter(token) {
+            for language in Language::parse_list(&token[5..])? {
+                if !languages.contains(&language) {
+                    languages.push(language);
+                }
+            }
+        } else {
+            rest.push(token);
+        }
+    }
+    Ok((rest.join(" "), languages))
+}
+
+/// Final path component of an interpreter, e.g. `python3` for `/usr/bin/python3`
+fn interpreter_name(command: &str) -> &str {
+    command.rsplit('/').next().unwrap_or(command)
+}
+
+/// Whether a `.h` header uses C++-only constructs
+fn looks_like_cpp(content: &str) -> bool {
+    content.lines().take(HEADER_SCAN_LINES).any(|line| {
+        let line = line.trim_start();
+        [
+            "class ",
+            "namespace ",
+            "template<",
+            "template <",
+            "using namespace ",
+        ]
+        .iter()
+        .any(|prefix| line.starts_with(prefix))
+            || matches!(line.trim_end(), "public:" | "private:" | "protected:")
+            || line.contains("std::")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_from_file_names_and_shebangs() {
+        let detect = |path: &str, content: &str| Language::detect(Path::new(path), Some(content));
+
+        assert_eq!(
+            detect("bin/deploy", "#!/usr/bin/env python3\nimport os\n"),
+            Some(Language::Python)
+        );
+        assert_eq!(
+            detect(
+                "scripts/run",
+                "#!/usr/bin/env -S node --max-old-space-size=4096\n"
+            ),
+            Some(Language::JavaScript)
+        );
+        assert_eq!(
+            detect("tools/setup", "#!/bin/bash\nset -e\n"),
+            Some(Language::Shell)
+        );
+        assert_eq!(
+            detect("Dockerfile", "FROM rust:1.80\n"),
+            Some(Language::Dockerfile)
+        );
+        assert_eq!(detect("Dockerfile.dev", ""), Some(Language::Dockerfile));
+        assert_eq!(
+            detect("Makefile", "all:\n\tcargo build\n"),
+            Some(Language::Makefile)
+        );
+        assert_eq!(
+            detect("Jenkinsfile", "pipeline {}\n"),
+            Some(Language::Groovy)
+        );
+        assert_eq!(
+            detect("pkg/BUILD", "cc_library(name = 'x')\n"),
+            Some(Language::Starlark)
+        );
+        assert_eq!(detect("README", "plain words\n"), None);
+        // The extension wins over the shebang
+        assert_eq!(detect("run.rb", "#!/bin/sh\n"), Some(Language::Ruby));
+    }
+
+    #[test]
+    fn test_detect_headers_and_modelines() {
+        let detect = |path: &str, content: &str| Language::detect(Path::new(path), Some(content));
+
+        assert_eq!(
+            detect("point.h", "struct point { int x; };\n"),
+            Some(Language::C)
+        );
+        assert_eq!(
+            detect(
+                "vec.h",
+                "#pragma once\nnamespace geo {\nclass Vec {\npublic:\n};\n}\n"
+            ),
+            Some(Language::Cpp)
+        );
+        // Without content, extension mapping is all there is
+        assert_eq!(
+            Language::detect(Path::new("vec.h"), None),
+            Some(Language::C)
+        );
+
+        assert_eq!(
+            detect("tasks.conf", "# vim: set ft=python :\nx = 1\n"),
+            Some(Language::Python)
+        );
+        assert_eq!(
+            detect("old.inc", "# -*- mode: ruby; coding: utf-8 -*-\n"),
+            Some(Language::Ruby)
+        );
+        assert_eq!(
+            detect("legacy.h", "/* -*- C++ -*- */\n"),
+            Some(Language::Cpp)
+        );
+
+        // Trailing modelines count, ones in the middle of the file do not
+        let body = "x = 1\n".repeat(20);
+        assert_eq!(
+            detect("tasks.conf", &format!("{body}# vim: ft=python\n\n")),
+            Some(Language::Python)
+        );
+        assert_eq!(
+            detect("tasks.conf", &format!("{body}# vim: ft=python\n{body}")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_split_language_filters() {
+        let (query, languages) = split_language_filters("lang:rust,go  parse config").unwrap();
+        assert_eq!(query, "parse config");
+        assert_eq!(languages, vec![Language::Rust, Language::Go]);
+
+        let (query, languages) = split_language_filters("handler LANG:ts lang:c++").unwrap();
+        assert_eq!(query, "handler");
+        assert_eq!(languages, vec![Language::TypeScript, Language::Cpp]);
+
+        // Queries without filters keep their exact spacing
+        let (query, languages) = split_language_filters("a  b").unwrap();
+        assert_eq!(query, "a  b");
+        assert!(languages.is_empty());
+
+        assert!(split_language_filters("lang:cobol x").is_err());
+    }
+}
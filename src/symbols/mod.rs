@@ -1,3 +1,12 @@
 pub mod extractor;
+mod language;
+pub mod outline;
+mod queries;
 
-pub use extractor::{ImportStatement, Symbol, SymbolExtractor, SymbolType};
+pub use extractor::{
+    split_qualified, ImportStatement, ScopeKind, Symbol, SymbolExtractor, SymbolReference,
+    SymbolType,
+};
+pub use language::{split_language_filters, Language};
+pub use outline::{build_outline, OutlineNode};
+pub use queries::init_queries;
@@ -0,0 +1,254 @@
+//! File outlines built from extracted symbols.
+//!
+//! Definitions are nested by their source ranges, so a method sits under the
+//! class or impl block that encloses it. Symbols whose container lies outside
+//! their own range (Go methods, declared next to rather than inside their
+//! receiver type) are attached to the top-level type named by their receiver.
+
+use crate::symbols::{Symbol, SymbolType};
+use rustc_hash::FxHashMap;
+use serde::Serialize;
+
+/// A symbol in a file outline with its nested symbols
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct OutlineNode {
+    pub name: String,
+    pub kind: SymbolType,
+    /// First line of the definition (1-based)
+    pub line: usize,
+    /// Last line of the definition (1-based)
+    pub end_line: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<OutlineNode>,
+}
+
+/// Whether `outer` encloses `inner`, by byte range when both have one and
+/// by line range otherwise
+fn encloses(outer: &Symbol, inner: &Symbol) -> bool {
+    if !outer.byte_range.is_empty() && !inner.byte_range.is_empty() {
+        outer.byte_range.start <= inner.byte_range.start
+            && inner.byte_range.end <= outer.byte_range.end
+    } else {
+        outer.line <= inner.line && inner.end_line.max(inner.line) <= outer.end_line
+    }
+}
+
+fn is_type_like(kind: &SymbolType) -> bool {
+    matches!(
+        kind,
+        SymbolType::Class
+            | SymbolType::Struct
+            | SymbolType::Interface
+            | SymbolType::Trait
+            | SymbolType::Enum
+            | SymbolType::Type
+    )
+}
+
+/// Build the outline tree of a file from its cached symbols and its source
+/// (for signatures).
+///
+/// File-name entries and non-definitions are skipped; siblings are ordered by
+/// position in the file.
+pub fn build_outline(symbols: &[Symbol], source: &str) -> Vec<OutlineNode> {
+    let mut defs: Vec<&Symbol> = symbols
+        .iter()
+        .filter(|s| s.is_definition && s.symbol_type != SymbolType::FileName)
+        .collect();
+    // Outer definitions sort before the ones they enclose
+    defs.sort_by_key(|s| {
+        (
+            s.line,
+            std::cmp::Reverse(s.end_line),
+            s.byte_range.start,
+            std::cmp::Reverse(s.byte_range.end),
+        )
+    });
+
+    let mut parent: Vec<Option<usize>> = vec![None; defs.len()];
+    let mut stack: Vec<usize> = Vec::new();
+    for (idx, symbol) in defs.iter().enumerate() {
+        while let Some(&top) = stack.last() {
+            if encloses(defs[top], symbol) {
+                break;
+            }
+            stack.pop();
+        }
+        parent[idx] = stack.last().copied();
+        stack.push(idx);
+    }
+
+    // Top-level symbols declared on a receiver: attach them to the top-level
+    // type of that name (generic arguments ignored)
+    let mut top_level_types: FxHashMap<&str, usize> = FxHashMap::default();
+    for (idx, symbol) in defs.iter().enumerate() {
+        if parent[idx].is_none() && is_type_like(&symbol.symbol_type) {
+            top_level_types
+                .entry(base_name(&symbol.name))
+                .or_insert(idx);
+        }
+    }
+    for idx in 0..defs.len() {
+        if parent[idx].is_some() {
+            continue;
+        }
+        if let Some(receiver) = &defs[idx].receiver {
+            if let Some(&container) = top_level_types.get(base_name(receiver)) {
+                if container != idx {
+                    parent[idx] = Some(container);
+                }
+            }
+        }
+    }
+
+    let mut children: Vec<Vec<usize>> = vec![Vec::new(); defs.len()];
+    let mut roots = Vec::new();
+    for (idx, p) in parent.iter().enumerate() {
+        match p {
+            Some(p) => children[*p].push(idx),
+            None => roots.push(idx),
+        }
+    }
+
+    fn build(idx: usize, defs: &[&Symbol], children: &[Vec<usize>], source: &str) -> OutlineNode {
+        let symbol = defs[idx];
+        OutlineNode {
+            name: symbol.name.clone(),
+            kind: symbol.symbol_type.clone(),
+            line: symbol.line + 1,
+            end_line: symbol.end_line.max(symbol.line) + 1,
+            signature: symbol.signature(source),
+            children: children[idx]
+                .iter()
+                .map(|&child| build(child, defs, children, source))
+                .collect(),
+        }
+    }
+
+    roots
+        .into_iter()
+        .map(|idx| build(idx, &defs, &children, source))
+        .collect()
+}
+
+/// Name without generic arguments or a pointer prefix (`*Server`, `Parser<'a>`)
+fn base_name(name: &str) -> &str {
+    name.trim_start_matches(['*', '&'])
+        .split(['<', '['])
+        .next()
+        .unwrap_or("")
+        .trim()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::symbols::SymbolExtractor;
+    use std::path::Path;
+
+    fn outline(file: &str, source: &str) -> Vec<OutlineNode> {
+        let mut symbols = SymbolExtractor::new(Path::new(file))
+            .extract(source)
+            .unwrap();
+        symbols.push(Symbol::file_name("test"));
+        build_outline(&symbols, source)
+    }
+
+    fn shape(nodes: &[OutlineNode]) -> Vec<(String, Vec<String>)> {
+        nodes
+            .iter()
+            .map(|n| {
+                let children = n.children.iter().map(|c| c.name.clone()).collect();
+                (n.name.clone(), children)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_rust_outline_nests_by_range() {
+        let source = r#"pub struct Parser;
+
+impl Parser {
+    /// Parse it
+    pub fn parse(&self) -> bool {
+        true
+    }
+
+    fn reset(&mut self) {}
+}
+
+pub fn main() {}
+"#;
+        let nodes = outline("lib.rs", source);
+        assert_eq!(
+            shape(&nodes),
+            vec![
+                ("Parser".to_string(), vec![]),
+                (
+                    "Parser".to_string(),
+                    vec!["parse".to_string(), "reset".to_string()]
+                ),
+                ("main".to_string(), vec![]),
+            ]
+        );
+        let parse = &nodes[1].children[0];
+        assert_eq!((parse.line, parse.end_line), (5, 7));
+        assert_eq!(
+            parse.signature.as_deref(),
+            Some("pub fn parse(&self) -> bool")
+        );
+    }
+
+    #[test]
+    fn test_typescript_outline_lists_class_members() {
+        let source = r#"export class UserService {
+    save(): void {}
+    load = () => {};
+}
+
+export const helper = () => 1;
+"#;
+        let nodes = outline("service.ts", source);
+        assert_eq!(
+            shape(&nodes),
+            vec![
+                (
+                    "UserService".to_string(),
+                    vec!["save".to_string(), "load".to_string()]
+                ),
+                ("helper".to_string(), vec![]),
+            ]
+        );
+        assert_eq!(nodes[0].children[0].kind, SymbolType::Method);
+        assert_eq!(
+            nodes[0].children[0].signature.as_deref(),
+            Some("save(): void")
+        );
+    }
+
+    #[test]
+    fn test_go_methods_attach_to_receiver_type() {
+        let source = r#"package main
+
+type Server struct {
+	port int
+}
+
+func (s *Server) Start() error {
+	return nil
+}
+
+func main() {}
+"#;
+        let nodes = outline("main.go", source);
+        assert_eq!(
+            shape(&nodes),
+            vec![
+                ("Server".to_string(), vec!["Start".to_string()]),
+                ("main".to_string(), vec![]),
+            ]
+        );
+    }
+}
@@ -0,0 +1,588 @@
+//! Declarative symbol and import extraction with tree-sitter queries.
+//!
+//! Each grammar has a query file in the `tags.scm` style, embedded from
+//! `src/symbols/queries/<name>.scm`:
+//!
+//! - `@definition.<kind>` marks a definition node and `@name` its name, where
+//!   `<kind>` is one of `function`, `method`, `class`, `struct`, `enum`,
+//!   `interface`, `trait`, `type`, `constant`, `variable`, `module`, `impl`,
+//!   `namespace` or `property`
+//! - `@receiver` names the type of a method declared outside it (Go)
+//! - `@import` marks an import statement and `@path` the imported path
+//!   (quotes are stripped)
+//!
+//! Captures starting with `_` are free for predicates (`#eq?`, `#any-of?`).
+//! A file may start with `; inherits: javascript` to include another
+//! language's queries first. When a node matches several patterns with the
+//! same name, the earliest pattern wins.
+//!
+//! Containers are the enclosing definitions whose kind opens a scope, found
+//! by byte range. A `function` directly inside a type (impl, trait, class,
+//! interface, struct or enum) is a method. Query files can be overridden from a directory of
+//! `<name>.scm` files via [`init_queries`].
+
+use super::extractor::{ImportStatement, ImportType, ScopeKind, Symbol, SymbolType};
+use anyhow::{anyhow, Context, Result};
+use rustc_hash::{FxHashMap, FxHashSet};
+use std::collections::hash_map::Entry;
+use std::path::Path;
+use std::sync::OnceLock;
+use tree_sitter::{Node, Query, QueryCursor, StreamingIterator};
+use tree_sitter_language::LanguageFn;
+
+/// A tree-sitter grammar with its query file
+pub(super) struct Grammar {
+    /// Query file name (without `.scm`)
+    pub name: &'static str,
+    pub language: LanguageFn,
+    /// Kind of the imports the query captures, if any
+    pub import_type: Option<ImportType>,
+    embedded: &'static str,
+}
+
+const fn grammar(
+    name: &'static str,
+    language: LanguageFn,
+    import_type: Option<ImportType>,
+    embedded: &'static str,
+) -> Grammar {
+    Grammar {
+        name,
+        language,
+        import_type,
+        embedded,
+    }
+}
+
+static GRAMMARS: &[Grammar] = &[
+    grammar(
+        "rust",
+        tree_sitter_rust::LANGUAGE,
+        Some(ImportType::Rust),
+        include_str!("queries/rust.scm"),
+    ),
+    grammar(
+        "python",
+        tree_sitter_python::LANGUAGE,
+        Some(ImportType::Python),
+        include_str!("queries/python.scm"),
+    ),
+    grammar(
+        "javascript",
+        tree_sitter_javascript::LANGUAGE,
+        Some(ImportType::JavaScript),
+        include_str!("queries/javascript.scm"),
+    ),
+    grammar(
+        "typescript",
+        tree_sitter_typescript::LANGUAGE_TYPESCRIPT,
+        Some(ImportType::JavaScript),
+        include_str!("queries/typescript.scm"),
+    ),
+    grammar(
+        "go",
+        tree_sitter_go::LANGUAGE,
+        Some(ImportType::Go),
+        include_str!("queries/go.scm"),
+    ),
+    grammar(
+        "c",
+        tree_sitter_c::LANGUAGE,
+        Some(ImportType::Include),
+        include_str!("queries/c.scm"),
+    ),
+    grammar(
+        "cpp",
+        tree_sitter_cpp::LANGUAGE,
+        Some(ImportType::Include),
+        include_str!("queries/cpp.scm"),
+    ),
+    grammar(
+        "java",
+        tree_sitter_java::LANGUAGE,
+        Some(ImportType::Java),
+        include_str!("queries/java.scm"),
+    ),
+    grammar(
+        "c_sharp",
+        tree_sitter_c_sharp::LANGUAGE,
+        Some(ImportType::CSharp),
+        include_str!("queries/c_sharp.scm"),
+    ),
+    grammar(
+        "ruby",
+        tree_sitter_ruby::LANGUAGE,
+        Some(ImportType::Ruby),
+        include_str!("queries/ruby.scm"),
+    ),
+    grammar(
+        "php",
+        tree_sitter_php::LANGUAGE_PHP,
+        Some(ImportType::Php),
+        include_str!("queries/php.scm"),
+    ),
+    grammar(
+        "bash",
+        tree_sitter_bash::LANGUAGE,
+        None,
+        include_str!("queries/bash.scm"),
+    ),
+    grammar(
+        "swift",
+        tree_sitter_swift::LANGUAGE,
+        None,
+        include_str!("queries/swift.scm"),
+    ),
+    grammar(
+        "elixir",
+        tree_sitter_elixir::LANGUAGE,
+        None,
+        include_str!("queries/elixir.scm"),
+    ),
+    grammar(
+        "kotlin",
+        tree_sitter_kotlin_ng::LANGUAGE,
+        None,
+        include_str!("queries/kotlin.scm"),
+    ),
+    grammar(
+        "scala",
+        tree_sitter_scala::LANGUAGE,
+        None,
+        include_str!("queries/scala.scm"),
+    ),
+    grammar(
+        "lua",
+        tree_sitter_lua::LANGUAGE,
+        None,
+        include_str!("queries/lua.scm"),
+    ),
+    grammar(
+        "haskell",
+        tree_sitter_haskell::LANGUAGE,
+        None,
+        include_str!("queries/haskell.scm"),
+    ),
+    grammar(
+        "sql",
+        tree_sitter_sequel::LANGUAGE,
+        None,
+        include_str!("queries/sql.scm"),
+    ),
+    grammar(
+        "proto",
+        tree_sitter_proto::LANGUAGE,
+        None,
+        include_str!("queries/proto.scm"),
+    ),
+    // Config and markup languages: parsed, but no symbols by default
+    grammar("json", tree_sitter_json::LANGUAGE, None, ""),
+    grammar("toml", tree_sitter_toml_ng::LANGUAGE, None, ""),
+    grammar("yaml", tree_sitter_yaml::LANGUAGE, None, ""),
+    grammar("html", tree_sitter_html::LANGUAGE, None, ""),
+    grammar("css", tree_sitter_css::LANGUAGE, None, ""),
+    grammar("markdown", tree_sitter_md::LANGUAGE, None, ""),
+];
+
+/// Look up a grammar by its query file name
+pub(super) fn grammar_named(name: &str) -> Option<&'static Grammar> {
+    GRAMMARS.iter().find(|g| g.name == name)
+}
+
+/// Kind named by a `@definition.<kind>` capture
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DefinitionKind {
+    Function,
+    Method,
+    Class,
+    Struct,
+    Enum,
+    Interface,
+    Trait,
+    Type,
+    Constant,
+    Variable,
+    Module,
+    Impl,
+    Namespace,
+    Property,
+}
+
+impl DefinitionKind {
+    fn from_capture(kind: &str) -> Option<Self> {
+        Some(match kind {
+            "function" => Self::Function,
+            "method" => Self::Method,
+            "class" => Self::Class,
+            "struct" => Self::Struct,
+            "enum" => Self::Enum,
+            "interface" => Self::Interface,
+            "trait" => Self::Trait,
+            "type" => Self::Type,
+            "constant" => Self::Constant,
+            "variable" => Self::Variable,
+            "module" => Self::Module,
+            "impl" => Self::Impl,
+            "namespace" => Self::Namespace,
+            "property" => Self::Property,
+            _ => return None,
+        })
+    }
+
+    fn symbol_type(self) -> SymbolType {
+        match self {
+            Self::Function => SymbolType::Function,
+            Self::Method | Self::Property => SymbolType::Method,
+            Self::Class | Self::Module | Self::Impl | Self::Namespace => SymbolType::Class,
+            Self::Struct => SymbolType::Struct,
+            Self::Enum => SymbolType::Enum,
+            Self::Interface => SymbolType::Interface,
+            Self::Trait => SymbolType::Trait,
+            Self::Type => SymbolType::Type,
+            Self::Constant => SymbolType::Constant,
+            Self::Variable => SymbolType::Variable,
+        }
+    }
+
+    /// Scope opened for the definitions nested inside, if any
+    fn scope(self) -> Option<ScopeKind> {
+        match self {
+            Self::Function | Self::Method => Some(ScopeKind::Function),
+            Self::Class => Some(ScopeKind::Class),
+            Self::Struct => Some(ScopeKind::Struct),
+            Self::Enum => Some(ScopeKind::Enum),
+            Self::Interface => Some(ScopeKind::Interface),
+            Self::Trait => Some(ScopeKind::Trait),
+            Self::Module => Some(ScopeKind::Module),
+            Self::Impl => Some(ScopeKind::Impl),
+            Self::Namespace => Some(ScopeKind::Namespace),
+            Self::Type | Self::Constant | Self::Variable | Self::Property => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Capture {
+    Name,
+    Definition(DefinitionKind),
+    Receiver,
+    Import,
+    Path,
+    Ignored,
+}
+
+impl Capture {
+    fn parse(name: &str) -> Result<Self> {
+        Ok(match name {
+            "name" => Self::Name,
+            "receiver" => Self::Receiver,
+            "import" => Self::Import,
+            "path" => Self::Path,
+            _ if name.starts_with('_') => Self::Ignored,
+            _ => {
+                let kind = name
+                    .strip_prefix("definition.")
+                    .and_then(DefinitionKind::from_capture)
+                    .ok_or_else(|| anyhow!("unknown capture @{}", name))?;
+                Self::Definition(kind)
+            }
+        })
+    }
+}
+
+/// A compiled query file
+pub(super) struct LanguageQueries {
+    query: Query,
+    /// Meaning of each capture, by capture index
+    captures: Vec<Capture>,
+}
+
+/// Symbols and imports found by a query
+pub(super) struct QueryResults {
+    pub symbols: Vec<Symbol>,
+    pub imports: Vec<ImportStatement>,
+    /// Node IDs of the definition names, which are not references
+    pub name_nodes: FxHashSet<usize>,
+}
+
+/// A definition match before containers are assigned
+struct Found<'tree> {
+    pattern: usize,
+    kind: DefinitionKind,
+    node: Node<'tree>,
+    name: Node<'tree>,
+    receiver: Option<Node<'tree>>,
+}
+
+impl LanguageQueries {
+    fn compile(grammar: &Grammar, source: &str) -> Result<Self> {
+        let query = Query::new(&grammar.language.into(), source).map_err(|e| anyhow!("{}", e))?;
+        let captures = query
+            .capture_names()
+            .iter()
+            .map(|name| Capture::parse(name))
+            .collect::<Result<_>>()?;
+        Ok(Self { query, captures })
+    }
+
+    /// Whether the query has no patterns (nothing to extract)
+    pub fn is_empty(&self) -> bool {
+        self.query.pattern_count() == 0
+    }
+
+    /// Run the query over a parse tree
+    pub fn run(&self, root: Node, source: &str, import_type: Option<ImportType>) -> QueryResults {
+        let mut cursor = QueryCursor::new();
+        let mut matches = cursor.matches(&self.query, root, source.as_bytes());
+
+        let mut found: FxHashMap<(usize, usize), Found> = FxHashMap::default();
+        let mut import_paths: FxHashMap<usize, (Node, Node)> = FxHashMap::default();
+        while let Some(m) = matches.next() {
+            let (mut name, mut definition, mut receiver) = (None, None, None);
+            let (mut import, mut path) = (None, None);
+            for capture in m.captures {
+                match self.captures[capture.index as usize] {
+                    Capture::Name => name = Some(capture.node),
+                    Capture::Definition(kind) => definition = Some((kind, capture.node)),
+                    Capture::Receiver => receiver = Some(capture.node),
+                    Capture::Import => import = Some(capture.node),
+                    Capture::Path => path = Some(capture.node),
+                    Capture::Ignored => {}
+                }
+            }
+            if let (Some((kind, node)), Some(name)) = (definition, name) {
+                let candidate = Found {
+                    pattern: m.pattern_index,
+                    kind,
+                    node,
+                    name,
+                    receiver,
+                };
+                match found.entry((node.id(), name.id())) {
+                    Entry::Occupied(mut existing) => {
+                        if candidate.pattern < existing.get().pattern {
+                            existing.insert(candidate);
+                        }
+                    }
+                    Entry::Vacant(slot) => {
+                        slot.insert(candidate);
+                    }
+                }
+            }
+            if let Some(path) = path {
+                import_paths
+                    .entry(path.id())
+                    .or_insert((import.unwrap_or(path), path));
+            }
+        }
+
+        let mut found: Vec<Found> = found.into_values().collect();
+        // Outer definitions sort before the ones they enclose
+        found.sort_by_key(|f| {
+            (
+                f.node.start_byte(),
+                std::cmp::Reverse(f.node.end_byte()),
+                f.pattern,
+            )
+        });
+
+        let name_nodes = found.iter().map(|f| f.name.id()).collect();
+        let mut symbols: Vec<Symbol> = Vec::with_capacity(found.len());
+        // Enclosing definitions: (node, index of its symbol if it opens a scope)
+        let mut open: Vec<(Node, Option<u32>)> = Vec::new();
+        for f in &found {
+            let range = f.node.byte_range();
+            while let Some((outer, _)) = open.last() {
+                if outer.start_byte() <= range.start && range.end <= outer.end_byte() {
+                    break;
+                }
+                open.pop();
+            }
+            // Quoted identifiers (SQL `"users"`, `[users]`) are named without quotes
+            let name = source[f.name.byte_range()].trim_matches(['"', '`', '[', ']']);
+            let parent = open
+                .iter()
+                .rev()
+                .filter(|(outer, _)| outer.id() != f.node.id())
+                .find_map(|(_, idx)| *idx);
+            let kind = match (f.kind, parent.and_then(|p| symbols[p as usize].scope)) {
+                (
+                    DefinitionKind::Function,
+                    Some(
+                        ScopeKind::Impl
+                        | ScopeKind::Trait
+                        | ScopeKind::Class
+                        | ScopeKind::Interface
+                        | ScopeKind::Struct
+                        | ScopeKind::Enum,
+                    ),
+                ) => DefinitionKind::Method,
+                (kind, _) => kind,
+            };
+            let mut symbol = Symbol::definition(name, kind.symbol_type(), &f.node, source);
+            symbol.parent = parent;
+            symbol.scope = f.kind.scope();
+            symbol.receiver = f
+                .receiver
+                .map(|receiver| source[receiver.byte_range()].to_string());
+            let idx = symbols.len() as u32;
+            open.push((f.node, symbol.scope.map(|_| idx)));
+            symbols.push(symbol);
+        }
+
+        let imports = match import_type {
+            Some(import_type) => {
+                let mut paths: Vec<(Node, Node)> = import_paths.into_values().collect();
+                paths.sort_by_key(|(_, path)| path.start_byte());
+                paths
+                    .into_iter()
+                    .filter_map(|(statement, path)| {
+                        let text =
+                            source[path.byte_range()].trim_matches(['"', '\'', '`', '<', '>']);
+                        (!text.is_empty()).then(|| ImportStatement {
+                            path: text.to_string(),
+                            line: statement.start_position().row,
+                            import_type: import_type.clone(),
+                        })
+                    })
+                    .collect()
+            }
+            None => Vec::new(),
+        };
+
+        QueryResults {
+            symbols,
+            imports,
+            name_nodes,
+        }
+    }
+}
+
+static QUERIES: OnceLock<Vec<LanguageQueries>> = OnceLock::new();
+
+/// Compiled queries of a grammar, loading the embedded query files on first use
+pub(super) fn queries_for(grammar: &Grammar) -> &'static LanguageQueries {
+    let all =
+        QUERIES.get_or_init(|| load_queries(None).expect("embedded symbol queries must compile"));
+    let idx = GRAMMARS
+        .iter()
+        .position(|g| std::ptr::eq(g, grammar))
+        .expect("grammar comes from the grammar table");
+    &all[idx]
+}
+
+/// Load the symbol queries, preferring `<dir>/<name>.scm` over the embedded
+/// query file for each language.
+///
+/// Must be called before any file is parsed; fails if a query file does not
+/// compile or the queries were already loaded.
+pub fn init_queries(dir: Option<&Path>) -> Result<()> {
+    let queries = load_queries(dir)?;
+    QUERIES
+        .set(queries)
+        .map_err(|_| anyhow!("Symbol queries were already loaded"))
+}
+
+fn load_queries(dir: Option<&Path>) -> Result<Vec<LanguageQueries>> {
+    if let Some(dir) = dir {
+        for entry in std::fs::read_dir(dir)
+            .with_context(|| format!("Failed to read query directory {}", dir.display()))?
+        {
+            let path = entry?.path();
+            let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("");
+            if path.extension().is_some_and(|e| e == "scm") && grammar_named(stem).is_none() {
+                tracing::warn!(path = %path.display(), "Ignoring query file for unknown language");
+            }
+        }
+    }
+
+    GRAMMARS
+        .iter()
+        .map(|grammar| {
+            let source = query_source(grammar.name, dir, 0)?;
+            LanguageQueries::compile(grammar, &source)
+                .with_context(|| format!("Invalid symbol query file for {}", grammar.name))
+        })
+        .collect()
+}
+
+/// Query file text with `; inherits:` languages prepended
+fn query_source(name: &str, dir: Option<&Path>, depth: usize) -> Result<String> {
+    if depth > 4 {
+        return Err(anyhow!("Query inheritance is too deep at {}", name));
+    }
+    let own = match dir.map(|d| d.join(format!("{}.scm", name))) {
+        Some(path) if path.exists() => std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?,
+        _ => grammar_named(name)
+            .map(|g| g.embedded.to_string())
+            .ok_or_else(|| anyhow!("Unknown query language {}", name))?,
+    };
+
+    let mut source = String::new();
+    if let Some(inherits) = own
+        .lines()
+        .next()
+        .and_then(|line| line.trim().strip_prefix("; inherits:"))
+    {
+        for parent in inherits.split(',').map(str::trim) {
+            source.push_str(&query_source(parent, dir, depth + 1)?);
+            source.push('\n');
+        }
+    }
+    source.push_str(&own);
+    Ok(source)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_embedded_queries_compile() {
+        let queries = load_queries(None).unwrap();
+        assert_eq!(queries.len(), GRAMMARS.len());
+        let rust = &queries[0];
+        assert!(!rust.is_empty());
+        assert!(queries[GRAMMARS.len() - 1].is_empty());
+    }
+
+    #[test]
+    fn test_override_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("bash.scm"),
+            "(variable_assignment name: (_) @name) @definition.variable\n",
+        )
+        .unwrap();
+        let queries = load_queries(Some(dir.path())).unwrap();
+        let bash = grammar_named("bash").unwrap();
+        let idx = GRAMMARS.iter().position(|g| g.name == "bash").unwrap();
+
+        let source = "GREETING=hello\nfunction greet() { echo hi; }\n";
+        let mut parser = tree_sitter::Parser::new();
+        parser.set_language(&bash.language.into()).unwrap();
+        let tree = parser.parse(source, None).unwrap();
+        let results = queries[idx].run(tree.root_node(), source, None);
+        let names: Vec<_> = results.symbols.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["GREETING"]);
+        assert_eq!(results.symbols[0].symbol_type, SymbolType::Variable);
+
+        // Inherited files come from the override directory too
+        std::fs::write(
+            dir.path().join("c.scm"),
+            "(bogus_node) @definition.function",
+        )
+        .unwrap();
+        let Err(err) = load_queries(Some(dir.path())) else {
+            panic!("invalid node type should fail to compile");
+        };
+        assert!(format!("{:#}", err).contains("for c:"), "{err:#}");
+
+        std::fs::write(dir.path().join("c.scm"), "(translation_unit) @widget").unwrap();
+        let Err(err) = load_queries(Some(dir.path())) else {
+            panic!("unknown capture should be rejected");
+        };
+        assert!(format!("{:#}", err).contains("unknown capture @widget"));
+    }
+}
@@ -0,0 +1,3 @@
+; Bash definitions
+
+(function_definition name: (_) @name) @definition.function
@@ -0,0 +1,18 @@
+; C definitions and includes. Function names sit inside the declarator, possibly behind
+; pointer declarators; struct/union/enum uses without a body are skipped.
+
+(function_definition
+  declarator: (function_declarator declarator: (_) @name)) @definition.function
+(function_definition
+  declarator: (pointer_declarator
+    declarator: (function_declarator declarator: (_) @name))) @definition.function
+(function_definition
+  declarator: (pointer_declarator
+    declarator: (pointer_declarator
+      declarator: (function_declarator declarator: (_) @name)))) @definition.function
+
+(struct_specifier name: (_) @name body: (_)) @definition.struct
+(union_specifier name: (_) @name body: (_)) @definition.struct
+(enum_specifier name: (_) @name body: (_)) @definition.enum
+
+(preproc_include path: [(string_literal) (system_lib_string)] @path) @import
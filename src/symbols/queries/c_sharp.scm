@@ -0,0 +1,13 @@
+; C# definitions and using directives. The namespace is the last child, after
+; any `alias =`.
+
+(method_declaration name: (_) @name) @definition.method
+(constructor_declaration name: (_) @name) @definition.method
+(property_declaration name: (_) @name) @definition.property
+(class_declaration name: (_) @name) @definition.class
+(record_declaration name: (_) @name) @definition.class
+(struct_declaration name: (_) @name) @definition.struct
+(interface_declaration name: (_) @name) @definition.interface
+(enum_declaration name: (_) @name) @definition.enum
+
+(using_directive (_) @path .) @import
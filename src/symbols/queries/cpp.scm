@@ -0,0 +1,9 @@
+; inherits: c
+; C++ definitions on top of the C ones
+
+(function_definition
+  declarator: (reference_declarator
+    (function_declarator declarator: (_) @name))) @definition.function
+
+(class_specifier name: (_) @name body: (_)) @definition.class
+(namespace_definition name: (_) @name) @definition.namespace
@@ -0,0 +1,25 @@
+; Elixir definitions are macro calls: `defmodule Name do`, `def name(args)`,
+; `defp name(args) when guard`, ...
+
+(call
+  target: (identifier) @_keyword
+  (arguments . (alias) @name)
+  (#any-of? @_keyword "defmodule" "defprotocol")) @definition.module
+
+(call
+  target: (identifier) @_keyword
+  (arguments . (alias) @name)
+  (#eq? @_keyword "defimpl")) @definition.impl
+
+(call
+  target: (identifier) @_keyword
+  (arguments
+    .
+    [
+      (identifier) @name
+      (call target: (identifier) @name)
+      (binary_operator
+        left: (call target: (identifier) @name)
+        operator: "when")
+    ])
+  (#any-of? @_keyword "def" "defp" "defmacro" "defmacrop" "defguard" "defguardp" "defdelegate")) @definition.function
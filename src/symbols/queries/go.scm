@@ -0,0 +1,24 @@
+; Go definitions and imports. Methods are declared outside their type, so the receiver
+; type is captured as the container.
+
+(function_declaration name: (_) @name) @definition.function
+
+(method_declaration
+  receiver: (parameter_list
+    (parameter_declaration
+      type: [
+        (type_identifier) @receiver
+        (pointer_type (type_identifier) @receiver)
+        (generic_type type: (type_identifier) @receiver)
+        (pointer_type (generic_type type: (type_identifier) @receiver))
+      ]))
+  name: (_) @name) @definition.method
+(method_declaration name: (_) @name) @definition.method
+
+(type_spec name: (_) @name type: (struct_type)) @definition.struct
+(type_spec name: (_) @name type: (interface_type)) @definition.interface
+(type_spec name: (_) @name) @definition.type
+(const_spec name: (_) @name) @definition.constant
+(var_spec name: (_) @name) @definition.variable
+
+(import_spec path: (_) @path) @import
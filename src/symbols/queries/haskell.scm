@@ -0,0 +1,12 @@
+; Haskell definitions. Functions are named by their type signatures, so a
+; function defined by several equations is a single symbol; class method
+; signatures nest under their class.
+
+(header module: (module) @name) @definition.module
+(data_type name: (name) @name) @definition.type
+(newtype name: (name) @name) @definition.type
+(type_synonym name: (name) @name) @definition.type
+(type_family name: (name) @name) @definition.type
+(class name: (name) @name) @definition.trait
+(declarations (signature name: (variable) @name) @definition.function)
+(class_declarations (signature name: (variable) @name) @definition.method)
@@ -0,0 +1,10 @@
+; Java definitions and imports. Wildcard imports capture the package name.
+
+(method_declaration name: (_) @name) @definition.method
+(constructor_declaration name: (_) @name) @definition.method
+(class_declaration name: (_) @name) @definition.class
+(record_declaration name: (_) @name) @definition.class
+(interface_declaration name: (_) @name) @definition.interface
+(enum_declaration name: (_) @name) @definition.enum
+
+(import_declaration [(identifier) (scoped_identifier)] @path) @import
@@ -0,0 +1,27 @@
+; JavaScript definitions and imports
+
+(function_declaration name: (_) @name) @definition.function
+(class_declaration name: (_) @name) @definition.class
+(class name: (_) @name) @definition.class
+(method_definition name: (_) @name) @definition.method
+
+; Functions bound to a class field or a variable. The field pattern names no
+; node type because TypeScript, which inherits this file, has no
+; `field_definition` (its `public_field_definition` is matched there)
+(_
+  property: (property_identifier) @name
+  value: [(arrow_function) (function_expression)]) @definition.method
+(lexical_declaration
+  (variable_declarator
+    name: (identifier) @name
+    value: [(arrow_function) (function_expression)])) @definition.function
+(variable_declaration
+  (variable_declarator
+    name: (identifier) @name
+    value: [(arrow_function) (function_expression)])) @definition.function
+
+(import_statement source: (string) @path) @import
+(call_expression
+  function: (identifier) @_require
+  arguments: (arguments . (string) @path)
+  (#eq? @_require "require")) @import
@@ -0,0 +1,19 @@
+; Kotlin definitions. `class_declaration` also declares interfaces and enum
+; classes; the more specific patterns come first and take precedence. Only
+; top-level and member properties are captured, not local variables.
+
+(class_declaration "interface" name: (identifier) @name) @definition.interface
+(class_declaration
+  (modifiers (class_modifier) @_enum (#eq? @_enum "enum"))
+  name: (identifier) @name) @definition.enum
+(class_declaration name: (identifier) @name) @definition.class
+(object_declaration name: (identifier) @name) @definition.module
+(companion_object name: (identifier) @name) @definition.module
+(function_declaration name: (identifier) @name) @definition.function
+(type_alias type: (identifier) @name) @definition.type
+
+(property_declaration
+  (modifiers (property_modifier) @_const (#eq? @_const "const"))
+  (variable_declaration (identifier) @name)) @definition.constant
+(source_file (property_declaration (variable_declaration (identifier) @name)) @definition.variable)
+(class_body (property_declaration (variable_declaration (identifier) @name)) @definition.property)
@@ -0,0 +1,16 @@
+; Lua definitions. Functions stored in a table (`function M.name()`,
+; `function M:name()`) have the table as container.
+
+(function_declaration name: (identifier) @name) @definition.function
+(function_declaration
+  name: (dot_index_expression table: (_) @receiver field: (identifier) @name)) @definition.function
+(function_declaration
+  name: (method_index_expression table: (_) @receiver method: (identifier) @name)) @definition.method
+
+(assignment_statement
+  (variable_list . name: (identifier) @name)
+  (expression_list . value: (function_definition))) @definition.function
+(assignment_statement
+  (variable_list
+    . name: (dot_index_expression table: (_) @receiver field: (identifier) @name))
+  (expression_list . value: (function_definition))) @definition.function
@@ -0,0 +1,20 @@
+; PHP definitions, `use` imports and require/include of string paths
+; (optionally prefixed with `__DIR__ .`)
+
+(function_definition name: (_) @name) @definition.function
+(method_declaration name: (_) @name) @definition.method
+(class_declaration name: (_) @name) @definition.class
+(interface_declaration name: (_) @name) @definition.interface
+(trait_declaration name: (_) @name) @definition.trait
+(enum_declaration name: (_) @name) @definition.enum
+(namespace_definition name: (_) @name) @definition.namespace
+
+(namespace_use_clause . [(name) (qualified_name)] @path) @import
+(require_expression [(string) (encapsed_string)] @path) @import
+(require_expression (binary_expression right: [(string) (encapsed_string)] @path)) @import
+(require_once_expression [(string) (encapsed_string)] @path) @import
+(require_once_expression (binary_expression right: [(string) (encapsed_string)] @path)) @import
+(include_expression [(string) (encapsed_string)] @path) @import
+(include_expression (binary_expression right: [(string) (encapsed_string)] @path)) @import
+(include_once_expression [(string) (encapsed_string)] @path) @import
+(include_once_expression (binary_expression right: [(string) (encapsed_string)] @path)) @import
@@ -0,0 +1,6 @@
+; Protocol Buffers definitions: messages, enums, services and their rpcs.
+
+(message (message_name (identifier) @name)) @definition.struct
+(enum (enum_name (identifier) @name)) @definition.enum
+(service (service_name (identifier) @name)) @definition.interface
+(rpc (rpc_name (identifier) @name)) @definition.method
@@ -0,0 +1,8 @@
+; Python definitions and imports
+
+(function_definition name: (_) @name) @definition.function
+(class_definition name: (_) @name) @definition.class
+
+(import_from_statement module_name: (_) @path) @import
+(import_statement name: (dotted_name) @path) @import
+(import_statement name: (aliased_import name: (dotted_name) @path)) @import
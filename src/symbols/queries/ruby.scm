@@ -0,0 +1,11 @@
+; Ruby definitions and `require_relative` imports
+
+(method name: (_) @name) @definition.method
+(singleton_method name: (_) @name) @definition.method
+(class name: (_) @name) @definition.class
+(module name: (_) @name) @definition.module
+
+(call
+  method: (identifier) @_method
+  arguments: (argument_list . (string (string_content) @path))
+  (#eq? @_method "require_relative")) @import
@@ -0,0 +1,14 @@
+; Rust definitions and imports. Only `mod name;` declarations import a file;
+; inline `mod name { ... }` blocks do not.
+
+(function_item name: (_) @name) @definition.function
+(struct_item name: (_) @name) @definition.struct
+(enum_item name: (_) @name) @definition.enum
+(trait_item name: (_) @name) @definition.trait
+(impl_item type: (_) @name) @definition.impl
+(type_item name: (_) @name) @definition.type
+(const_item name: (_) @name) @definition.constant
+(static_item name: (_) @name) @definition.constant
+
+(use_declaration argument: (_) @path) @import
+(mod_item name: (_) @path !body) @import
@@ -0,0 +1,16 @@
+; Scala definitions. Values are captured at the top level and in class,
+; trait and object bodies, not inside functions.
+
+(class_definition name: (identifier) @name) @definition.class
+(trait_definition name: (identifier) @name) @definition.trait
+(object_definition name: (identifier) @name) @definition.module
+(enum_definition name: (identifier) @name) @definition.enum
+(function_definition name: (identifier) @name) @definition.function
+(function_declaration name: (identifier) @name) @definition.function
+(type_definition name: (type_identifier) @name) @definition.type
+
+(compilation_unit (val_definition pattern: (identifier) @name) @definition.variable)
+(compilation_unit (var_definition pattern: (identifier) @name) @definition.variable)
+(template_body (val_definition pattern: (identifier) @name) @definition.property)
+(template_body (var_definition pattern: (identifier) @name) @definition.property)
+(template_body (val_declaration name: (identifier) @name) @definition.property)
@@ -0,0 +1,25 @@
+; SQL definitions: tables, views, functions, triggers, types, indexes and
+; sequences. A schema qualifier (`public.users`) is the container.
+
+(create_table
+  (object_reference schema: (identifier) @receiver name: (identifier) @name)) @definition.struct
+(create_table (object_reference name: (identifier) @name)) @definition.struct
+
+(create_view
+  (object_reference schema: (identifier) @receiver name: (identifier) @name)) @definition.type
+(create_view (object_reference name: (identifier) @name)) @definition.type
+(create_materialized_view
+  (object_reference schema: (identifier) @receiver name: (identifier) @name)) @definition.type
+(create_materialized_view (object_reference name: (identifier) @name)) @definition.type
+
+(create_function
+  (object_reference schema: (identifier) @receiver name: (identifier) @name)) @definition.function
+(create_function (object_reference name: (identifier) @name)) @definition.function
+(create_trigger (keyword_trigger) . (object_reference name: (identifier) @name)) @definition.function
+
+(create_type
+  (object_reference schema: (identifier) @receiver name: (identifier) @name)) @definition.type
+(create_type (object_reference name: (identifier) @name)) @definition.type
+
+(create_index column: (identifier) @name) @definition.variable
+(create_sequence (object_reference name: (identifier) @name)) @definition.variable
@@ -0,0 +1,15 @@
+; Swift definitions. `class_declaration` also declares structs, enums and
+; extensions; the more specific patterns come first and take precedence.
+; Name fields also hold parameter and return types, so names are matched
+; by node type.
+
+(class_declaration declaration_kind: "struct" name: (_) @name) @definition.struct
+(class_declaration declaration_kind: "enum" name: (_) @name) @definition.enum
+(class_declaration declaration_kind: "extension" name: (_) @name) @definition.impl
+(class_declaration name: (_) @name) @definition.class
+(protocol_declaration name: (_) @name) @definition.interface
+(protocol_function_declaration name: (simple_identifier) @name) @definition.method
+(init_declaration name: "init" @name) @definition.method
+(function_declaration name: (simple_identifier) @name) @definition.function
+(property_declaration name: (pattern) @name) @definition.property
+(typealias_declaration name: (type_identifier) @name) @definition.type
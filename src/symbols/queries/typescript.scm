@@ -0,0 +1,10 @@
+; inherits: javascript
+; TypeScript definitions on top of the JavaScript ones
+
+(interface_declaration name: (_) @name) @definition.interface
+(type_alias_declaration name: (_) @name) @definition.type
+(enum_declaration name: (_) @name) @definition.enum
+(module name: (_) @name) @definition.module
+(public_field_definition
+  name: (_) @name
+  value: [(arrow_function) (function_expression)]) @definition.method
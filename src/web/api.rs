@@ -1,18 +1,28 @@
 //! REST API handlers for Fast Code Search
 
 use super::WebState;
+use crate::dependencies::packages::split_package_filters;
+use crate::dependencies::{
+    CycleStats, DependencyDirection, DependencyLayers, GraphFormat, GraphLevel, PackageSummary,
+    UnresolvedImportStats, MAX_DEPENDENCY_DEPTH, MAX_DEPENDENCY_FILES,
+};
 use crate::diagnostics::{
     self, ConfigSummary, DiagnosticsQuery, ExtensionBreakdown, HealthStatus,
-    KeywordDiagnosticsResponse, KeywordIndexDiagnostics, TestResult, TestSummary,
+    KeywordDiagnosticsResponse, KeywordIndexDiagnostics, LanguageBreakdown, TestResult,
+    TestSummary,
+};
+use crate::search::{
+    CountMode, DeadCodeReport, FeedbackEvent, IndexingStatus, QueryKind, RankMode,
+    ScoreExplanation, SearchFacets, SearchMatch, SearchScope,
 };
-use crate::search::{IndexingStatus, RankMode};
+use crate::symbols::{split_language_filters, Language, OutlineNode, SymbolType};
 use axum::{
     extract::{
         ws::{Message, WebSocket},
         Query, State, WebSocketUpgrade,
     },
-    http::StatusCode,
-    response::IntoResponse,
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
     Json,
 };
 use futures_util::{SinkExt, StreamExt};
@@ -24,7 +34,9 @@ use std::time::Instant;
 /// Search query parameters
 #[derive(Debug, Deserialize)]
 pub struct SearchQuery {
-    /// The search query string
+    /// The search query string; `lang:rust,go` tokens restrict results to
+    /// files detected as those languages, `pkg:api,web` tokens to files of
+    /// those packages
     q: String,
     /// Maximum number of results (default: 50)
     #[serde(default = "default_max_results")]
@@ -47,6 +59,36 @@ pub struct SearchQuery {
     /// Number of context lines to return before and after each match (default: 0)
     #[serde(default)]
     context: usize,
+    /// Count mode: "exact" verifies every candidate and returns the true match
+    /// count plus facets; anything else reports only the returned results
+    #[serde(default)]
+    count: String,
+    /// Whether to include a per-result score breakdown
+    #[serde(default)]
+    explain: bool,
+    /// Whether to collapse byte-identical files into a single result
+    #[serde(default)]
+    dedup: bool,
+    /// Comma-separated symbol kinds to keep with `symbols=true`
+    /// (function, method, class, struct, trait, interface, enum, const, type, variable)
+    #[serde(default)]
+    kind: String,
+    /// With `symbols=true`, return only real definitions (no file-name matches)
+    #[serde(default)]
+    definition_only: bool,
+    /// With `symbols=true`, match symbol names fuzzily across camelCase and
+    /// snake_case words (`usrsvc` finds `UserService`)
+    #[serde(default)]
+    fuzzy: bool,
+    /// Only search this file and the files within `hops` imports of it
+    #[serde(default)]
+    near: Option<String>,
+    /// Import hops from `near` (default: 1)
+    #[serde(default = "default_dependency_depth")]
+    hops: usize,
+    /// With `near`: `deps` (files it imports, default) or `dependents`
+    #[serde(default)]
+    direction: Option<String>,
 }
 
 fn default_max_results() -> usize {
@@ -78,6 +120,58 @@ pub struct SearchResultJson {
     /// 1-based line number of the first context line (only present when context > 0)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub context_start_line: Option<usize>,
+    /// Score breakdown (only present when explain=true)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub explain: Option<ScoreExplanation>,
+    /// Other paths with identical content (only present when dedup=true and copies exist)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duplicates: Option<Vec<String>>,
+    /// Enclosing definition of a symbol match, e.g. "impl Parser"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub container: Option<String>,
+    /// Signature of a symbol match
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+    /// Doc comment of a symbol match
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub doc: Option<String>,
+    /// Kind of a symbol match
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub symbol_type: Option<SymbolType>,
+    /// Name of the package containing the file (absent outside any package)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub package: Option<String>,
+}
+
+impl From<SearchMatch> for SearchResultJson {
+    fn from(m: SearchMatch) -> Self {
+        Self {
+            file_path: m.file_path,
+            content: m.content,
+            line_number: m.line_number,
+            match_start: m.match_start,
+            match_end: m.match_end,
+            content_truncated: m.content_truncated,
+            score: m.score,
+            match_type: if m.is_reference {
+                "SYMBOL_REFERENCE"
+            } else if m.is_symbol {
+                "SYMBOL_DEFINITION"
+            } else {
+                "TEXT"
+            },
+            dependency_count: m.dependency_count,
+            context_lines: None,
+            context_start_line: None,
+            explain: None,
+            duplicates: None,
+            container: m.container,
+            signature: m.signature,
+            doc: m.doc,
+            symbol_type: m.symbol_type,
+            package: None,
+        }
+    }
 }
 
 /// Search response
@@ -97,6 +191,15 @@ pub struct SearchResponse {
     /// Files actually searched (may be less in fast mode)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub candidates_searched: Option<usize>,
+    /// True number of matches across all candidates (only with `count=exact`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_matches: Option<usize>,
+    /// Number of files with at least one match (only with `count=exact`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub matched_files: Option<usize>,
+    /// Match counts per extension, language, root and directory (only with `count=exact`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub facets: Option<SearchFacets>,
 }
 
 /// Index stats response
@@ -147,7 +250,9 @@ pub async fn search_handler(
     State(state): State<WebState>,
     Query(params): Query<SearchQuery>,
 ) -> Result<Json<SearchResponse>, (StatusCode, String)> {
-    let query = params.q.trim().to_string();
+    let (query, languages) = split_language_filters(params.q.trim())
+        .map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?;
+    let (query, packages) = split_package_filters(&query);
 
     if query.is_empty() {
         return Ok(Json(SearchResponse {
@@ -158,6 +263,9 @@ pub async fn search_handler(
             rank_mode: None,
             total_candidates: None,
             candidates_searched: None,
+            total_matches: None,
+            matched_files: None,
+            facets: None,
         }));
     }
 
@@ -167,6 +275,27 @@ pub async fn search_handler(
     let is_regex = params.regex;
     let symbols_only = params.symbols;
     let context_lines = params.context.min(MAX_CONTEXT_LINES);
+    let count_mode = CountMode::parse(&params.count);
+    let explain = params.explain;
+    let symbol_kinds = SymbolType::parse_kind_list(&params.kind)
+        .map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?;
+    let near_direction = match params.direction.as_deref() {
+        Some(direction) => direction
+            .parse::<DependencyDirection>()
+            .map_err(|e| (StatusCode::BAD_REQUEST, e))?,
+        None => DependencyDirection::Dependencies,
+    };
+    let near = params.near;
+    let hops = params.hops.min(MAX_DEPENDENCY_DEPTH);
+    let mut scope = SearchScope {
+        collapse_duplicates: params.dedup,
+        symbol_kinds,
+        definition_only: params.definition_only,
+        fuzzy: params.fuzzy,
+        languages,
+        within: None,
+        packages,
+    };
 
     // Parse ranking mode
     let rank_mode = match params.rank.to_lowercase().as_str() {
@@ -193,35 +322,60 @@ pub async fn search_handler(
             ),
         })?;
 
+        if let Some(near) = &near {
+            let file_id = engine
+                .find_file_id(near)
+                .ok_or_else(|| (StatusCode::NOT_FOUND, format!("File not found: {}", near)))?;
+            scope.within = Some(engine.dependency_neighborhood(file_id, near_direction, hops));
+        }
+
         // Choose search method based on flags
         let (matches, ranking_info) = if symbols_only {
             // Search only in discovered symbols
-            let m = engine
-                .search_symbols(&query, &include_patterns, &exclude_patterns, max_results)
+            let (m, info) = engine
+                .search_symbols_ranked(
+                    &query,
+                    &include_patterns,
+                    &exclude_patterns,
+                    max_results,
+                    &scope,
+                )
                 .map_err(|e| {
                     (
                         StatusCode::BAD_REQUEST,
                         format!("Invalid filter pattern: {}", e),
                     )
                 })?;
-            (m, None)
+            (m, Some(info))
         } else if is_regex {
             // Use regex search with optional path filtering
-            let m = engine
-                .search_regex(&query, &include_patterns, &exclude_patterns, max_results)
+            let (m, info) = engine
+                .search_regex_ranked(
+                    &query,
+                    &include_patterns,
+                    &exclude_patterns,
+                    max_results,
+                    &scope,
+                )
                 .map_err(|e| {
                     (
                         StatusCode::BAD_REQUEST,
                         format!("Invalid regex pattern: {}", e),
                     )
                 })?;
-            (m, None)
-        } else if include_patterns.is_empty() && exclude_patterns.is_empty() {
+            (m, Some(info))
+        } else if include_patterns.is_empty()
+            && exclude_patterns.is_empty()
+            && !scope.collapse_duplicates
+            && scope.languages.is_empty()
+            && scope.within.is_none()
+            && scope.packages.is_empty()
+        {
             // Plain text search with ranking
             let (m, info) = engine.search_ranked(&query, max_results, rank_mode);
             (m, Some(info))
         } else {
-            // Plain text search with path filtering and ranking
+            // Plain text search with path/language filtering and ranking
             let (m, info) = engine
                 .search_with_filter_ranked(
                     &query,
@@ -229,6 +383,7 @@ pub async fn search_handler(
                     &exclude_patterns,
                     max_results,
                     rank_mode,
+                    &scope,
                 )
                 .map_err(|e| {
                     (
@@ -239,6 +394,23 @@ pub async fn search_handler(
             (m, Some(info))
         };
 
+        // Exact counting re-verifies every candidate, so it only runs on request
+        let kind = if symbols_only {
+            QueryKind::Symbols
+        } else if is_regex {
+            QueryKind::Regex
+        } else {
+            QueryKind::Text
+        };
+        let counts = if count_mode == CountMode::Exact {
+            let counts = engine
+                .count_matches(&query, &include_patterns, &exclude_patterns, kind, &scope)
+                .map_err(|e| (StatusCode::BAD_REQUEST, format!("Invalid query: {}", e)))?;
+            Some(counts)
+        } else {
+            None
+        };
+
         // Evict fallback file bytes cached when the OS mmap limit was exceeded.
         // Without this, heap usage grows unboundedly across search requests on
         // large codebases where mmap is unavailable for some files.
@@ -276,22 +448,27 @@ pub async fn search_handler(
                     (None, None)
                 };
 
+                let duplicates = if scope.collapse_duplicates {
+                    Some(engine.duplicate_paths(m.file_id)).filter(|paths| !paths.is_empty())
+                } else {
+                    None
+                };
+
+                let explanation = if explain {
+                    engine.explain_match(&m, &query, kind, &scope, ranking_info.as_ref())
+                } else {
+                    None
+                };
+
+                let package = engine.file_package(m.file_id).map(|p| p.name.clone());
+
                 SearchResultJson {
-                    file_path: m.file_path,
-                    content: m.content,
-                    line_number: m.line_number,
-                    match_start: m.match_start,
-                    match_end: m.match_end,
-                    content_truncated: m.content_truncated,
-                    score: m.score,
-                    match_type: if m.is_symbol {
-                        "SYMBOL_DEFINITION"
-                    } else {
-                        "TEXT"
-                    },
-                    dependency_count: m.dependency_count,
                     context_lines: ctx_lines,
                     context_start_line: ctx_start,
+                    explain: explanation,
+                    duplicates,
+                    package,
+                    ..SearchResultJson::from(m)
                 }
             })
             .collect();
@@ -309,6 +486,9 @@ pub async fn search_handler(
                 .map(|r| format!("{:?}", r.mode).to_lowercase()),
             total_candidates: ranking_info.as_ref().map(|r| r.total_candidates),
             candidates_searched: ranking_info.as_ref().map(|r| r.candidates_searched),
+            total_matches: counts.as_ref().map(|c| c.total_matches),
+            matched_files: counts.as_ref().map(|c| c.matched_files),
+            facets: counts.map(|c| c.facets),
         }))
     })
     .await
@@ -445,14 +625,72 @@ pub async fn status_handler(
 pub struct DependencyQuery {
     /// File path to look up
     file: String,
+    /// Import hops to follow (default 1 = direct edges only)
+    #[serde(default = "default_dependency_depth")]
+    depth: usize,
+    /// `deps` or `dependents`; defaults to the endpoint's own direction
+    #[serde(default)]
+    direction: Option<String>,
+}
+
+fn default_dependency_depth() -> usize {
+    1
 }
 
 /// Dependency response
 #[derive(Debug, Serialize)]
 pub struct DependencyResponse {
     pub file: String,
+    /// Reached files, nearest first
     pub files: Vec<String>,
     pub count: usize,
+    /// Files grouped by import distance (only present when depth > 1)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub layers: Option<Vec<DependencyLayerResult>>,
+    /// Whether the file cap cut the walk short
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub truncated: bool,
+    /// Imports that matched several files and were not linked (dependencies only)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub ambiguous: Vec<AmbiguousImportResult>,
+}
+
+/// Files at one import distance from the queried file
+#[derive(Debug, Serialize)]
+pub struct DependencyLayerResult {
+    /// Import hops from the queried file
+    pub distance: usize,
+    pub files: Vec<String>,
+}
+
+/// An import that could refer to several indexed files
+#[derive(Debug, Serialize)]
+pub struct AmbiguousImportResult {
+    /// Import path as written in the source
+    pub import: String,
+    /// Line of the import statement (1-based)
+    pub line: usize,
+    /// Files the import could refer to
+    pub candidates: Vec<String>,
+}
+
+/// Display paths of each layer, nearest first
+fn dependency_layer_results(
+    engine: &crate::search::SearchEngine,
+    layers: &DependencyLayers,
+) -> Vec<DependencyLayerResult> {
+    layers
+        .layers
+        .iter()
+        .enumerate()
+        .map(|(i, ids)| DependencyLayerResult {
+            distance: i + 1,
+            files: ids
+                .iter()
+                .filter_map(|&id| engine.get_file_path(id))
+                .collect(),
+        })
+        .collect()
 }
 
 /// Get files that depend on (import) the specified file
@@ -460,6 +698,31 @@ pub async fn dependents_handler(
     State(state): State<WebState>,
     Query(params): Query<DependencyQuery>,
 ) -> Result<Json<DependencyResponse>, (StatusCode, String)> {
+    dependency_query(state, params, DependencyDirection::Dependents).await
+}
+
+/// Get files that the specified file depends on (imports)
+pub async fn dependencies_handler(
+    State(state): State<WebState>,
+    Query(params): Query<DependencyQuery>,
+) -> Result<Json<DependencyResponse>, (StatusCode, String)> {
+    dependency_query(state, params, DependencyDirection::Dependencies).await
+}
+
+/// Walk import edges from a file, `depth` hops in the requested direction
+async fn dependency_query(
+    state: WebState,
+    params: DependencyQuery,
+    default_direction: DependencyDirection,
+) -> Result<Json<DependencyResponse>, (StatusCode, String)> {
+    let direction = match params.direction.as_deref() {
+        Some(direction) => direction
+            .parse::<DependencyDirection>()
+            .map_err(|e| (StatusCode::BAD_REQUEST, e))?,
+        None => default_direction,
+    };
+    let depth = params.depth.clamp(1, MAX_DEPENDENCY_DEPTH);
+
     let engine = state.engine.clone();
     tokio::task::spawn_blocking(move || {
         let engine = engine.try_read().map_err(|e| match e {
@@ -480,18 +743,38 @@ pub async fn dependents_handler(
             )
         })?;
 
-        let dependent_ids = engine.get_dependents(file_id);
-        let files: Vec<String> = dependent_ids
-            .iter()
-            .filter_map(|&id| engine.get_file_path(id))
+        let walk = engine.dependency_layers(file_id, direction, depth, MAX_DEPENDENCY_FILES);
+        let files: Vec<String> = walk
+            .files()
+            .filter_map(|id| engine.get_file_path(id))
             .collect();
 
         let count = files.len();
 
+        let ambiguous = match direction {
+            DependencyDirection::Dependencies => engine
+                .get_ambiguous_imports(file_id)
+                .iter()
+                .map(|import| AmbiguousImportResult {
+                    import: import.path.clone(),
+                    line: import.line + 1,
+                    candidates: import
+                        .candidates
+                        .iter()
+                        .filter_map(|&id| engine.get_file_path(id))
+                        .collect(),
+                })
+                .collect(),
+            DependencyDirection::Dependents => Vec::new(),
+        };
+
         Ok(Json(DependencyResponse {
             file: params.file,
             files,
             count,
+            layers: (depth > 1).then(|| dependency_layer_results(&engine, &walk)),
+            truncated: walk.truncated,
+            ambiguous,
         }))
     })
     .await
@@ -503,13 +786,65 @@ pub async fn dependents_handler(
     })?
 }
 
-/// Get files that the specified file depends on (imports)
-pub async fn dependencies_handler(
+/// Query parameters for the impact endpoint
+#[derive(Debug, Deserialize)]
+pub struct ImpactQuery {
+    /// File whose change is being assessed
+    file: String,
+    /// Maximum import hops to follow (default and maximum 64)
+    #[serde(default)]
+    depth: Option<usize>,
+    /// Maximum number of affected files to return (default 1000)
+    #[serde(default = "default_impact_max")]
+    max: usize,
+}
+
+fn default_impact_max() -> usize {
+    1000
+}
+
+/// Impact (blast radius) response
+#[derive(Debug, Serialize)]
+pub struct ImpactResponse {
+    pub file: String,
+    /// Transitive dependents grouped by import distance
+    pub layers: Vec<DependencyLayerResult>,
+    /// Number of affected files
+    pub total_affected: usize,
+    /// Largest import distance reached
+    pub max_distance: usize,
+    /// Whether the `max` cap cut the walk short
+    pub truncated: bool,
+    /// Affected files that the file also imports, directly or transitively (import cycles)
+    pub cycles: Vec<String>,
+    /// Whether the `max` cap cut the walk over the file's imports short, so
+    /// `cycles` may be incomplete
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub cycles_truncated: bool,
+    pub elapsed_ms: f64,
+}
+
+/// Who is affected if this file changes: the transitive closure of its
+/// dependents as a layered breadth-first walk
+pub async fn impact_handler(
     State(state): State<WebState>,
-    Query(params): Query<DependencyQuery>,
-) -> Result<Json<DependencyResponse>, (StatusCode, String)> {
+    Query(params): Query<ImpactQuery>,
+) -> Result<Json<ImpactResponse>, (StatusCode, String)> {
+    if params.file.trim().is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "Missing file parameter".to_string(),
+        ));
+    }
+    let depth = params
+        .depth
+        .unwrap_or(MAX_DEPENDENCY_DEPTH)
+        .clamp(1, MAX_DEPENDENCY_DEPTH);
+    let max = params.max.clamp(1, MAX_DEPENDENCY_FILES);
+
     let engine = state.engine.clone();
     tokio::task::spawn_blocking(move || {
+        let start_time = Instant::now();
         let engine = engine.try_read().map_err(|e| match e {
             std::sync::TryLockError::WouldBlock => (
                 StatusCode::SERVICE_UNAVAILABLE,
@@ -527,19 +862,579 @@ pub async fn dependencies_handler(
                 format!("File not found: {}", params.file),
             )
         })?;
+        let file = engine.get_file_path(file_id).unwrap_or(params.file);
 
-        let dependency_ids = engine.get_dependencies(file_id);
-        let files: Vec<String> = dependency_ids
+        let impact = engine.impact(file_id, depth, max);
+        let layers = dependency_layer_results(&engine, &impact.dependents);
+        let cycles = impact
+            .cycle
             .iter()
             .filter_map(|&id| engine.get_file_path(id))
             .collect();
 
-        let count = files.len();
+        Ok(Json(ImpactResponse {
+            file,
+            total_affected: impact.dependents.len(),
+            max_distance: layers.len(),
+            layers,
+            truncated: impact.dependents.truncated,
+            cycles,
+            cycles_truncated: impact.cycle_truncated,
+            elapsed_ms: start_time.elapsed().as_secs_f64() * 1000.0,
+        }))
+    })
+    .await
+    .map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Task join error: {}", e),
+        )
+    })?
+}
 
-        Ok(Json(DependencyResponse {
+/// Query parameters for the graph export endpoint
+#[derive(Debug, Deserialize)]
+pub struct GraphQuery {
+    /// `dot`, `graphml` or `json` (default)
+    #[serde(default)]
+    format: Option<String>,
+    /// Only include files whose path starts with this prefix
+    #[serde(default)]
+    prefix: Option<String>,
+    /// Node granularity: `file` (default), `directory` or `package`
+    #[serde(default)]
+    level: Option<String>,
+}
+
+/// Export the import graph as Graphviz DOT, GraphML or JSON
+pub async fn graph_handler(
+    State(state): State<WebState>,
+    Query(params): Query<GraphQuery>,
+) -> Result<Response, (StatusCode, String)> {
+    let format = match params.format.as_deref() {
+        Some(format) => format
+            .parse::<GraphFormat>()
+            .map_err(|e| (StatusCode::BAD_REQUEST, e))?,
+        None => GraphFormat::Json,
+    };
+    let level = match params.level.as_deref() {
+        Some(level) => level
+            .parse::<GraphLevel>()
+            .map_err(|e| (StatusCode::BAD_REQUEST, e))?,
+        None => GraphLevel::File,
+    };
+
+    let engine = state.engine.clone();
+    tokio::task::spawn_blocking(move || {
+        let engine = engine.try_read().map_err(|e| match e {
+            std::sync::TryLockError::WouldBlock => (
+                StatusCode::SERVICE_UNAVAILABLE,
+                "Index is currently being updated, please try again shortly".to_string(),
+            ),
+            std::sync::TryLockError::Poisoned(e) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to acquire engine read lock: {}", e),
+            ),
+        })?;
+
+        let graph = engine.dependency_graph(params.prefix.as_deref(), level);
+        Ok(match format {
+            GraphFormat::Dot => (
+                [(header::CONTENT_TYPE, "text/vnd.graphviz; charset=utf-8")],
+                graph.to_dot(),
+            )
+                .into_response(),
+            GraphFormat::GraphMl => (
+                [(
+                    header::CONTENT_TYPE,
+                    "application/graphml+xml; charset=utf-8",
+                )],
+                graph.to_graphml(),
+            )
+                .into_response(),
+            GraphFormat::Json => Json(graph).into_response(),
+        })
+    })
+    .await
+    .map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Task join error: {}", e),
+        )
+    })?
+}
+
+/// Import cycles listed by `/api/diagnostics`
+const DIAGNOSTICS_IMPORT_CYCLES: usize = 10;
+
+/// Query parameters for the import cycles endpoint
+#[derive(Debug, Deserialize)]
+pub struct CyclesQuery {
+    /// Only report cycles with at least one file whose path starts with this prefix
+    #[serde(default)]
+    prefix: Option<String>,
+    /// Maximum number of cycles listed, largest first (default: 100)
+    #[serde(default = "default_cycles_max")]
+    max: usize,
+}
+
+fn default_cycles_max() -> usize {
+    100
+}
+
+/// Import cycles response
+#[derive(Debug, Serialize)]
+pub struct CyclesResponse {
+    #[serde(flatten)]
+    pub stats: CycleStats,
+    /// Whether `max` left cycles out of `largest_cycles`
+    pub truncated: bool,
+    pub elapsed_ms: f64,
+}
+
+/// Report import cycles (strongly connected components of the import graph)
+/// with the lines of the imports that form them
+pub async fn cycles_handler(
+    State(state): State<WebState>,
+    Query(params): Query<CyclesQuery>,
+) -> Result<Json<CyclesResponse>, (StatusCode, String)> {
+    let engine = state.engine.clone();
+    tokio::task::spawn_blocking(move || {
+        let start_time = Instant::now();
+        let engine = engine.try_read().map_err(|e| match e {
+            std::sync::TryLockError::WouldBlock => (
+                StatusCode::SERVICE_UNAVAILABLE,
+                "Index is currently being updated, please try again shortly".to_string(),
+            ),
+            std::sync::TryLockError::Poisoned(e) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to acquire engine read lock: {}", e),
+            ),
+        })?;
+
+        let stats = engine.cycle_stats(params.prefix.as_deref(), params.max);
+        Ok(Json(CyclesResponse {
+            truncated: stats.largest_cycles.len() < stats.cycles,
+            stats,
+            elapsed_ms: start_time.elapsed().as_secs_f64() * 1000.0,
+        }))
+    })
+    .await
+    .map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Task join error: {}", e),
+        )
+    })?
+}
+
+/// Third-party modules listed by `/api/diagnostics`
+const DIAGNOSTICS_EXTERNAL_MODULES: usize = 20;
+
+/// Query parameters for the unresolved imports endpoint
+#[derive(Debug, Deserialize)]
+pub struct UnresolvedQuery {
+    /// Only report files whose path starts with this prefix
+    #[serde(default)]
+    prefix: Option<String>,
+    /// Maximum number of files listed (default: 100)
+    #[serde(default = "default_unresolved_max")]
+    max: usize,
+    /// Maximum number of third-party modules listed (default: 50)
+    #[serde(default = "default_unresolved_modules")]
+    modules: usize,
+}
+
+fn default_unresolved_max() -> usize {
+    100
+}
+
+fn default_unresolved_modules() -> usize {
+    50
+}
+
+/// An import that matched no indexed file
+#[derive(Debug, Serialize)]
+pub struct UnresolvedImportResult {
+    /// Import path as written in the source
+    pub import: String,
+    /// Line of the import statement (1-based)
+    pub line: usize,
+    /// Third-party module the import refers to (absent for project-local imports)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub module: Option<String>,
+}
+
+/// Unresolved imports of one file
+#[derive(Debug, Serialize)]
+pub struct UnresolvedFileResult {
+    pub file: String,
+    pub language: String,
+    pub imports: Vec<UnresolvedImportResult>,
+}
+
+/// Unresolved imports response
+#[derive(Debug, Serialize)]
+pub struct UnresolvedResponse {
+    #[serde(flatten)]
+    pub stats: UnresolvedImportStats,
+    /// Files with unresolved imports, sorted by path
+    pub unresolved: Vec<UnresolvedFileResult>,
+    /// Whether `max` left files out of `unresolved`
+    pub truncated: bool,
+    pub elapsed_ms: f64,
+}
+
+/// Report imports that matched no indexed file, per file and language, with
+/// the third-party modules they refer to
+pub async fn unresolved_handler(
+    State(state): State<WebState>,
+    Query(params): Query<UnresolvedQuery>,
+) -> Result<Json<UnresolvedResponse>, (StatusCode, String)> {
+    let engine = state.engine.clone();
+    tokio::task::spawn_blocking(move || {
+        let start_time = Instant::now();
+        let engine = engine.try_read().map_err(|e| match e {
+            std::sync::TryLockError::WouldBlock => (
+                StatusCode::SERVICE_UNAVAILABLE,
+                "Index is currently being updated, please try again shortly".to_string(),
+            ),
+            std::sync::TryLockError::Poisoned(e) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to acquire engine read lock: {}", e),
+            ),
+        })?;
+
+        let files = engine.files_with_unresolved_imports(params.prefix.as_deref());
+        let stats = engine.unresolved_import_stats(files.iter().map(|&(id, _)| id), params.modules);
+        let unresolved: Vec<UnresolvedFileResult> = files
+            .iter()
+            .take(params.max)
+            .map(|(id, file)| UnresolvedFileResult {
+                file: file.clone(),
+                language: engine
+                    .file_store
+                    .get_language(*id)
+                    .map_or("Other", Language::name)
+                    .to_string(),
+                imports: engine
+                    .get_unresolved_imports(*id)
+                    .iter()
+                    .map(|import| UnresolvedImportResult {
+                        import: import.path.clone(),
+                        line: import.line + 1,
+                        module: import.module.clone(),
+                    })
+                    .collect(),
+            })
+            .collect();
+
+        Ok(Json(UnresolvedResponse {
+            truncated: unresolved.len() < files.len(),
+            stats,
+            unresolved,
+            elapsed_ms: start_time.elapsed().as_secs_f64() * 1000.0,
+        }))
+    })
+    .await
+    .map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Task join error: {}", e),
+        )
+    })?
+}
+
+/// Query parameters for the packages endpoint
+#[derive(Debug, Deserialize)]
+pub struct PackagesQuery {
+    /// Only list packages whose manifest path starts with this prefix
+    #[serde(default)]
+    prefix: Option<String>,
+}
+
+/// Packages response
+#[derive(Debug, Serialize)]
+pub struct PackagesResponse {
+    pub packages: Vec<PackageSummary>,
+    pub elapsed_ms: f64,
+}
+
+/// List the packages of the indexed files with their declared dependencies
+pub async fn packages_handler(
+    State(state): State<WebState>,
+    Query(params): Query<PackagesQuery>,
+) -> Result<Json<PackagesResponse>, (StatusCode, String)> {
+    let engine = state.engine.clone();
+    tokio::task::spawn_blocking(move || {
+        let start_time = Instant::now();
+        let engine = engine.try_read().map_err(|e| match e {
+            std::sync::TryLockError::WouldBlock => (
+                StatusCode::SERVICE_UNAVAILABLE,
+                "Index is currently being updated, please try again shortly".to_string(),
+            ),
+            std::sync::TryLockError::Poisoned(e) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to acquire engine read lock: {}", e),
+            ),
+        })?;
+
+        Ok(Json(PackagesResponse {
+            packages: engine.packages(params.prefix.as_deref()),
+            elapsed_ms: start_time.elapsed().as_secs_f64() * 1000.0,
+        }))
+    })
+    .await
+    .map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Task join error: {}", e),
+        )
+    })?
+}
+
+/// Query parameters for the dead code endpoint
+#[derive(Debug, Deserialize)]
+pub struct DeadCodeQuery {
+    /// Only report files whose path starts with this prefix
+    #[serde(default)]
+    prefix: Option<String>,
+    /// Also report exported symbols whose name appears in no other file (default: true)
+    #[serde(default = "default_true")]
+    symbols: bool,
+    /// Maximum number of directories listed, most candidates first (default: 100)
+    #[serde(default = "default_dead_code_max")]
+    max: usize,
+}
+
+fn default_dead_code_max() -> usize {
+    100
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Dead code response
+#[derive(Debug, Serialize)]
+pub struct DeadCodeResponse {
+    #[serde(flatten)]
+    pub report: DeadCodeReport,
+    /// Whether `max` left directories out
+    pub truncated: bool,
+    pub elapsed_ms: f64,
+}
+
+/// List dead code candidates: files no indexed file imports (entry points
+/// excluded) and exported symbols nothing references, grouped by directory
+pub async fn dead_code_handler(
+    State(state): State<WebState>,
+    Query(params): Query<DeadCodeQuery>,
+) -> Result<Json<DeadCodeResponse>, (StatusCode, String)> {
+    let engine = state.engine.clone();
+    tokio::task::spawn_blocking(move || {
+        let start_time = Instant::now();
+        let engine = engine.try_read().map_err(|e| match e {
+            std::sync::TryLockError::WouldBlock => (
+                StatusCode::SERVICE_UNAVAILABLE,
+                "Index is currently being updated, please try again shortly".to_string(),
+            ),
+            std::sync::TryLockError::Poisoned(e) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to acquire engine read lock: {}", e),
+            ),
+        })?;
+
+        let report = engine.dead_code(params.prefix.as_deref(), params.symbols, params.max);
+        let listed: usize = report
+            .directories
+            .iter()
+            .map(|d| d.files.len() + d.symbols.len())
+            .sum();
+        Ok(Json(DeadCodeResponse {
+            truncated: listed < report.unused_files + report.unused_symbols,
+            report,
+            elapsed_ms: start_time.elapsed().as_secs_f64() * 1000.0,
+        }))
+    })
+    .await
+    .map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Task join error: {}", e),
+        )
+    })?
+}
+
+/// Query parameters for the references endpoint
+#[derive(Debug, Deserialize)]
+pub struct ReferencesQuery {
+    /// Symbol name (exact, case-sensitive)
+    symbol: String,
+    /// File defining the symbol; when omitted every definition of the name is used
+    #[serde(default)]
+    file: Option<String>,
+    /// Maximum number of results (default: 50)
+    #[serde(default = "default_max_results")]
+    max: usize,
+}
+
+/// References response
+#[derive(Debug, Serialize)]
+pub struct ReferencesResponse {
+    pub symbol: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+    pub results: Vec<SearchResultJson>,
+    pub total_results: usize,
+    pub elapsed_ms: f64,
+}
+
+/// Find usages of a symbol, ranked by proximity to its definition
+pub async fn references_handler(
+    State(state): State<WebState>,
+    Query(params): Query<ReferencesQuery>,
+) -> Result<Json<ReferencesResponse>, (StatusCode, String)> {
+    let symbol = params.symbol.trim().to_string();
+    if symbol.is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "Missing symbol parameter".to_string(),
+        ));
+    }
+    let max_results = params.max.clamp(1, 1000);
+
+    let engine = state.engine.clone();
+    tokio::task::spawn_blocking(move || {
+        let start_time = Instant::now();
+        let engine = engine.try_read().map_err(|e| match e {
+            std::sync::TryLockError::WouldBlock => (
+                StatusCode::SERVICE_UNAVAILABLE,
+                "Index is currently being updated, please try again shortly".to_string(),
+            ),
+            std::sync::TryLockError::Poisoned(e) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to acquire engine read lock: {}", e),
+            ),
+        })?;
+
+        let matches = engine
+            .find_references(&symbol, params.file.as_deref(), max_results)
+            .map_err(|e| (StatusCode::NOT_FOUND, e.to_string()))?;
+        let results: Vec<SearchResultJson> =
+            matches.into_iter().map(SearchResultJson::from).collect();
+        let total_results = results.len();
+
+        Ok(Json(ReferencesResponse {
+            symbol,
             file: params.file,
-            files,
-            count,
+            results,
+            total_results,
+            elapsed_ms: start_time.elapsed().as_secs_f64() * 1000.0,
+        }))
+    })
+    .await
+    .map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Task join error: {}", e),
+        )
+    })?
+}
+
+/// Query parameters for the definition endpoint
+#[derive(Debug, Deserialize)]
+pub struct DefinitionQuery {
+    /// File containing the identifier
+    file: String,
+    /// Line number (1-based)
+    line: usize,
+    /// Column (1-based, in UTF-16 code units like LSP positions)
+    col: usize,
+    /// Maximum number of candidates (default: 50)
+    #[serde(default = "default_max_results")]
+    max: usize,
+}
+
+/// A go-to-definition candidate
+#[derive(Debug, Serialize)]
+pub struct DefinitionResultJson {
+    #[serde(flatten)]
+    pub result: SearchResultJson,
+    /// How the definition was reached: same_file, imported, same_directory or global
+    pub scope: &'static str,
+    /// 1-based column of the defined name on `line_number`, in UTF-16 code units
+    pub column: usize,
+}
+
+/// Definition response
+#[derive(Debug, Serialize)]
+pub struct DefinitionResponse {
+    pub file: String,
+    pub line: usize,
+    pub col: usize,
+    /// Identifier found at the requested position
+    pub identifier: String,
+    pub results: Vec<DefinitionResultJson>,
+    pub total_results: usize,
+    pub elapsed_ms: f64,
+}
+
+/// Go to definition of the identifier at a file position
+pub async fn definition_handler(
+    State(state): State<WebState>,
+    Query(params): Query<DefinitionQuery>,
+) -> Result<Json<DefinitionResponse>, (StatusCode, String)> {
+    if params.file.trim().is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "Missing file parameter".to_string(),
+        ));
+    }
+    if params.line == 0 || params.col == 0 {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "line and col are 1-based and must be positive".to_string(),
+        ));
+    }
+    let max_results = params.max.clamp(1, 1000);
+
+    let engine = state.engine.clone();
+    tokio::task::spawn_blocking(move || {
+        let start_time = Instant::now();
+        let engine = engine.try_read().map_err(|e| match e {
+            std::sync::TryLockError::WouldBlock => (
+                StatusCode::SERVICE_UNAVAILABLE,
+                "Index is currently being updated, please try again shortly".to_string(),
+            ),
+            std::sync::TryLockError::Poisoned(e) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to acquire engine read lock: {}", e),
+            ),
+        })?;
+
+        let lookup = engine
+            .find_definition(&params.file, params.line, params.col, max_results)
+            .map_err(|e| (StatusCode::NOT_FOUND, e.to_string()))?;
+        let results: Vec<DefinitionResultJson> = lookup
+            .candidates
+            .into_iter()
+            .map(|c| DefinitionResultJson {
+                result: SearchResultJson::from(c.result),
+                scope: c.scope.as_str(),
+                column: c.column,
+            })
+            .collect();
+        let total_results = results.len();
+
+        Ok(Json(DefinitionResponse {
+            file: params.file,
+            line: params.line,
+            col: params.col,
+            identifier: lookup.identifier,
+            results,
+            total_results,
+            elapsed_ms: start_time.elapsed().as_secs_f64() * 1000.0,
         }))
     })
     .await
@@ -626,6 +1521,81 @@ pub async fn file_handler(
     })?
 }
 
+/// Query parameters for file outline endpoint
+#[derive(Debug, Deserialize)]
+pub struct OutlineQuery {
+    /// File path to outline
+    file: String,
+}
+
+/// File outline response
+#[derive(Debug, Serialize)]
+pub struct OutlineResponse {
+    pub file: String,
+    /// Top-level symbols, each with its nested symbols
+    pub symbols: Vec<OutlineNode>,
+    /// Number of symbols in the tree, at any depth
+    pub total_symbols: usize,
+    pub elapsed_ms: f64,
+}
+
+/// Return a file's symbols as a tree (kinds, line ranges, signatures),
+/// served from the symbol cache without re-parsing
+pub async fn outline_handler(
+    State(state): State<WebState>,
+    Query(params): Query<OutlineQuery>,
+) -> Result<Json<OutlineResponse>, (StatusCode, String)> {
+    if params.file.trim().is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "Missing file parameter".to_string(),
+        ));
+    }
+
+    let engine = state.engine.clone();
+    tokio::task::spawn_blocking(move || {
+        let start_time = Instant::now();
+        let engine = engine.try_read().map_err(|e| match e {
+            std::sync::TryLockError::WouldBlock => (
+                StatusCode::SERVICE_UNAVAILABLE,
+                "Index is currently being updated, please try again shortly".to_string(),
+            ),
+            std::sync::TryLockError::Poisoned(e) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to acquire engine read lock: {}", e),
+            ),
+        })?;
+
+        let file_id = engine.find_file_id(&params.file).ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                format!("File not found: {}", params.file),
+            )
+        })?;
+        let file = engine.get_file_path(file_id).unwrap_or(params.file);
+        let symbols = engine.file_outline(file_id);
+
+        fn count(nodes: &[OutlineNode]) -> usize {
+            nodes.iter().map(|n| 1 + count(&n.children)).sum()
+        }
+        let total_symbols = count(&symbols);
+
+        Ok(Json(OutlineResponse {
+            file,
+            symbols,
+            total_symbols,
+            elapsed_ms: start_time.elapsed().as_secs_f64() * 1000.0,
+        }))
+    })
+    .await
+    .map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Task join error: {}", e),
+        )
+    })?
+}
+
 /// Query parameters for context (lines around a hit) endpoint
 #[derive(Debug, Deserialize)]
 pub struct ContextQuery {
@@ -720,6 +1690,68 @@ pub async fn context_handler(
     })?
 }
 
+/// Feedback response
+#[derive(Debug, Serialize)]
+pub struct FeedbackResponse {
+    /// False when `file_path` is not an indexed file (the event is ignored)
+    pub recorded: bool,
+    /// Total feedback events known to the server (including replayed ones)
+    pub events: usize,
+}
+
+/// Record which search result a user opened (click-feedback learning-to-rank)
+pub async fn feedback_handler(
+    State(state): State<WebState>,
+    Json(event): Json<FeedbackEvent>,
+) -> Result<Json<FeedbackResponse>, (StatusCode, String)> {
+    if event.file_path.trim().is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "file_path must not be empty".to_string(),
+        ));
+    }
+
+    let engine = state.engine.clone();
+    tokio::task::spawn_blocking(move || {
+        let engine = engine.try_read().map_err(|e| match e {
+            std::sync::TryLockError::WouldBlock => (
+                StatusCode::SERVICE_UNAVAILABLE,
+                "Index is currently being updated, please try again shortly".to_string(),
+            ),
+            std::sync::TryLockError::Poisoned(e) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to acquire engine read lock: {}", e),
+            ),
+        })?;
+
+        let Some(feedback) = engine.feedback.clone() else {
+            return Err((
+                StatusCode::NOT_FOUND,
+                "Click feedback is disabled (set [feedback] enabled = true)".to_string(),
+            ));
+        };
+
+        let recorded = engine.record_feedback(event).map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to record feedback: {}", e),
+            )
+        })?;
+
+        Ok(Json(FeedbackResponse {
+            recorded,
+            events: feedback.event_count(),
+        }))
+    })
+    .await
+    .map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Task join error: {}", e),
+        )
+    })?
+}
+
 /// WebSocket upgrade handler for progress streaming
 pub async fn ws_progress_handler(
     State(state): State<WebState>,
@@ -904,6 +1936,7 @@ pub async fn diagnostics_handler(
 
         // Build extension breakdown
         let mut ext_map: HashMap<String, (usize, u64)> = HashMap::new();
+        let mut language_map: HashMap<&'static str, (usize, u64)> = HashMap::new();
         let mut all_file_paths: Vec<(u32, String)> = Vec::new();
 
         for file_id in 0..engine.file_store.len() as u32 {
@@ -918,10 +1951,19 @@ pub async fn diagnostics_handler(
                     .unwrap_or("(none)")
                     .to_lowercase();
 
+                // Use len_if_mapped() to avoid triggering lazy loading during diagnostics
+                let bytes = mapped_file.len_if_mapped().unwrap_or(0) as u64;
                 let entry = ext_map.entry(ext).or_insert((0, 0));
                 entry.0 += 1;
-                // Use len_if_mapped() to avoid triggering lazy loading during diagnostics
-                entry.1 += mapped_file.len_if_mapped().unwrap_or(0) as u64;
+                entry.1 += bytes;
+
+                let language = engine
+                    .file_store
+                    .get_language(file_id)
+                    .map_or("Other", Language::name);
+                let entry = language_map.entry(language).or_insert((0, 0));
+                entry.0 += 1;
+                entry.1 += bytes;
             }
         }
 
@@ -934,9 +1976,19 @@ pub async fn diagnostics_handler(
                 total_bytes: bytes,
             })
             .collect();
-        files_by_extension.sort_by(|a, b| b.count.cmp(&a.count));
+        files_by_extension.sort_by_key(|b| std::cmp::Reverse(b.count));
         files_by_extension.truncate(20); // Top 20 extensions
 
+        let mut files_by_language: Vec<LanguageBreakdown> = language_map
+            .into_iter()
+            .map(|(language, (count, bytes))| LanguageBreakdown {
+                language: language.to_string(),
+                count,
+                total_bytes: bytes,
+            })
+            .collect();
+        files_by_language.sort_by_key(|b| std::cmp::Reverse(b.count));
+
         // Sample random files for display
         let mut rng = rand::rng();
         let sample_count_actual = sample_count.min(all_file_paths.len());
@@ -1203,7 +2255,18 @@ pub async fn diagnostics_handler(
                 total_size_human: diagnostics::format_bytes(stats.total_size),
                 num_trigrams: stats.num_trigrams,
                 dependency_edges: stats.dependency_edges,
+                ambiguous_imports: engine.dependency_index.total_ambiguous_imports(),
+                duplicates: engine.duplicate_stats(),
+                import_cycles: engine.cycle_stats(None, DIAGNOSTICS_IMPORT_CYCLES),
+                unresolved_imports: engine.unresolved_import_stats(
+                    engine
+                        .files_with_unresolved_imports(None)
+                        .into_iter()
+                        .map(|(id, _)| id),
+                    DIAGNOSTICS_EXTERNAL_MODULES,
+                ),
                 files_by_extension,
+                files_by_language,
                 sample_files,
             },
             self_tests,
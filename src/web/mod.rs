@@ -7,7 +7,7 @@ use axum::{
     body::Body,
     extract::State,
     http::{header, Response, StatusCode},
-    routing::get,
+    routing::{get, post},
     Router,
 };
 use rust_embed::RustEmbed;
@@ -62,8 +62,18 @@ pub fn create_router(
         .route("/api/diagnostics", get(api::diagnostics_handler))
         .route("/api/dependents", get(api::dependents_handler))
         .route("/api/dependencies", get(api::dependencies_handler))
+        .route("/api/impact", get(api::impact_handler))
+        .route("/api/graph", get(api::graph_handler))
+        .route("/api/cycles", get(api::cycles_handler))
+        .route("/api/unresolved", get(api::unresolved_handler))
+        .route("/api/packages", get(api::packages_handler))
+        .route("/api/dead-code", get(api::dead_code_handler))
+        .route("/api/references", get(api::references_handler))
+        .route("/api/definition", get(api::definition_handler))
         .route("/api/file", get(api::file_handler))
+        .route("/api/outline", get(api::outline_handler))
         .route("/api/context", get(api::context_handler))
+        .route("/api/feedback", post(api::feedback_handler))
         // WebSocket for progress streaming
         .route("/ws/progress", get(api::ws_progress_handler))
         // Static files
@@ -781,7 +781,7 @@ async function performSearch() {
 
         const groupedResults = groupResultsByFile(data.results);
 
-        resultsContainer.innerHTML = groupedResults.map(group => {
+        resultsContainer.innerHTML = groupedResults.map((group, groupIdx) => {
             const firstHit = group.hits[0];
             const depCount = Math.max(...group.hits.map(hit => hit.dependency_count || 0));
             const lang = hljsLangForPath(group.filePath);
@@ -845,7 +845,8 @@ async function performSearch() {
                         <div class="px-4 py-1.5 flex justify-between items-center" style="background:#f8f4df;border-bottom:1px solid #e3dec8">
                             <div class="flex items-center gap-2 min-w-0">
                                 <span class="font-label text-xs" style="color:#7a785f;flex-shrink:0">line ${result.line_number}</span>
-                                <span style="${typeBadgeStyle};padding:2px 6px;font-size:10px;font-family:'JetBrains Mono',monospace">${matchType.text}</span>
+                                <span style="${typeBadgeStyle};padding:2px 6px;font-size:10px;font-family:'JetBrains Mono',monospace${result.signature ? ';cursor:help' : ''}"${result.signature ? ` title="${escapeHtml(result.signature + (result.doc ? '\n\n' + result.doc : ''))}"` : ''}>${matchType.text}</span>
+                                ${result.container ? `<span class="font-label text-xs truncate" style="color:#7a785f">in ${escapeHtml(result.container)}</span>` : ''}
                             </div>
                             <div class="flex items-center gap-3 flex-shrink-0">
                                 <span style="cursor:help;font-family:'JetBrains Mono',monospace;font-size:10px;color:#7a785f;text-transform:uppercase"
@@ -856,6 +857,7 @@ async function performSearch() {
                                     style="font-size:18px;cursor:pointer;color:#7a785f;background:none;border:none;padding:0"
                                     data-file-path="${escapeHtml(result.file_path)}"
                                     data-line-number="${result.line_number}"
+                                    data-rank="${groupIdx}"
                                     title="View full file at this line">open_in_new</button>
                             </div>
                         </div>
@@ -884,6 +886,7 @@ async function performSearch() {
                                 style="font-size:18px;cursor:pointer;color:#7a785f;background:none;border:none;padding:0"
                                 data-file-path="${escapeHtml(group.filePath)}"
                                 data-line-number="${firstHit.line_number}"
+                                data-rank="${groupIdx}"
                                 title="View full file">open_in_new</button>
                         </div>
                     </div>
@@ -923,7 +926,11 @@ async function performSearch() {
         resultsContainer.querySelectorAll('.view-file-btn').forEach(btn => {
             const filePath = btn.dataset.filePath;
             const lineNumber = parseInt(btn.dataset.lineNumber, 10);
-            btn.addEventListener('click', () => showFileModal(filePath, lineNumber));
+            const rank = parseInt(btn.dataset.rank, 10) || 0;
+            btn.addEventListener('click', () => {
+                sendClickFeedback(query, filePath, rank);
+                showFileModal(filePath, lineNumber);
+            });
             btn.addEventListener('mouseenter', () => showContextTooltip(btn, filePath, lineNumber));
             btn.addEventListener('mouseleave', hideContextTooltip);
         });
@@ -936,6 +943,19 @@ async function performSearch() {
 
 const debouncedSearch = debounce(performSearch, DEBOUNCE_MS);
 
+/**
+ * Report an opened result so the server can learn ranking boosts.
+ * Fire-and-forget: feedback may be disabled server-side (404), which is fine.
+ */
+function sendClickFeedback(query, filePath, rank) {
+    fetch(`${API_BASE}/api/feedback`, {
+        method: 'POST',
+        headers: { 'Content-Type': 'application/json' },
+        body: JSON.stringify({ query, file_path: filePath, rank, clicked: true }),
+        keepalive: true,
+    }).catch(() => {});
+}
+
 function groupResultsByFile(results) {
     const groups = [];
     const indexByPath = new Map();
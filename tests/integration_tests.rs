@@ -156,6 +156,9 @@ async fn test_grpc_search_finds_rust_function() -> Result<()> {
         exclude_paths: vec![],
         is_regex: false,
         symbols_only: false,
+        kinds: vec![],
+        definition_only: false,
+        fuzzy: false,
     };
 
     let mut stream = client.search(request).await?.into_inner();
@@ -192,6 +195,9 @@ async fn test_grpc_search_finds_python_function() -> Result<()> {
         exclude_paths: vec![],
         is_regex: false,
         symbols_only: false,
+        kinds: vec![],
+        definition_only: false,
+        fuzzy: false,
     };
 
     let mut stream = client.search(request).await?.into_inner();
@@ -224,6 +230,9 @@ async fn test_grpc_search_empty_query_returns_empty() -> Result<()> {
         exclude_paths: vec![],
         is_regex: false,
         symbols_only: false,
+        kinds: vec![],
+        definition_only: false,
+        fuzzy: false,
     };
 
     let mut stream = client.search(request).await?.into_inner();
@@ -251,6 +260,9 @@ async fn test_grpc_search_no_match_returns_empty() -> Result<()> {
         exclude_paths: vec![],
         is_regex: false,
         symbols_only: false,
+        kinds: vec![],
+        definition_only: false,
+        fuzzy: false,
     };
 
     let mut stream = client.search(request).await?.into_inner();
@@ -451,6 +463,31 @@ async fn test_http_status_endpoint() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_http_feedback_disabled_by_default() -> Result<()> {
+    let ctx = setup_test_server().await?;
+
+    let client = reqwest::Client::new();
+    let response = client
+        .post(format!("{}/api/feedback", ctx.http_url))
+        .json(&serde_json::json!({
+            "query": "TestStruct",
+            "file_path": "test_file.rs",
+            "rank": 0,
+            "clicked": true
+        }))
+        .send()
+        .await?;
+
+    assert_eq!(
+        response.status(),
+        reqwest::StatusCode::NOT_FOUND,
+        "Feedback must be rejected when the kill switch is off"
+    );
+
+    Ok(())
+}
+
 // =============================================================================
 // Cross-language search tests
 // =============================================================================
@@ -574,6 +611,9 @@ async fn test_grpc_search_symbols_only() -> Result<()> {
         exclude_paths: vec![],
         is_regex: false,
         symbols_only: true,
+        kinds: vec![],
+        definition_only: false,
+        fuzzy: false,
     };
 
     let mut stream = client.search(request).await?.into_inner();
@@ -646,6 +686,9 @@ async fn test_grpc_regex_search() -> Result<()> {
         exclude_paths: vec![],
         is_regex: true,
         symbols_only: false,
+        kinds: vec![],
+        definition_only: false,
+        fuzzy: false,
     };
 
     let mut stream = client.search(request).await?.into_inner();
@@ -699,6 +742,48 @@ async fn test_http_search_with_include_filter() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_http_search_near_file() -> Result<()> {
+    let ctx = setup_test_server().await?;
+
+    let client = reqwest::Client::new();
+
+    // Without imports the neighborhood is the file itself
+    let response = client
+        .get(format!("{}/api/search", ctx.http_url))
+        .query(&[("q", "class"), ("near", "test_file.py"), ("hops", "2")])
+        .send()
+        .await?;
+
+    assert!(response.status().is_success());
+
+    let body: serde_json::Value = response.json().await?;
+    for result in body["results"].as_array().unwrap() {
+        let path = result["file_path"].as_str().unwrap();
+        assert!(path.ends_with("test_file.py"), "Unexpected file: {}", path);
+    }
+
+    let response = client
+        .get(format!("{}/api/search", ctx.http_url))
+        .query(&[("q", "class"), ("near", "missing.py")])
+        .send()
+        .await?;
+    assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+
+    let response = client
+        .get(format!("{}/api/search", ctx.http_url))
+        .query(&[
+            ("q", "class"),
+            ("near", "test_file.py"),
+            ("direction", "up"),
+        ])
+        .send()
+        .await?;
+    assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_http_search_with_exclude_filter() -> Result<()> {
     let ctx = setup_test_server().await?;
@@ -744,6 +829,9 @@ async fn test_grpc_search_with_path_filters() -> Result<()> {
         exclude_paths: vec![],
         is_regex: false,
         symbols_only: false,
+        kinds: vec![],
+        definition_only: false,
+        fuzzy: false,
     };
 
     let mut stream = client.search(request).await?.into_inner();
@@ -811,6 +899,9 @@ async fn test_grpc_search_max_results_limit() -> Result<()> {
         exclude_paths: vec![],
         is_regex: false,
         symbols_only: false,
+        kinds: vec![],
+        definition_only: false,
+        fuzzy: false,
     };
 
     let mut stream = client.search(request).await?.into_inner();
@@ -896,6 +987,168 @@ async fn test_http_dependents_endpoint() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_http_impact_endpoint() -> Result<()> {
+    let ctx = setup_test_server().await?;
+
+    let client = reqwest::Client::new();
+
+    let response = client
+        .get(format!("{}/api/impact", ctx.http_url))
+        .query(&[("file", "test_file.py"), ("depth", "3")])
+        .send()
+        .await?;
+
+    assert!(response.status().is_success());
+
+    let body: serde_json::Value = response.json().await?;
+    assert!(body["layers"].as_array().is_some(), "impact.layers missing");
+    assert!(body["total_affected"].as_u64().is_some());
+    assert!(body["cycles"].as_array().is_some(), "impact.cycles missing");
+    assert_eq!(body["truncated"], false);
+
+    // Unknown direction on the dependency endpoints is a client error
+    let response = client
+        .get(format!("{}/api/dependencies", ctx.http_url))
+        .query(&[("file", "test_file.py"), ("direction", "sideways")])
+        .send()
+        .await?;
+    assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_http_cycles_endpoint() -> Result<()> {
+    let ctx = setup_test_server().await?;
+
+    let client = reqwest::Client::new();
+
+    let response = client
+        .get(format!("{}/api/cycles", ctx.http_url))
+        .query(&[("prefix", "src/"), ("max", "5")])
+        .send()
+        .await?;
+
+    assert!(response.status().is_success());
+
+    let body: serde_json::Value = response.json().await?;
+    assert!(body["cycles"].as_u64().is_some(), "cycles count missing");
+    assert!(body["size_distribution"].as_array().is_some());
+    assert!(body["largest_cycles"].as_array().is_some());
+    assert_eq!(body["truncated"], false);
+
+    let diagnostics: serde_json::Value = client
+        .get(format!("{}/api/diagnostics", ctx.http_url))
+        .send()
+        .await?
+        .json()
+        .await?;
+    assert!(diagnostics["index"]["import_cycles"]["files_in_cycles"]
+        .as_u64()
+        .is_some());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_http_unresolved_endpoint() -> Result<()> {
+    let ctx = setup_test_server().await?;
+
+    let client = reqwest::Client::new();
+
+    let response = client
+        .get(format!("{}/api/unresolved", ctx.http_url))
+        .query(&[("max", "10")])
+        .send()
+        .await?;
+
+    assert!(response.status().is_success());
+
+    let body: serde_json::Value = response.json().await?;
+    assert!(body["total"].as_u64().is_some(), "unresolved total missing");
+    assert!(body["by_language"].as_array().is_some());
+    assert!(body["external_modules"].as_array().is_some());
+    let files = body["unresolved"].as_array().unwrap();
+    assert!(files.len() <= 10);
+    for file in files {
+        for import in file["imports"].as_array().unwrap() {
+            assert!(import["line"].as_u64().unwrap() >= 1);
+        }
+    }
+
+    let diagnostics: serde_json::Value = client
+        .get(format!("{}/api/diagnostics", ctx.http_url))
+        .send()
+        .await?
+        .json()
+        .await?;
+    assert!(diagnostics["index"]["unresolved_imports"]["total"]
+        .as_u64()
+        .is_some());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_http_packages_endpoint_and_filter() -> Result<()> {
+    let ctx = setup_test_server().await?;
+
+    let client = reqwest::Client::new();
+
+    let response = client
+        .get(format!("{}/api/packages", ctx.http_url))
+        .send()
+        .await?;
+
+    assert!(response.status().is_success());
+
+    let body: serde_json::Value = response.json().await?;
+    assert!(body["packages"].as_array().is_some(), "packages missing");
+
+    // The fixture files belong to no package, so a package filter leaves nothing
+    let body: serde_json::Value = client
+        .get(format!("{}/api/search", ctx.http_url))
+        .query(&[("q", "def pkg:no-such-package")])
+        .send()
+        .await?
+        .json()
+        .await?;
+    assert_eq!(body["query"], "def");
+    assert_eq!(body["total_results"], 0);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_http_dead_code_endpoint() -> Result<()> {
+    let ctx = setup_test_server().await?;
+
+    let client = reqwest::Client::new();
+
+    let response = client
+        .get(format!("{}/api/dead-code", ctx.http_url))
+        .query(&[("max", "5")])
+        .send()
+        .await?;
+
+    assert!(response.status().is_success());
+
+    let body: serde_json::Value = response.json().await?;
+    // The fixture files import nothing from each other
+    assert_eq!(body["unused_files"], body["files_checked"]);
+    let directories = body["directories"].as_array().unwrap();
+    assert!(directories.len() <= 5);
+    for directory in directories {
+        for symbol in directory["symbols"].as_array().unwrap() {
+            assert!(symbol["line"].as_u64().unwrap() >= 1);
+        }
+    }
+    assert!(body["truncated"].is_boolean());
+
+    Ok(())
+}
+
 // =============================================================================
 // Diagnostics and monitoring tests
 // =============================================================================
@@ -1506,6 +1759,9 @@ async fn test_super_integration() -> Result<()> {
             exclude_paths: vec![],
             is_regex: false,
             symbols_only: false,
+            kinds: vec![],
+            definition_only: false,
+            fuzzy: false,
         };
         let mut stream = client.search(req).await?.into_inner();
         let mut results = vec![];
@@ -1570,6 +1826,9 @@ async fn test_super_integration() -> Result<()> {
             exclude_paths: vec![],
             is_regex: false,
             symbols_only: true,
+            kinds: vec![],
+            definition_only: false,
+            fuzzy: false,
         };
         let mut stream_sym = client.search(req).await?.into_inner();
         let mut results = vec![];
@@ -1641,6 +1900,9 @@ async fn test_super_integration() -> Result<()> {
             exclude_paths: vec![],
             is_regex: true,
             symbols_only: false,
+            kinds: vec![],
+            definition_only: false,
+            fuzzy: false,
         };
         let mut stream_regex = client.search(req).await?.into_inner();
         let mut results = vec![];
@@ -1721,6 +1983,9 @@ async fn test_super_integration() -> Result<()> {
             exclude_paths: vec![],
             is_regex: false,
             symbols_only: false,
+            kinds: vec![],
+            definition_only: false,
+            fuzzy: false,
         };
         let mut stream_py = client.search(req).await?.into_inner();
         let mut results = vec![];
@@ -1752,6 +2017,9 @@ async fn test_super_integration() -> Result<()> {
             exclude_paths: vec!["*.js".to_string()],
             is_regex: false,
             symbols_only: false,
+            kinds: vec![],
+            definition_only: false,
+            fuzzy: false,
         };
         let mut stream_nojs = client.search(req).await?.into_inner();
         let mut results = vec![];
@@ -1796,6 +2064,9 @@ async fn test_super_integration() -> Result<()> {
             exclude_paths: vec![],
             is_regex: false,
             symbols_only: false,
+            kinds: vec![],
+            definition_only: false,
+            fuzzy: false,
         };
         let mut stream_max = client.search(req).await?.into_inner();
         let mut results = vec![];
@@ -1851,6 +2122,9 @@ async fn test_super_integration() -> Result<()> {
             exclude_paths: vec![],
             is_regex: false,
             symbols_only: false,
+            kinds: vec![],
+            definition_only: false,
+            fuzzy: false,
         };
         let mut stream_empty = client.search(req).await?.into_inner();
         let mut results = vec![];
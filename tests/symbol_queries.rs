@@ -0,0 +1,81 @@
+//! Symbol query overrides, end to end.
+//!
+//! Queries are loaded once per process, so this lives in its own test binary:
+//! `init_queries` must run before anything is parsed.
+
+use fast_code_search::config::IndexerConfig;
+use fast_code_search::search::SearchEngine;
+use fast_code_search::symbols::{init_queries, SymbolExtractor};
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+/// Replaces the built-in JavaScript rules: functions in object literals are
+/// definitions, classes are not
+const JAVASCRIPT_OVERRIDE: &str = r#"(function_declaration name: (_) @name) @definition.function
+(pair
+  key: (property_identifier) @name
+  value: [(arrow_function) (function_expression)]) @definition.function
+"#;
+
+const SOURCE: &str = r#"class Api {}
+
+export const handlers = {
+    fetchUser: (id) => load(id),
+};
+
+function load(id) {}
+"#;
+
+#[test]
+fn test_override_query_files_change_extracted_symbols() {
+    let queries = TempDir::new().unwrap();
+    fs::write(queries.path().join("javascript.scm"), JAVASCRIPT_OVERRIDE).unwrap();
+    init_queries(Some(queries.path())).unwrap();
+    assert!(init_queries(None).is_err(), "queries load only once");
+
+    let names = |file: &str| -> Vec<String> {
+        SymbolExtractor::new(Path::new(file))
+            .extract(SOURCE)
+            .unwrap()
+            .into_iter()
+            .map(|s| s.name)
+            .collect()
+    };
+    assert_eq!(names("api.js"), vec!["fetchUser", "load"]);
+    // TypeScript inherits the JavaScript rules from the override directory
+    // too, and keeps its own additions
+    assert_eq!(names("api.ts"), vec!["fetchUser", "load"]);
+    // Languages without an override keep the built-in rules
+    let rust = SymbolExtractor::new(Path::new("lib.rs"))
+        .extract("struct Api;\n")
+        .unwrap();
+    assert_eq!(rust[0].name, "Api");
+
+    // The indexed symbols come from the override as well
+    let project = TempDir::new().unwrap();
+    let file = project.path().join("api.js");
+    fs::write(&file, SOURCE).unwrap();
+    let mut engine = SearchEngine::new();
+    engine.index_file(&file).unwrap();
+    engine.finalize();
+    let results = engine.search_symbols("fetchUser", "", "", 10).unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].line_number, 4);
+    // The class is no longer a definition (only the file name matches)
+    let results = engine.search_symbols("Api", "", "", 10).unwrap();
+    assert!(results.iter().all(|m| m.line_number != 1), "{results:?}");
+
+    // Editing an override file invalidates indexes built with the old rules
+    let config = IndexerConfig {
+        symbol_queries_dir: Some(queries.path().to_string_lossy().into_owned()),
+        ..IndexerConfig::default()
+    };
+    let fingerprint = config.fingerprint();
+    fs::write(
+        queries.path().join("javascript.scm"),
+        "(class_declaration name: (_) @name) @definition.class\n",
+    )
+    .unwrap();
+    assert_ne!(config.fingerprint(), fingerprint);
+}